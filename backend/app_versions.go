@@ -0,0 +1,318 @@
+package main
+
+import (
+	"encoding/json"
+	"fmt"
+	"io"
+	"net/http"
+	"os"
+	"path/filepath"
+	"sort"
+	"strings"
+	"time"
+
+	"github.com/BurntSushi/toml"
+	"github.com/gin-gonic/gin"
+	"github.com/google/uuid"
+)
+
+// Every upload is kept as a version under apps/.versions/<id>/ as
+// <vid>.tar.gz, <vid>.toml and <vid>.json (metadata). The current version is
+// linked (or copied) to apps/<id>.tar.gz and apps/<id>.toml, which is what
+// clients install from, and its id is recorded in the "current" file.
+const versionsDirName = ".versions"
+
+type AppVersion struct {
+	ID           string `json:"id"`
+	Version      string `json:"version"`
+	UploadedAt   int64  `json:"uploaded_at"`
+	Size         int64  `json:"size"`
+	UploaderID   string `json:"uploader_id"`
+	UploaderName string `json:"uploader_name"`
+	Current      bool   `json:"current"`
+}
+
+func appVersionsDir(id string) string {
+	return filepath.Join(appsDir, versionsDirName, id)
+}
+
+func appVersionPath(id, vid, ext string) string {
+	return filepath.Join(appVersionsDir(id), vid+ext)
+}
+
+func newAppVersionID(now time.Time) string {
+	return now.UTC().Format("20060102-150405") + "-" + uuid.NewString()[:8]
+}
+
+func currentAppVersionID(id string) string {
+	content, err := os.ReadFile(filepath.Join(appVersionsDir(id), "current"))
+	if err != nil {
+		return ""
+	}
+	return strings.TrimSpace(string(content))
+}
+
+func readAppVersion(id, vid string) (AppVersion, error) {
+	var version AppVersion
+	content, err := os.ReadFile(appVersionPath(id, vid, ".json"))
+	if err != nil {
+		return version, fmt.Errorf("version not found")
+	}
+	if err := json.Unmarshal(content, &version); err != nil {
+		return version, fmt.Errorf("version metadata unreadable")
+	}
+	version.ID = vid
+	return version, nil
+}
+
+func writeAppVersion(id string, version AppVersion) error {
+	version.Current = false
+	content, err := json.MarshalIndent(version, "", "  ")
+	if err != nil {
+		return fmt.Errorf("failed to encode version metadata")
+	}
+	if err := os.WriteFile(appVersionPath(id, version.ID, ".json"), content, 0644); err != nil {
+		return fmt.Errorf("failed to save version metadata")
+	}
+	return nil
+}
+
+func configVersionLabel(config []byte) string {
+	var cfg AppConfig
+	if _, err := toml.Decode(string(config), &cfg); err != nil {
+		return ""
+	}
+	return cfg.Version
+}
+
+// ensureAppVersionHistory imports an archive uploaded before version
+// history existed, so the first new upload does not throw it away. Only
+// uploads call it; reads leave such an archive alone and list no versions.
+func ensureAppVersionHistory(id string) error {
+	if currentAppVersionID(id) != "" {
+		return nil
+	}
+	archivePath := filepath.Join(appsDir, id+".tar.gz")
+	stat, err := os.Stat(archivePath)
+	if err != nil {
+		return nil
+	}
+	if err := os.MkdirAll(appVersionsDir(id), 0755); err != nil {
+		return fmt.Errorf("failed to prepare versions dir")
+	}
+
+	config, _ := os.ReadFile(filepath.Join(appsDir, id+".toml"))
+	version := AppVersion{
+		ID:         newAppVersionID(stat.ModTime()),
+		Version:    configVersionLabel(config),
+		UploadedAt: stat.ModTime().Unix(),
+		Size:       stat.Size(),
+	}
+	if err := linkOrCopyFile(archivePath, appVersionPath(id, version.ID, ".tar.gz")); err != nil {
+		return err
+	}
+	if err := os.WriteFile(appVersionPath(id, version.ID, ".toml"), config, 0644); err != nil {
+		return fmt.Errorf("failed to save version config")
+	}
+	if err := writeAppVersion(id, version); err != nil {
+		return err
+	}
+	return setCurrentAppVersion(id, version.ID)
+}
+
+// storeAppVersion moves a fully received archive into the version history
+// and makes it the current version.
+func storeAppVersion(id, archivePath string, config []byte, uploaderID, uploaderName string) (AppVersion, error) {
+	var version AppVersion
+	if err := ensureAppVersionHistory(id); err != nil {
+		return version, err
+	}
+	if err := os.MkdirAll(appVersionsDir(id), 0755); err != nil {
+		return version, fmt.Errorf("failed to prepare versions dir")
+	}
+
+	now := time.Now()
+	version = AppVersion{
+		ID:           newAppVersionID(now),
+		Version:      configVersionLabel(config),
+		UploadedAt:   now.Unix(),
+		UploaderID:   uploaderID,
+		UploaderName: uploaderName,
+	}
+	target := appVersionPath(id, version.ID, ".tar.gz")
+	if err := os.Rename(archivePath, target); err != nil {
+		return version, fmt.Errorf("upload was discarded")
+	}
+	if stat, err := os.Stat(target); err == nil {
+		version.Size = stat.Size()
+	}
+	if err := os.WriteFile(appVersionPath(id, version.ID, ".toml"), config, 0644); err != nil {
+		return version, fmt.Errorf("failed to save config")
+	}
+	if err := writeAppVersion(id, version); err != nil {
+		return version, err
+	}
+	if err := promoteAppVersion(id, version.ID); err != nil {
+		return version, err
+	}
+	version.Current = true
+	return version, nil
+}
+
+func listAppVersions(id string) ([]AppVersion, error) {
+	if !isSafeAppID(id) {
+		return nil, fmt.Errorf("invalid app id")
+	}
+	entries, err := os.ReadDir(appVersionsDir(id))
+	if err != nil {
+		if os.IsNotExist(err) {
+			return []AppVersion{}, nil
+		}
+		return nil, fmt.Errorf("failed to list versions")
+	}
+
+	current := currentAppVersionID(id)
+	versions := []AppVersion{}
+	for _, entry := range entries {
+		if entry.IsDir() || !strings.HasSuffix(entry.Name(), ".json") {
+			continue
+		}
+		version, err := readAppVersion(id, strings.TrimSuffix(entry.Name(), ".json"))
+		if err != nil {
+			continue
+		}
+		version.Current = version.ID == current
+		versions = append(versions, version)
+	}
+	sort.Slice(versions, func(i, j int) bool {
+		return versions[i].UploadedAt > versions[j].UploadedAt
+	})
+	return versions, nil
+}
+
+func setCurrentAppVersion(id, vid string) error {
+	if err := os.WriteFile(filepath.Join(appVersionsDir(id), "current"), []byte(vid), 0644); err != nil {
+		return fmt.Errorf("failed to record current version")
+	}
+	return nil
+}
+
+// promoteAppVersion makes a stored version the one clients install.
+func promoteAppVersion(id, vid string) error {
+	if !isSafeAppID(id) || !isSafeAppID(vid) {
+		return fmt.Errorf("invalid version")
+	}
+	if _, err := readAppVersion(id, vid); err != nil {
+		return err
+	}
+	config, err := os.ReadFile(appVersionPath(id, vid, ".toml"))
+	if err != nil {
+		return fmt.Errorf("version config not found")
+	}
+	if err := linkOrCopyFile(appVersionPath(id, vid, ".tar.gz"), filepath.Join(appsDir, id+".tar.gz")); err != nil {
+		return err
+	}
+	if err := os.WriteFile(filepath.Join(appsDir, id+".toml"), config, 0644); err != nil {
+		return fmt.Errorf("failed to save config")
+	}
+	return setCurrentAppVersion(id, vid)
+}
+
+func deleteAppVersion(id, vid string) error {
+	if !isSafeAppID(id) || !isSafeAppID(vid) {
+		return fmt.Errorf("invalid version")
+	}
+	if _, err := readAppVersion(id, vid); err != nil {
+		return err
+	}
+	if currentAppVersionID(id) == vid {
+		return fmt.Errorf("cannot delete the current version")
+	}
+	for _, ext := range []string{".tar.gz", ".toml", ".json"} {
+		_ = os.Remove(appVersionPath(id, vid, ext))
+	}
+	return nil
+}
+
+// linkOrCopyFile atomically replaces dst with the contents of src, using a
+// hard link where the filesystem allows it so versions don't cost double
+// the disk space.
+func linkOrCopyFile(src, dst string) error {
+	tmp := dst + ".tmp"
+	_ = os.Remove(tmp)
+	if err := os.Link(src, tmp); err != nil {
+		in, err := os.Open(src)
+		if err != nil {
+			return fmt.Errorf("version archive not found")
+		}
+		defer in.Close()
+		out, err := os.Create(tmp)
+		if err != nil {
+			return fmt.Errorf("failed to copy version archive")
+		}
+		if _, err := io.Copy(out, in); err != nil {
+			_ = out.Close()
+			_ = os.Remove(tmp)
+			return fmt.Errorf("failed to copy version archive")
+		}
+		if err := out.Close(); err != nil {
+			_ = os.Remove(tmp)
+			return fmt.Errorf("failed to copy version archive")
+		}
+	}
+	if err := os.Rename(tmp, dst); err != nil {
+		_ = os.Remove(tmp)
+		return fmt.Errorf("failed to replace archive")
+	}
+	return nil
+}
+
+func listAppVersionsHandler(c *gin.Context) {
+	versions, err := listAppVersions(c.Param("id"))
+	if err != nil {
+		c.JSON(http.StatusBadRequest, gin.H{"error": err.Error()})
+		return
+	}
+	c.JSON(http.StatusOK, versions)
+}
+
+func getAppVersionConfigHandler(c *gin.Context) {
+	id := c.Param("id")
+	vid := c.Param("vid")
+	if !isSafeAppID(id) || !isSafeAppID(vid) {
+		c.JSON(http.StatusBadRequest, gin.H{"error": "invalid version"})
+		return
+	}
+	content, err := os.ReadFile(appVersionPath(id, vid, ".toml"))
+	if err != nil {
+		c.JSON(http.StatusNotFound, gin.H{"error": "config not found"})
+		return
+	}
+	c.Data(http.StatusOK, "text/plain; charset=utf-8", content)
+}
+
+func getAppVersionArchiveHandler(c *gin.Context) {
+	id := c.Param("id")
+	vid := c.Param("vid")
+	if !isSafeAppID(id) || !isSafeAppID(vid) {
+		c.JSON(http.StatusBadRequest, gin.H{"error": "invalid version"})
+		return
+	}
+	file, err := os.Open(appVersionPath(id, vid, ".tar.gz"))
+	if err != nil {
+		c.JSON(http.StatusNotFound, gin.H{"error": "archive not found"})
+		return
+	}
+	defer file.Close()
+
+	stat, err := file.Stat()
+	if err != nil {
+		c.JSON(http.StatusInternalServerError, gin.H{"error": "archive not readable"})
+		return
+	}
+
+	name := id + "-" + vid + ".tar.gz"
+	c.Header("Content-Disposition", "attachment; filename=\""+name+"\"")
+	c.Header("Content-Type", "application/gzip")
+	http.ServeContent(c.Writer, c.Request, name, stat.ModTime(), file)
+}
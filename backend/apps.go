@@ -13,23 +13,41 @@ import (
 	"github.com/gin-gonic/gin"
 )
 
-const appsDir = "./apps"
+var appsDir = "./apps"
 
 type AppConfig struct {
-	Name        string `toml:"name" json:"name"`
-	Description string `toml:"description" json:"description"`
-	Version     string `toml:"version" json:"version"`
-	Executable  string `toml:"executable" json:"executable"`
+	Name         string          `toml:"name" json:"name"`
+	Description  string          `toml:"description" json:"description"`
+	Version      string          `toml:"version" json:"version"`
+	Executable   string          `toml:"executable" json:"executable"`
+	TrackProcess string          `toml:"track_process" json:"track_process,omitempty"`
+	Launch       []LaunchProfile `toml:"launch" json:"launch,omitempty"`
+	// BaseApp marks the package as a mod for another app; its files are
+	// layered over that app's content instead of being launched.
+	BaseApp string `toml:"base_app" json:"base_app,omitempty"`
+}
+
+// LaunchProfile is one [[launch]] entry of an app config. Paths are relative
+// to the app's content folder; the client resolves and validates them.
+type LaunchProfile struct {
+	Name         string            `toml:"name" json:"name"`
+	Executable   string            `toml:"executable" json:"executable"`
+	Args         []string          `toml:"args" json:"args,omitempty"`
+	Env          map[string]string `toml:"env" json:"env,omitempty"`
+	WorkingDir   string            `toml:"working_dir" json:"working_dir,omitempty"`
+	TrackProcess string            `toml:"track_process" json:"track_process,omitempty"`
 }
 
 type AppInfo struct {
-	ID          string `json:"id"`
-	Name        string `json:"name"`
-	Description string `json:"description"`
-	Version     string `json:"version"`
-	ArchiveSize int64  `json:"archive_size"`
-	HasArchive  bool   `json:"has_archive"`
-	Executable  string `json:"executable,omitempty"`
+	ID             string          `json:"id"`
+	Name           string          `json:"name"`
+	Description    string          `json:"description"`
+	Version        string          `json:"version"`
+	ArchiveSize    int64           `json:"archive_size"`
+	HasArchive     bool            `json:"has_archive"`
+	Executable     string          `json:"executable,omitempty"`
+	LaunchProfiles []LaunchProfile `json:"launch_profiles,omitempty"`
+	BaseApp        string          `json:"base_app,omitempty"`
 }
 
 func listAppsHandler(c *gin.Context) {
@@ -118,21 +136,42 @@ func listApps() ([]AppInfo, error) {
 		if name == "" {
 			name = id
 		}
+		baseApp := strings.TrimSpace(cfg.BaseApp)
+		if !isSafeAppID(baseApp) || baseApp == id {
+			baseApp = ""
+		}
 
 		apps = append(apps, AppInfo{
-			ID:          id,
-			Name:        name,
-			Description: cfg.Description,
-			Version:     cfg.Version,
-			ArchiveSize: size,
-			HasArchive:  hasArchive,
-			Executable:  cfg.Executable,
+			ID:             id,
+			Name:           name,
+			Description:    cfg.Description,
+			Version:        cfg.Version,
+			ArchiveSize:    size,
+			HasArchive:     hasArchive,
+			Executable:     cfg.Executable,
+			LaunchProfiles: launchProfiles(cfg.Launch),
+			BaseApp:        baseApp,
 		})
 	}
 
 	return apps, nil
 }
 
+// launchProfiles drops profiles that can't be launched, so clients only
+// show entries that have both a name and an executable.
+func launchProfiles(profiles []LaunchProfile) []LaunchProfile {
+	valid := []LaunchProfile{}
+	for _, profile := range profiles {
+		profile.Name = strings.TrimSpace(profile.Name)
+		profile.Executable = strings.TrimSpace(profile.Executable)
+		if profile.Name == "" || profile.Executable == "" {
+			continue
+		}
+		valid = append(valid, profile)
+	}
+	return valid
+}
+
 func isSafeAppID(id string) bool {
 	if id == "" {
 		return false
@@ -146,7 +185,7 @@ func isSafeAppID(id string) bool {
 	return true
 }
 
-func uploadAppHandler(c *gin.Context) error {
+func uploadAppHandler(c *gin.Context, store *Store) error {
 	id := strings.TrimSpace(c.Param("id"))
 	if !isSafeAppID(id) {
 		return fmt.Errorf("invalid app id")
@@ -166,26 +205,54 @@ func uploadAppHandler(c *gin.Context) error {
 		return fmt.Errorf("failed to prepare apps dir")
 	}
 
-	configPath := filepath.Join(appsDir, id+".toml")
-	if err := os.WriteFile(configPath, configBytes, 0644); err != nil {
-		return fmt.Errorf("failed to save config")
-	}
-
-	archivePath := filepath.Join(appsDir, id+".tar.gz")
-	dst, err := os.Create(archivePath)
+	// Stream into a partial file first so an aborted upload never replaces
+	// the archive or config that clients are currently installing from.
+	partialPath := partialUploadPath(id)
+	dst, err := os.Create(partialPath)
 	if err != nil {
 		return fmt.Errorf("failed to create archive file")
 	}
-	defer dst.Close()
 
 	// TRUE streaming copy
 	if _, err := io.Copy(dst, c.Request.Body); err != nil {
+		_ = dst.Close()
+		_ = os.Remove(partialPath)
 		return fmt.Errorf("failed to stream archive")
 	}
+	if err := dst.Close(); err != nil {
+		_ = os.Remove(partialPath)
+		return fmt.Errorf("failed to finalize archive")
+	}
+
+	uploaderID, _ := getUserID(c)
+	uploaderName := ""
+	if user, err := store.GetUser(uploaderID); err == nil {
+		uploaderName = user.Username
+	}
+	if _, err := storeAppVersion(id, partialPath, configBytes, uploaderID, uploaderName); err != nil {
+		_ = os.Remove(partialPath)
+		return err
+	}
 
 	return nil
 }
 
+func partialUploadPath(id string) string {
+	return filepath.Join(appsDir, id+".tar.gz.part")
+}
+
+// discardAppUpload removes the partial archive of an upload the client
+// cancelled. It is a no-op when nothing is pending.
+func discardAppUpload(id string) error {
+	if !isSafeAppID(id) {
+		return fmt.Errorf("invalid app id")
+	}
+	if err := os.Remove(partialUploadPath(id)); err != nil && !os.IsNotExist(err) {
+		return fmt.Errorf("failed to discard upload")
+	}
+	return nil
+}
+
 func readAppConfig(id string) (string, error) {
 	if !isSafeAppID(id) {
 		return "", fmt.Errorf("invalid app id")
@@ -209,6 +276,11 @@ func writeAppConfig(id string, content string) error {
 	if err := os.WriteFile(path, []byte(content), 0644); err != nil {
 		return fmt.Errorf("failed to save config")
 	}
+	// Keep the current version's copy in sync so a later rollback and
+	// re-promote doesn't resurrect the old metadata.
+	if vid := currentAppVersionID(id); vid != "" {
+		_ = os.WriteFile(appVersionPath(id, vid, ".toml"), []byte(content), 0644)
+	}
 	return nil
 }
 
@@ -220,5 +292,6 @@ func deleteApp(id string) error {
 	archivePath := filepath.Join(appsDir, id+".tar.gz")
 	_ = os.Remove(tomlPath)
 	_ = os.Remove(archivePath)
+	_ = os.RemoveAll(appVersionsDir(id))
 	return nil
 }
@@ -0,0 +1,251 @@
+package main
+
+import (
+	"bytes"
+	"encoding/base64"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"os"
+	"path/filepath"
+	"testing"
+)
+
+func TestDiscardUploadRequiresAdmin(t *testing.T) {
+	store := newTestStore(t)
+	router := newTestRouter(store)
+	token := newTestSession(t, store, "u1")
+
+	req := httptest.NewRequest(http.MethodDelete, "/admin/apps/upload/test-app", nil)
+	req.Header.Set("Authorization", "Bearer "+token)
+	rec := httptest.NewRecorder()
+	router.ServeHTTP(rec, req)
+	if rec.Code != http.StatusForbidden {
+		t.Fatalf("expected 403, got %d", rec.Code)
+	}
+}
+
+func TestDiscardUploadRemovesPartial(t *testing.T) {
+	store := newTestStore(t)
+	router := newTestRouterWithConfig(store, &Config{Admins: []string{"admin"}})
+	token := newTestSession(t, store, "admin")
+
+	useTempAppsDir(t)
+
+	partial := partialUploadPath("discard-test-app")
+	if err := os.WriteFile(partial, []byte("partial"), 0644); err != nil {
+		t.Fatalf("failed to write partial upload: %v", err)
+	}
+
+	for _, expect := range []int{http.StatusOK, http.StatusOK} {
+		req := httptest.NewRequest(http.MethodDelete, "/admin/apps/upload/discard-test-app", nil)
+		req.Header.Set("Authorization", "Bearer "+token)
+		rec := httptest.NewRecorder()
+		router.ServeHTTP(rec, req)
+		if rec.Code != expect {
+			t.Fatalf("expected %d, got %d", expect, rec.Code)
+		}
+	}
+	if _, err := os.Stat(partial); !os.IsNotExist(err) {
+		t.Fatalf("expected partial upload to be removed")
+	}
+}
+
+func uploadTestApp(t *testing.T, router http.Handler, token, id, config, archive string) {
+	t.Helper()
+
+	req := httptest.NewRequest(http.MethodPut, "/admin/apps/upload/"+id, bytes.NewBufferString(archive))
+	req.Header.Set("Authorization", "Bearer "+token)
+	req.Header.Set("X-App-Config", base64.StdEncoding.EncodeToString([]byte(config)))
+	rec := httptest.NewRecorder()
+	router.ServeHTTP(rec, req)
+	if rec.Code != http.StatusOK {
+		t.Fatalf("upload failed with %d: %s", rec.Code, rec.Body.String())
+	}
+}
+
+func listTestAppVersions(t *testing.T, router http.Handler, token, id string) []AppVersion {
+	t.Helper()
+
+	req := httptest.NewRequest(http.MethodGet, "/apps/"+id+"/versions", nil)
+	req.Header.Set("Authorization", "Bearer "+token)
+	rec := httptest.NewRecorder()
+	router.ServeHTTP(rec, req)
+	if rec.Code != http.StatusOK {
+		t.Fatalf("expected 200 listing versions, got %d", rec.Code)
+	}
+	var versions []AppVersion
+	if err := json.Unmarshal(rec.Body.Bytes(), &versions); err != nil {
+		t.Fatalf("invalid versions payload: %v", err)
+	}
+	return versions
+}
+
+func TestAppVersionsPromoteAndDelete(t *testing.T) {
+	store := newTestStore(t)
+	router := newTestRouterWithConfig(store, &Config{Admins: []string{"admin"}})
+	token := newTestSession(t, store, "admin")
+	useTempAppsDir(t)
+
+	uploadTestApp(t, router, token, "demo", "name = \"Demo\"\nversion = \"1.0\"\n", "first")
+	uploadTestApp(t, router, token, "demo", "name = \"Demo\"\nversion = \"2.0\"\n", "second build")
+
+	versions := listTestAppVersions(t, router, token, "demo")
+	if len(versions) != 2 {
+		t.Fatalf("expected 2 versions, got %d", len(versions))
+	}
+	var older AppVersion
+	for _, v := range versions {
+		if v.Version == "1.0" {
+			older = v
+		}
+		if v.UploaderName != "admin" {
+			t.Fatalf("expected uploader to be recorded, got %q", v.UploaderName)
+		}
+	}
+	if older.ID == "" || older.Current {
+		t.Fatalf("expected 1.0 to be stored as a non-current version")
+	}
+
+	req := httptest.NewRequest(http.MethodPost, "/admin/apps/demo/versions/"+older.ID+"/promote", nil)
+	req.Header.Set("Authorization", "Bearer "+token)
+	rec := httptest.NewRecorder()
+	router.ServeHTTP(rec, req)
+	if rec.Code != http.StatusOK {
+		t.Fatalf("expected 200 promoting, got %d", rec.Code)
+	}
+	archive, err := os.ReadFile(filepath.Join(appsDir, "demo.tar.gz"))
+	if err != nil || string(archive) != "first" {
+		t.Fatalf("expected promoted archive to be current, got %q (%v)", archive, err)
+	}
+
+	req = httptest.NewRequest(http.MethodDelete, "/admin/apps/demo/versions/"+older.ID, nil)
+	req.Header.Set("Authorization", "Bearer "+token)
+	rec = httptest.NewRecorder()
+	router.ServeHTTP(rec, req)
+	if rec.Code != http.StatusBadRequest {
+		t.Fatalf("expected current version delete to be rejected, got %d", rec.Code)
+	}
+
+	for _, v := range versions {
+		if v.ID == older.ID {
+			continue
+		}
+		req = httptest.NewRequest(http.MethodDelete, "/admin/apps/demo/versions/"+v.ID, nil)
+		req.Header.Set("Authorization", "Bearer "+token)
+		rec = httptest.NewRecorder()
+		router.ServeHTTP(rec, req)
+		if rec.Code != http.StatusOK {
+			t.Fatalf("expected 200 deleting old version, got %d", rec.Code)
+		}
+	}
+	if got := listTestAppVersions(t, router, token, "demo"); len(got) != 1 {
+		t.Fatalf("expected 1 version left, got %d", len(got))
+	}
+}
+
+func TestListAppVersionsDoesNotWrite(t *testing.T) {
+	store := newTestStore(t)
+	router := newTestRouter(store)
+	token := newTestSession(t, store, "u1")
+	useTempAppsDir(t)
+
+	// An app uploaded before version history existed.
+	if err := os.WriteFile(filepath.Join(appsDir, "legacy.tar.gz"), []byte("old"), 0644); err != nil {
+		t.Fatalf("failed to write archive: %v", err)
+	}
+	if err := os.WriteFile(filepath.Join(appsDir, "legacy.toml"), []byte("name = \"Legacy\"\n"), 0644); err != nil {
+		t.Fatalf("failed to write config: %v", err)
+	}
+
+	if got := listTestAppVersions(t, router, token, "legacy"); len(got) != 0 {
+		t.Fatalf("expected no versions, got %d", len(got))
+	}
+	if _, err := os.Stat(appVersionsDir("legacy")); !os.IsNotExist(err) {
+		t.Fatalf("expected listing to leave the version history alone")
+	}
+}
+
+func TestListAppsIncludesLaunchProfiles(t *testing.T) {
+	store := newTestStore(t)
+	router := newTestRouterWithConfig(store, &Config{Admins: []string{"admin"}})
+	token := newTestSession(t, store, "admin")
+	useTempAppsDir(t)
+
+	config := `name = "Demo"
+executable = "game.exe"
+
+[[launch]]
+name = "Windowed"
+executable = "game.exe"
+args = ["-windowed", "-w", "1280"]
+env = { DEMO_MODE = "1" }
+
+[[launch]]
+name = "Dedicated server"
+executable = "server/server.exe"
+working_dir = "server"
+
+[[launch]]
+name = "Broken"
+`
+	uploadTestApp(t, router, token, "demo", config, "archive")
+
+	req := httptest.NewRequest(http.MethodGet, "/apps", nil)
+	req.Header.Set("Authorization", "Bearer "+token)
+	rec := httptest.NewRecorder()
+	router.ServeHTTP(rec, req)
+	if rec.Code != http.StatusOK {
+		t.Fatalf("expected 200, got %d", rec.Code)
+	}
+	var apps []AppInfo
+	if err := json.Unmarshal(rec.Body.Bytes(), &apps); err != nil {
+		t.Fatalf("invalid apps payload: %v", err)
+	}
+	if len(apps) != 1 {
+		t.Fatalf("expected 1 app, got %d", len(apps))
+	}
+	profiles := apps[0].LaunchProfiles
+	if len(profiles) != 2 {
+		t.Fatalf("expected 2 valid launch profiles, got %d", len(profiles))
+	}
+	if profiles[0].Name != "Windowed" || len(profiles[0].Args) != 3 || profiles[0].Env["DEMO_MODE"] != "1" {
+		t.Fatalf("unexpected first profile: %+v", profiles[0])
+	}
+	if profiles[1].WorkingDir != "server" {
+		t.Fatalf("expected working dir to be kept, got %q", profiles[1].WorkingDir)
+	}
+}
+
+func TestListAppsMarksModPackages(t *testing.T) {
+	store := newTestStore(t)
+	router := newTestRouterWithConfig(store, &Config{Admins: []string{"admin"}})
+	token := newTestSession(t, store, "admin")
+	useTempAppsDir(t)
+
+	uploadTestApp(t, router, token, "demo", "name = \"Demo\"\nexecutable = \"game.exe\"\n", "archive")
+	uploadTestApp(t, router, token, "demo-hd", "name = \"HD textures\"\nbase_app = \"demo\"\n", "archive")
+	uploadTestApp(t, router, token, "loop", "name = \"Loop\"\nbase_app = \"loop\"\n", "archive")
+
+	req := httptest.NewRequest(http.MethodGet, "/apps", nil)
+	req.Header.Set("Authorization", "Bearer "+token)
+	rec := httptest.NewRecorder()
+	router.ServeHTTP(rec, req)
+	if rec.Code != http.StatusOK {
+		t.Fatalf("expected 200, got %d", rec.Code)
+	}
+	var apps []AppInfo
+	if err := json.Unmarshal(rec.Body.Bytes(), &apps); err != nil {
+		t.Fatalf("invalid apps payload: %v", err)
+	}
+	bases := map[string]string{}
+	for _, app := range apps {
+		bases[app.ID] = app.BaseApp
+	}
+	if len(bases) != 3 || bases["demo"] != "" || bases["demo-hd"] != "demo" {
+		t.Fatalf("unexpected base apps: %v", bases)
+	}
+	if bases["loop"] != "" {
+		t.Fatalf("expected a package can't be a mod of itself, got %q", bases["loop"])
+	}
+}
@@ -1,120 +1,93 @@
 package main
 
 import (
-	"context"
+	"crypto/sha256"
+	"crypto/subtle"
+	"encoding/base64"
 	"encoding/json"
 	"net/http"
 	"sync"
-	"strings"
 	"time"
 
 	"github.com/dgraph-io/badger"
 	"github.com/gin-gonic/gin"
 	"github.com/google/uuid"
-	"golang.org/x/oauth2"
+	"github.com/gorilla/websocket"
 )
 
-var discordOAuthConfig *oauth2.Config
 var loginChallenges = newLoginChallengeStore()
+var loginCodes = newLoginChallengeStore()
 
 const loginChallengeTTL = 10 * time.Minute
 
-func InitDiscordOAuth(cfg *Config) {
-	discordOAuthConfig = &oauth2.Config{
-		ClientID:     cfg.Discord.ClientID,
-		ClientSecret: cfg.Discord.ClientSecret,
-		RedirectURL:  cfg.Discord.RedirectURI,
-		Scopes:       cfg.Discord.Scopes,
-		Endpoint: oauth2.Endpoint{
-			AuthURL:  "https://discord.com/api/oauth2/authorize",
-			TokenURL: "https://discord.com/api/oauth2/token",
-		},
-	}
-}
-
-func DiscordLoginHandler(cfg *Config) gin.HandlerFunc {
-	return func(c *gin.Context) {
-		redirect := c.Query("redirect")
-		if redirect == "" {
-			c.JSON(400, gin.H{"error": "missing redirect"})
-			return
-		}
+// wsCloseUnauthorized is the close code for a WebSocket whose auth frame
+// was missing or carried an invalid session, so clients can tell it apart
+// from a dropped connection and ask the user to log in again.
+const wsCloseUnauthorized = 4401
 
-		if cfg.LoginPasswordRequired() {
-			password := c.Query("password")
-			if password == "" || password != cfg.LoginPassword() {
-				c.JSON(http.StatusUnauthorized, gin.H{"error": "invalid access password"})
-				return
-			}
-		}
+const wsAuthTimeout = 10 * time.Second
 
-		nonce := loginChallenges.Create(loginChallengeTTL)
-		state := nonce + "|" + redirect
-		url := discordOAuthConfig.AuthCodeURL(state)
-		c.Redirect(http.StatusTemporaryRedirect, url)
-	}
-}
+// loginCodeTTL only has to cover the client exchanging the code right after
+// the redirect.
+const loginCodeTTL = time.Minute
 
-func DiscordCallbackHandler(store *Store, cfg *Config) gin.HandlerFunc {
+// TokenHandler exchanges the one-time code from the login redirect for a
+// session. The code only works together with the verifier whose challenge
+// started the login, so a code leaked through history or logs is useless.
+func TokenHandler(store *Store, cfg *Config) gin.HandlerFunc {
 	return func(c *gin.Context) {
-
-		code := c.Query("code")
-		state := c.Query("state")
-
-		if code == "" || state == "" {
-			c.JSON(400, gin.H{"error": "missing code/state"})
-			return
+		var body struct {
+			Code         string `json:"code"`
+			CodeVerifier string `json:"code_verifier"`
 		}
-
-		// Extract redirect from state
-		parts := strings.SplitN(state, "|", 2)
-		if len(parts) != 2 {
-			c.JSON(400, gin.H{"error": "invalid state"})
+		if err := c.ShouldBindJSON(&body); err != nil || body.Code == "" || body.CodeVerifier == "" {
+			c.JSON(http.StatusBadRequest, gin.H{"error": "missing code or verifier"})
 			return
 		}
-		nonce := parts[0]
-		redirect := parts[1]
-		if !loginChallenges.Consume(nonce) {
-			c.JSON(http.StatusUnauthorized, gin.H{"error": "invalid login request"})
-			return
-		}
-
-		token, err := discordOAuthConfig.Exchange(context.Background(), code)
-		if err != nil {
-			c.JSON(500, gin.H{"error": "token exchange failed"})
-			return
-		}
-
-		client := discordOAuthConfig.Client(context.Background(), token)
 
-		resp, err := client.Get("https://discord.com/api/users/@me")
-		if err != nil {
-			c.JSON(500, gin.H{"error": "discord fetch failed"})
+		challenge, ok := loginCodes.Consume(body.Code)
+		if !ok || !verifyCodeChallenge(body.CodeVerifier, challenge.codeChallenge) {
+			c.JSON(http.StatusUnauthorized, gin.H{"error": "invalid login code"})
 			return
 		}
-		defer resp.Body.Close()
 
-		var discordUser struct {
-			ID       string `json:"id"`
-			Username string `json:"username"`
-		}
-
-		json.NewDecoder(resp.Body).Decode(&discordUser)
-
-		store.UpsertUser(User{
-			ID:       discordUser.ID,
-			Username: discordUser.Username,
-		})
+		issueSession(c, store, cfg, challenge.userID)
+	}
+}
 
-		sessionToken, err := store.CreateSession(discordUser.ID, cfg.SessionTTL())
-		if err != nil {
-			c.JSON(500, gin.H{"error": "session create failed"})
-			return
-		}
+func verifyCodeChallenge(verifier string, challenge string) bool {
+	if len(verifier) < 43 || len(verifier) > 128 {
+		return false
+	}
+	sum := sha256.Sum256([]byte(verifier))
+	expected := base64.RawURLEncoding.EncodeToString(sum[:])
+	return subtle.ConstantTimeCompare([]byte(expected), []byte(challenge)) == 1
+}
 
-		// Redirect browser → gui application
-		c.Redirect(302, redirect+"?token="+sessionToken)
+// issueSession creates a session and refresh token pair for userID and
+// writes them as the response.
+func issueSession(c *gin.Context, store *Store, cfg *Config, userID string) {
+	sessionToken, err := store.CreateSession(userID, cfg.SessionTTL())
+	if err != nil {
+		c.JSON(http.StatusInternalServerError, gin.H{"error": "session create failed"})
+		return
 	}
+	refreshToken, err := store.CreateRefreshToken(userID, cfg.RefreshTTL())
+	if err != nil {
+		c.JSON(http.StatusInternalServerError, gin.H{"error": "session create failed"})
+		return
+	}
+	expiresAt, err := store.SessionExpiry(sessionToken)
+	if err != nil {
+		c.JSON(http.StatusInternalServerError, gin.H{"error": "session create failed"})
+		return
+	}
+
+	c.JSON(http.StatusOK, gin.H{
+		"token":         sessionToken,
+		"refresh_token": refreshToken,
+		"expires_at":    expiresAt,
+	})
 }
 
 func AuthMiddleware(store *Store) gin.HandlerFunc {
@@ -137,6 +110,37 @@ func AuthMiddleware(store *Store) gin.HandlerFunc {
 	}
 }
 
+// authenticateWs reads the first frame of a freshly upgraded socket, which
+// must be {"type":"auth","token":"<session token>"}, and returns the user
+// it belongs to. The token is sent in a frame rather than the URL so it
+// stays out of access logs. On failure the socket is closed with
+// wsCloseUnauthorized.
+func (s *Store) authenticateWs(conn *websocket.Conn) (string, bool) {
+	_ = conn.SetReadDeadline(time.Now().Add(wsAuthTimeout))
+
+	var msg struct {
+		Type  string `json:"type"`
+		Token string `json:"token"`
+	}
+	userID := ""
+	_, data, err := conn.ReadMessage()
+	if err == nil {
+		err = json.Unmarshal(data, &msg)
+	}
+	if err == nil && msg.Type == "auth" && msg.Token != "" {
+		userID, err = s.GetUserFromSession(msg.Token)
+	}
+	if err != nil || userID == "" {
+		closeMsg := websocket.FormatCloseMessage(wsCloseUnauthorized, "invalid session")
+		_ = conn.WriteControl(websocket.CloseMessage, closeMsg, time.Now().Add(time.Second))
+		_ = conn.Close()
+		return "", false
+	}
+
+	_ = conn.SetReadDeadline(time.Time{})
+	return userID, true
+}
+
 func MeHandler(store *Store, cfg *Config) gin.HandlerFunc {
 	return func(c *gin.Context) {
 		userID, ok := getUserID(c)
@@ -165,10 +169,63 @@ func LogoutHandler(store *Store) gin.HandlerFunc {
 			c.JSON(http.StatusInternalServerError, gin.H{"error": "logout failed"})
 			return
 		}
+		// Clients that renew their session also send the refresh token, so
+		// it can't be used to log back in.
+		var body struct {
+			RefreshToken string `json:"refresh_token"`
+		}
+		if err := c.ShouldBindJSON(&body); err == nil && body.RefreshToken != "" {
+			if err := store.DeleteRefreshToken(body.RefreshToken); err != nil {
+				c.JSON(http.StatusInternalServerError, gin.H{"error": "logout failed"})
+				return
+			}
+		}
 		c.JSON(http.StatusOK, gin.H{"status": "logged out"})
 	}
 }
 
+// SessionHandler reports when the caller's session expires, so the client
+// can renew it ahead of time. expires_at is a unix timestamp, 0 if the
+// session never expires.
+func SessionHandler(store *Store) gin.HandlerFunc {
+	return func(c *gin.Context) {
+		token, ok := getBearerToken(c)
+		if !ok {
+			return
+		}
+		expiresAt, err := store.SessionExpiry(token)
+		if err != nil {
+			c.JSON(http.StatusUnauthorized, gin.H{"error": "invalid session"})
+			return
+		}
+		c.JSON(http.StatusOK, gin.H{"expires_at": expiresAt})
+	}
+}
+
+// RefreshHandler trades a refresh token for a new session. The refresh
+// token is single use: a new one is issued with every renewal. The old
+// session is left to expire on its own, so requests already using it, such
+// as running downloads, keep working.
+func RefreshHandler(store *Store, cfg *Config) gin.HandlerFunc {
+	return func(c *gin.Context) {
+		var body struct {
+			RefreshToken string `json:"refresh_token"`
+		}
+		if err := c.ShouldBindJSON(&body); err != nil || body.RefreshToken == "" {
+			c.JSON(http.StatusBadRequest, gin.H{"error": "missing refresh token"})
+			return
+		}
+
+		userID, err := store.ConsumeRefreshToken(body.RefreshToken)
+		if err != nil {
+			c.JSON(http.StatusUnauthorized, gin.H{"error": "invalid refresh token"})
+			return
+		}
+
+		issueSession(c, store, cfg, userID)
+	}
+}
+
 func getBearerToken(c *gin.Context) (string, bool) {
 	authHeader := c.GetHeader("Authorization")
 	if authHeader == "" {
@@ -266,48 +323,117 @@ func (s *Store) DeleteSession(token string) error {
 	})
 }
 
+// SessionExpiry returns when a session expires as a unix timestamp, 0 if
+// it never does.
+func (s *Store) SessionExpiry(token string) (uint64, error) {
+	var expiresAt uint64
+
+	err := s.db.View(func(txn *badger.Txn) error {
+		item, err := txn.Get([]byte("session:" + token))
+		if err != nil {
+			return err
+		}
+		expiresAt = item.ExpiresAt()
+		return nil
+	})
+
+	return expiresAt, err
+}
+
+func (s *Store) CreateRefreshToken(userID string, ttl time.Duration) (string, error) {
+	token := uuid.NewString()
+
+	err := s.db.Update(func(txn *badger.Txn) error {
+		entry := badger.NewEntry([]byte("refresh:"+token), []byte(userID))
+		if ttl > 0 {
+			entry = entry.WithTTL(ttl)
+		}
+		return txn.SetEntry(entry)
+	})
+
+	return token, err
+}
+
+// ConsumeRefreshToken looks up and deletes a refresh token in one
+// transaction, so each token renews a session at most once.
+func (s *Store) ConsumeRefreshToken(token string) (string, error) {
+	var userID string
+
+	err := s.db.Update(func(txn *badger.Txn) error {
+		key := []byte("refresh:" + token)
+		item, err := txn.Get(key)
+		if err != nil {
+			return err
+		}
+		if err := item.Value(func(val []byte) error {
+			userID = string(val)
+			return nil
+		}); err != nil {
+			return err
+		}
+		return txn.Delete(key)
+	})
+
+	return userID, err
+}
+
+func (s *Store) DeleteRefreshToken(token string) error {
+	return s.db.Update(func(txn *badger.Txn) error {
+		return txn.Delete([]byte("refresh:" + token))
+	})
+}
+
+type loginChallenge struct {
+	provider      string
+	codeChallenge string
+	userID        string
+	expiry        time.Time
+}
+
+// loginChallengeStore holds single-use login state: the OAuth state nonce
+// while the user is at Discord, then the code handed back to the client.
 type loginChallengeStore struct {
 	mu    sync.Mutex
-	items map[string]time.Time
+	items map[string]loginChallenge
 }
 
 func newLoginChallengeStore() *loginChallengeStore {
 	return &loginChallengeStore{
-		items: make(map[string]time.Time),
+		items: make(map[string]loginChallenge),
 	}
 }
 
-func (s *loginChallengeStore) Create(ttl time.Duration) string {
+func (s *loginChallengeStore) Create(ttl time.Duration, challenge loginChallenge) string {
 	nonce := uuid.NewString()
-	expiry := time.Now().Add(ttl)
+	challenge.expiry = time.Now().Add(ttl)
 	s.mu.Lock()
 	defer s.mu.Unlock()
 	s.cleanupLocked()
-	s.items[nonce] = expiry
+	s.items[nonce] = challenge
 	return nonce
 }
 
-func (s *loginChallengeStore) Consume(nonce string) bool {
+func (s *loginChallengeStore) Consume(nonce string) (loginChallenge, bool) {
 	if nonce == "" {
-		return false
+		return loginChallenge{}, false
 	}
 	now := time.Now()
 	s.mu.Lock()
 	defer s.mu.Unlock()
-	expiry, ok := s.items[nonce]
+	challenge, ok := s.items[nonce]
 	if !ok {
 		s.cleanupLocked()
-		return false
+		return loginChallenge{}, false
 	}
 	delete(s.items, nonce)
 	s.cleanupLocked()
-	return expiry.After(now)
+	return challenge, challenge.expiry.After(now)
 }
 
 func (s *loginChallengeStore) cleanupLocked() {
 	now := time.Now()
-	for key, expiry := range s.items {
-		if !expiry.After(now) {
+	for key, challenge := range s.items {
+		if !challenge.expiry.After(now) {
 			delete(s.items, key)
 		}
 	}
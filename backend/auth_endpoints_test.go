@@ -1,10 +1,18 @@
 package main
 
 import (
+	"bytes"
+	"crypto/sha256"
+	"encoding/base64"
+	"encoding/json"
 	"net/http"
 	"net/http/httptest"
 	"os"
+	"strings"
 	"testing"
+	"time"
+
+	"github.com/gorilla/websocket"
 )
 
 func TestAppEndpointsRequireAuth(t *testing.T) {
@@ -72,6 +80,297 @@ func TestAppEndpointsAllowAuth(t *testing.T) {
 	}
 }
 
+func TestSessionEndpointReportsExpiry(t *testing.T) {
+	store := newTestStore(t)
+	router := newTestRouter(store)
+
+	if err := store.UpsertUser(User{ID: "u1", Username: "tester"}); err != nil {
+		t.Fatalf("failed to upsert user: %v", err)
+	}
+	token, err := store.CreateSession("u1", time.Hour)
+	if err != nil {
+		t.Fatalf("failed to create session: %v", err)
+	}
+
+	req := httptest.NewRequest(http.MethodGet, "/auth/session", nil)
+	req.Header.Set("Authorization", "Bearer "+token)
+	rec := httptest.NewRecorder()
+	router.ServeHTTP(rec, req)
+	if rec.Code != http.StatusOK {
+		t.Fatalf("expected 200, got %d", rec.Code)
+	}
+	var resp struct {
+		ExpiresAt int64 `json:"expires_at"`
+	}
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatalf("failed to decode response: %v", err)
+	}
+	remaining := time.Until(time.Unix(resp.ExpiresAt, 0))
+	if remaining <= 0 || remaining > time.Hour {
+		t.Fatalf("expected expiry within the hour, got %v", remaining)
+	}
+
+	req = httptest.NewRequest(http.MethodGet, "/auth/session", nil)
+	req.Header.Set("Authorization", "Bearer not-a-session")
+	rec = httptest.NewRecorder()
+	router.ServeHTTP(rec, req)
+	if rec.Code != http.StatusUnauthorized {
+		t.Fatalf("expected 401 for unknown session, got %d", rec.Code)
+	}
+}
+
+func TestRefreshIssuesNewSession(t *testing.T) {
+	store := newTestStore(t)
+	router := newTestRouter(store)
+
+	oldToken := newTestSession(t, store, "u1")
+	refreshToken, err := store.CreateRefreshToken("u1", time.Hour)
+	if err != nil {
+		t.Fatalf("failed to create refresh token: %v", err)
+	}
+
+	refresh := func(token string) *httptest.ResponseRecorder {
+		body := bytes.NewBufferString(`{"refresh_token":"` + token + `"}`)
+		req := httptest.NewRequest(http.MethodPost, "/auth/refresh", body)
+		req.Header.Set("Content-Type", "application/json")
+		rec := httptest.NewRecorder()
+		router.ServeHTTP(rec, req)
+		return rec
+	}
+
+	rec := refresh(refreshToken)
+	if rec.Code != http.StatusOK {
+		t.Fatalf("expected 200, got %d", rec.Code)
+	}
+	var resp struct {
+		Token        string `json:"token"`
+		RefreshToken string `json:"refresh_token"`
+	}
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatalf("failed to decode response: %v", err)
+	}
+	if resp.Token == "" || resp.Token == oldToken {
+		t.Fatalf("expected a new session token, got %q", resp.Token)
+	}
+	if resp.RefreshToken == "" || resp.RefreshToken == refreshToken {
+		t.Fatalf("expected a new refresh token, got %q", resp.RefreshToken)
+	}
+
+	userID, err := store.GetUserFromSession(resp.Token)
+	if err != nil || userID != "u1" {
+		t.Fatalf("expected new session for u1, got %q (%v)", userID, err)
+	}
+	if _, err := store.GetUserFromSession(oldToken); err != nil {
+		t.Fatalf("expected old session to stay valid until it expires: %v", err)
+	}
+
+	if rec := refresh(refreshToken); rec.Code != http.StatusUnauthorized {
+		t.Fatalf("expected 401 when reusing a refresh token, got %d", rec.Code)
+	}
+	if rec := refresh(resp.RefreshToken); rec.Code != http.StatusOK {
+		t.Fatalf("expected the new refresh token to work, got %d", rec.Code)
+	}
+}
+
+func TestLogoutRevokesRefreshToken(t *testing.T) {
+	store := newTestStore(t)
+	router := newTestRouter(store)
+
+	token := newTestSession(t, store, "u1")
+	refreshToken, err := store.CreateRefreshToken("u1", time.Hour)
+	if err != nil {
+		t.Fatalf("failed to create refresh token: %v", err)
+	}
+
+	body := bytes.NewBufferString(`{"refresh_token":"` + refreshToken + `"}`)
+	req := httptest.NewRequest(http.MethodPost, "/auth/logout", body)
+	req.Header.Set("Authorization", "Bearer "+token)
+	req.Header.Set("Content-Type", "application/json")
+	rec := httptest.NewRecorder()
+	router.ServeHTTP(rec, req)
+	if rec.Code != http.StatusOK {
+		t.Fatalf("expected 200, got %d", rec.Code)
+	}
+
+	if _, err := store.ConsumeRefreshToken(refreshToken); err == nil {
+		t.Fatalf("expected refresh token to be revoked")
+	}
+}
+
+func TestClearSessionsRevokesRefreshTokens(t *testing.T) {
+	store := newTestStore(t)
+
+	newTestSession(t, store, "u1")
+	refreshToken, err := store.CreateRefreshToken("u1", time.Hour)
+	if err != nil {
+		t.Fatalf("failed to create refresh token: %v", err)
+	}
+
+	removed, err := store.ClearSessions()
+	if err != nil {
+		t.Fatalf("failed to clear sessions: %v", err)
+	}
+	if removed != 1 {
+		t.Fatalf("expected 1 session removed, got %d", removed)
+	}
+	if _, err := store.ConsumeRefreshToken(refreshToken); err == nil {
+		t.Fatalf("expected refresh token to be revoked")
+	}
+}
+
+func TestDiscordLoginTakesChallengeAndPasswordInBody(t *testing.T) {
+	store := newTestStore(t)
+	router := newTestRouterWithConfig(store, &Config{
+		Discord: DiscordConfig{ClientID: "client"},
+		Access:  AccessConfig{Password: "secret"},
+	})
+
+	challenge := strings.Repeat("a", 43)
+	cases := []struct {
+		name       string
+		body       string
+		expectCode int
+	}{
+		{name: "no challenge", body: `{"redirect":"http://app","password":"secret"}`, expectCode: http.StatusBadRequest},
+		{name: "plain method", body: `{"redirect":"http://app","code_challenge":"` + challenge + `","code_challenge_method":"plain","password":"secret"}`, expectCode: http.StatusBadRequest},
+		{name: "wrong password", body: `{"redirect":"http://app","code_challenge":"` + challenge + `","code_challenge_method":"S256","password":"nope"}`, expectCode: http.StatusUnauthorized},
+		{name: "ok", body: `{"redirect":"http://app","code_challenge":"` + challenge + `","code_challenge_method":"S256","password":"secret"}`, expectCode: http.StatusOK},
+	}
+
+	for _, tc := range cases {
+		t.Run(tc.name, func(t *testing.T) {
+			req := httptest.NewRequest(http.MethodPost, "/auth/discord/login", bytes.NewBufferString(tc.body))
+			req.Header.Set("Content-Type", "application/json")
+			rec := httptest.NewRecorder()
+			router.ServeHTTP(rec, req)
+			if rec.Code != tc.expectCode {
+				t.Fatalf("expected %d, got %d", tc.expectCode, rec.Code)
+			}
+			if tc.expectCode != http.StatusOK {
+				return
+			}
+			var resp struct {
+				URL string `json:"url"`
+			}
+			if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+				t.Fatalf("failed to decode response: %v", err)
+			}
+			if !strings.HasPrefix(resp.URL, "https://discord.com/") || strings.Contains(resp.URL, "secret") {
+				t.Fatalf("unexpected login url %q", resp.URL)
+			}
+		})
+	}
+}
+
+func TestTokenExchangeNeedsVerifier(t *testing.T) {
+	store := newTestStore(t)
+	router := newTestRouter(store)
+
+	if err := store.UpsertUser(User{ID: "u1", Username: "tester"}); err != nil {
+		t.Fatalf("failed to upsert user: %v", err)
+	}
+	verifier := strings.Repeat("v", 64)
+	sum := sha256.Sum256([]byte(verifier))
+	challenge := loginChallenge{
+		codeChallenge: base64.RawURLEncoding.EncodeToString(sum[:]),
+		userID:        "u1",
+	}
+
+	exchange := func(code string, verifier string) *httptest.ResponseRecorder {
+		body := bytes.NewBufferString(`{"code":"` + code + `","code_verifier":"` + verifier + `"}`)
+		req := httptest.NewRequest(http.MethodPost, "/auth/token", body)
+		req.Header.Set("Content-Type", "application/json")
+		rec := httptest.NewRecorder()
+		router.ServeHTTP(rec, req)
+		return rec
+	}
+
+	code := loginCodes.Create(loginCodeTTL, challenge)
+	if rec := exchange(code, strings.Repeat("x", 64)); rec.Code != http.StatusUnauthorized {
+		t.Fatalf("expected 401 for wrong verifier, got %d", rec.Code)
+	}
+	if rec := exchange(code, verifier); rec.Code != http.StatusUnauthorized {
+		t.Fatalf("expected code to be spent after a failed exchange, got %d", rec.Code)
+	}
+
+	code = loginCodes.Create(loginCodeTTL, challenge)
+	rec := exchange(code, verifier)
+	if rec.Code != http.StatusOK {
+		t.Fatalf("expected 200, got %d", rec.Code)
+	}
+	var resp struct {
+		Token        string `json:"token"`
+		RefreshToken string `json:"refresh_token"`
+	}
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatalf("failed to decode response: %v", err)
+	}
+	if userID, err := store.GetUserFromSession(resp.Token); err != nil || userID != "u1" {
+		t.Fatalf("expected session for u1, got %q (%v)", userID, err)
+	}
+	if resp.RefreshToken == "" {
+		t.Fatalf("expected a refresh token")
+	}
+	if rec := exchange(code, verifier); rec.Code != http.StatusUnauthorized {
+		t.Fatalf("expected 401 when reusing a code, got %d", rec.Code)
+	}
+}
+
+func TestWebSocketAuthFrame(t *testing.T) {
+	store := newTestStore(t)
+	server := httptest.NewServer(newTestRouter(store))
+	defer server.Close()
+	wsURL := "ws" + strings.TrimPrefix(server.URL, "http") + "/social/ws"
+
+	token := newTestSession(t, store, "u1")
+
+	dial := func(t *testing.T, frame string) *websocket.Conn {
+		t.Helper()
+		conn, _, err := websocket.DefaultDialer.Dial(wsURL, nil)
+		if err != nil {
+			t.Fatalf("failed to dial: %v", err)
+		}
+		t.Cleanup(func() {
+			_ = conn.Close()
+		})
+		if err := conn.WriteMessage(websocket.TextMessage, []byte(frame)); err != nil {
+			t.Fatalf("failed to send auth frame: %v", err)
+		}
+		_ = conn.SetReadDeadline(time.Now().Add(5 * time.Second))
+		return conn
+	}
+
+	rejected := []struct {
+		name  string
+		frame string
+	}{
+		{name: "invalid token", frame: `{"type":"auth","token":"not-a-session"}`},
+		{name: "wrong type", frame: `{"type":"status","token":"` + token + `"}`},
+		{name: "not json", frame: token},
+	}
+	for _, tc := range rejected {
+		t.Run(tc.name, func(t *testing.T) {
+			conn := dial(t, tc.frame)
+			_, _, err := conn.ReadMessage()
+			if !websocket.IsCloseError(err, wsCloseUnauthorized) {
+				t.Fatalf("expected close %d, got %v", wsCloseUnauthorized, err)
+			}
+		})
+	}
+
+	t.Run("valid token", func(t *testing.T) {
+		conn := dial(t, `{"type":"auth","token":"`+token+`"}`)
+		_, data, err := conn.ReadMessage()
+		if err != nil {
+			t.Fatalf("expected snapshot, got %v", err)
+		}
+		var event SocialEvent
+		if err := json.Unmarshal(data, &event); err != nil || event.Type != "snapshot" {
+			t.Fatalf("expected snapshot event, got %q", data)
+		}
+	})
+}
+
 func TestMain(m *testing.M) {
 	// Ensure tests don't accidentally read real config.toml from another working dir.
 	_ = os.Chdir("..")
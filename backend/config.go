@@ -11,15 +11,17 @@ import (
 )
 
 type Config struct {
-	Port    int           `toml:"port" json:"port"`
-	Mode    string        `toml:"mode" json:"mode"`
-	Discord DiscordConfig `toml:"discord" json:"discord"`
-	Features Features     `toml:"features" json:"features"`
-	Access  AccessConfig  `toml:"access" json:"access"`
-	Admins  []string      `toml:"admins" json:"admins"`
-	Session SessionConfig `toml:"session" json:"session"`
-	Theme   *ThemeConfig  `toml:"theme" json:"theme"`
-	mu      sync.RWMutex
+	Port          int                 `toml:"port" json:"port"`
+	Mode          string              `toml:"mode" json:"mode"`
+	Discord       DiscordConfig       `toml:"discord" json:"discord"`
+	OIDC          OIDCConfig          `toml:"oidc" json:"oidc"`
+	LocalAccounts LocalAccountsConfig `toml:"local_accounts" json:"local_accounts"`
+	Features      Features            `toml:"features" json:"features"`
+	Access        AccessConfig        `toml:"access" json:"access"`
+	Admins        []string            `toml:"admins" json:"admins"`
+	Session       SessionConfig       `toml:"session" json:"session"`
+	Theme         *ThemeConfig        `toml:"theme" json:"theme"`
+	mu            sync.RWMutex
 }
 
 type DiscordConfig struct {
@@ -29,6 +31,24 @@ type DiscordConfig struct {
 	Scopes       []string `toml:"scopes" json:"scopes"`
 }
 
+// OIDCConfig sets up login with a generic OpenID Connect provider. The
+// endpoints are the ones listed in the provider's
+// .well-known/openid-configuration.
+type OIDCConfig struct {
+	Name         string   `toml:"name" json:"name"`
+	ClientID     string   `toml:"client_id" json:"client_id"`
+	ClientSecret string   `toml:"client_secret" json:"client_secret"`
+	RedirectURI  string   `toml:"redirect_uri" json:"redirect_uri"`
+	AuthURL      string   `toml:"auth_url" json:"auth_url"`
+	TokenURL     string   `toml:"token_url" json:"token_url"`
+	UserInfoURL  string   `toml:"userinfo_url" json:"userinfo_url"`
+	Scopes       []string `toml:"scopes" json:"scopes"`
+}
+
+type LocalAccountsConfig struct {
+	Enabled bool `toml:"enabled" json:"enabled"`
+}
+
 type Features struct {
 	ChatEnabled bool `toml:"chat_enabled" json:"chat_enabled"`
 }
@@ -38,7 +58,8 @@ type AccessConfig struct {
 }
 
 type SessionConfig struct {
-	TTLHours int `toml:"ttl_hours" json:"ttl_hours"`
+	TTLHours        int `toml:"ttl_hours" json:"ttl_hours"`
+	RefreshTTLHours int `toml:"refresh_ttl_hours" json:"refresh_ttl_hours"`
 }
 
 type ThemeConfig struct {
@@ -53,6 +74,8 @@ type ThemeConfig struct {
 
 const configPath = "./config.toml"
 
+const defaultRefreshTTL = 30 * 24 * time.Hour
+
 func LoadConfig() (*Config, error) {
 	var cfg Config
 	if _, err := toml.DecodeFile(configPath, &cfg); err != nil {
@@ -82,6 +105,8 @@ func (c *Config) Reload() error {
 	c.Port = next.Port
 	c.Mode = next.Mode
 	c.Discord = next.Discord
+	c.OIDC = next.OIDC
+	c.LocalAccounts = next.LocalAccounts
 	c.Features = next.Features
 	c.Access = next.Access
 	c.Admins = next.Admins
@@ -96,6 +121,8 @@ func (c *Config) Snapshot() Config {
 
 	discord := c.Discord
 	discord.Scopes = append([]string{}, c.Discord.Scopes...)
+	oidc := c.OIDC
+	oidc.Scopes = append([]string{}, c.OIDC.Scopes...)
 	admins := append([]string{}, c.Admins...)
 	var themeCopy *ThemeConfig
 	if c.Theme != nil {
@@ -104,14 +131,16 @@ func (c *Config) Snapshot() Config {
 	}
 
 	return Config{
-		Port:     c.Port,
-		Mode:     c.Mode,
-		Discord:  discord,
-		Features: c.Features,
-		Access:   AccessConfig{},
-		Admins:   admins,
-		Session:  c.Session,
-		Theme:    themeCopy,
+		Port:          c.Port,
+		Mode:          c.Mode,
+		Discord:       discord,
+		OIDC:          oidc,
+		LocalAccounts: c.LocalAccounts,
+		Features:      c.Features,
+		Access:        AccessConfig{},
+		Admins:        admins,
+		Session:       c.Session,
+		Theme:         themeCopy,
 	}
 }
 
@@ -166,6 +195,50 @@ func (c *Config) ChatEnabled() bool {
 	return c.Features.ChatEnabled
 }
 
+func (c *Config) DiscordSettings() DiscordConfig {
+	c.mu.RLock()
+	defer c.mu.RUnlock()
+	discord := c.Discord
+	discord.Scopes = append([]string{}, c.Discord.Scopes...)
+	return discord
+}
+
+func (c *Config) OIDCSettings() OIDCConfig {
+	c.mu.RLock()
+	defer c.mu.RUnlock()
+	oidc := c.OIDC
+	oidc.Scopes = append([]string{}, c.OIDC.Scopes...)
+	return oidc
+}
+
+func (c *Config) LocalAccountsEnabled() bool {
+	c.mu.RLock()
+	defer c.mu.RUnlock()
+	return c.LocalAccounts.Enabled
+}
+
+// LoginProviders lists the login providers that are set up, in the order
+// the login screen shows them.
+func (c *Config) LoginProviders() []LoginProvider {
+	c.mu.RLock()
+	defer c.mu.RUnlock()
+	providers := []LoginProvider{}
+	if c.Discord.ClientID != "" {
+		providers = append(providers, LoginProvider{ID: "discord", Name: "Discord", Kind: loginKindRedirect})
+	}
+	if c.OIDC.ClientID != "" {
+		name := c.OIDC.Name
+		if name == "" {
+			name = "Single sign-on"
+		}
+		providers = append(providers, LoginProvider{ID: "oidc", Name: name, Kind: loginKindRedirect})
+	}
+	if c.LocalAccounts.Enabled {
+		providers = append(providers, LoginProvider{ID: "local", Name: "Username and password", Kind: loginKindPassword})
+	}
+	return providers
+}
+
 func (c *Config) LoginPasswordRequired() bool {
 	c.mu.RLock()
 	defer c.mu.RUnlock()
@@ -186,3 +259,14 @@ func (c *Config) SessionTTL() time.Duration {
 	}
 	return time.Duration(c.Session.TTLHours) * time.Hour
 }
+
+// RefreshTTL is how long a client may go without renewing its session
+// before it has to log in again. Defaults to 30 days.
+func (c *Config) RefreshTTL() time.Duration {
+	c.mu.RLock()
+	defer c.mu.RUnlock()
+	if c.Session.RefreshTTLHours <= 0 {
+		return defaultRefreshTTL
+	}
+	return time.Duration(c.Session.RefreshTTLHours) * time.Hour
+}
@@ -0,0 +1,211 @@
+package main
+
+import (
+	"encoding/json"
+	"fmt"
+	"net/http"
+	"strings"
+	"time"
+
+	"github.com/dgraph-io/badger"
+	"github.com/gin-gonic/gin"
+	"github.com/google/uuid"
+)
+
+// Reports beyond this many per app drop the oldest ones.
+const maxCrashReportsPerApp = 200
+
+// Output tails are cut to this size before they are stored.
+const maxCrashOutputBytes = 64 * 1024
+
+type CrashReport struct {
+	ID              string `json:"id"`
+	AppID           string `json:"app_id"`
+	UserID          string `json:"user_id"`
+	Username        string `json:"username"`
+	AppVersion      string `json:"app_version"`
+	Profile         string `json:"profile,omitempty"`
+	ExitCode        *int   `json:"exit_code,omitempty"`
+	Signal          *int   `json:"signal,omitempty"`
+	DurationSeconds int64  `json:"duration_seconds"`
+	OS              string `json:"os"`
+	ClientVersion   string `json:"client_version"`
+	Output          string `json:"output"`
+	CreatedAt       int64  `json:"created_at"`
+}
+
+// Keys sort by time within an app, so a prefix scan lists oldest first.
+func crashReportKey(appID string, createdAt time.Time, id string) []byte {
+	return append(crashReportPrefix(appID), fmt.Sprintf("%020d:%s", createdAt.UnixNano(), id)...)
+}
+
+// App ids may contain ':', so the id is length-prefixed; otherwise the
+// prefix of app "foo" would also match the reports of app "foo:bar".
+func crashReportPrefix(appID string) []byte {
+	return []byte(fmt.Sprintf("crash:%d:%s:", len(appID), appID))
+}
+
+func (s *Store) AddCrashReport(report CrashReport, at time.Time) error {
+	return s.db.Update(func(txn *badger.Txn) error {
+		data, err := json.Marshal(report)
+		if err != nil {
+			return err
+		}
+		key := crashReportKey(report.AppID, at, report.ID)
+		if err := txn.Set(key, data); err != nil {
+			return err
+		}
+
+		var keys [][]byte
+		opts := badger.DefaultIteratorOptions
+		opts.PrefetchValues = false
+		it := txn.NewIterator(opts)
+		prefix := crashReportPrefix(report.AppID)
+		for it.Seek(prefix); it.ValidForPrefix(prefix); it.Next() {
+			keys = append(keys, it.Item().KeyCopy(nil))
+		}
+		it.Close()
+		for len(keys) > maxCrashReportsPerApp {
+			if err := txn.Delete(keys[0]); err != nil {
+				return err
+			}
+			keys = keys[1:]
+		}
+		return nil
+	})
+}
+
+// ListCrashReports returns an app's reports, newest first.
+func (s *Store) ListCrashReports(appID string) ([]CrashReport, error) {
+	results := []CrashReport{}
+	err := s.db.View(func(txn *badger.Txn) error {
+		it := txn.NewIterator(badger.DefaultIteratorOptions)
+		defer it.Close()
+
+		prefix := crashReportPrefix(appID)
+		for it.Seek(prefix); it.ValidForPrefix(prefix); it.Next() {
+			if err := it.Item().Value(func(val []byte) error {
+				var report CrashReport
+				if err := json.Unmarshal(val, &report); err != nil {
+					return err
+				}
+				results = append(results, report)
+				return nil
+			}); err != nil {
+				return err
+			}
+		}
+		return nil
+	})
+	for i, j := 0, len(results)-1; i < j; i, j = i+1, j-1 {
+		results[i], results[j] = results[j], results[i]
+	}
+	return results, err
+}
+
+func (s *Store) DeleteCrashReports(appID string) error {
+	return s.db.Update(func(txn *badger.Txn) error {
+		var keys [][]byte
+		opts := badger.DefaultIteratorOptions
+		opts.PrefetchValues = false
+		it := txn.NewIterator(opts)
+		prefix := crashReportPrefix(appID)
+		for it.Seek(prefix); it.ValidForPrefix(prefix); it.Next() {
+			keys = append(keys, it.Item().KeyCopy(nil))
+		}
+		it.Close()
+		for _, key := range keys {
+			if err := txn.Delete(key); err != nil {
+				return err
+			}
+		}
+		return nil
+	})
+}
+
+func (s *Store) postCrashReportEp(c *gin.Context) {
+	userID, ok := getUserID(c)
+	if !ok {
+		return
+	}
+	appID := c.Param("id")
+	if !isSafeAppID(appID) {
+		c.JSON(http.StatusBadRequest, gin.H{"error": "invalid app id"})
+		return
+	}
+	var body struct {
+		AppVersion      string `json:"app_version"`
+		Profile         string `json:"profile"`
+		ExitCode        *int   `json:"exit_code"`
+		Signal          *int   `json:"signal"`
+		DurationSeconds int64  `json:"duration_seconds"`
+		OS              string `json:"os"`
+		ClientVersion   string `json:"client_version"`
+		Output          string `json:"output"`
+	}
+	if err := c.ShouldBindJSON(&body); err != nil {
+		c.JSON(http.StatusBadRequest, gin.H{"error": "invalid crash report payload"})
+		return
+	}
+	if body.ExitCode == nil && body.Signal == nil {
+		c.JSON(http.StatusBadRequest, gin.H{"error": "exit code or signal required"})
+		return
+	}
+	output := body.Output
+	if len(output) > maxCrashOutputBytes {
+		output = strings.ToValidUTF8(output[len(output)-maxCrashOutputBytes:], "")
+	}
+
+	username := userID
+	if user, err := s.GetUser(userID); err == nil && user.Username != "" {
+		username = user.Username
+	}
+	now := time.Now()
+	report := CrashReport{
+		ID:              uuid.NewString(),
+		AppID:           appID,
+		UserID:          userID,
+		Username:        username,
+		AppVersion:      strings.TrimSpace(body.AppVersion),
+		Profile:         strings.TrimSpace(body.Profile),
+		ExitCode:        body.ExitCode,
+		Signal:          body.Signal,
+		DurationSeconds: body.DurationSeconds,
+		OS:              strings.TrimSpace(body.OS),
+		ClientVersion:   strings.TrimSpace(body.ClientVersion),
+		Output:          output,
+		CreatedAt:       now.Unix(),
+	}
+	if err := s.AddCrashReport(report, now); err != nil {
+		c.JSON(http.StatusInternalServerError, gin.H{"error": "crash report save failed"})
+		return
+	}
+	c.JSON(http.StatusOK, gin.H{"id": report.ID})
+}
+
+func (s *Store) listCrashReportsEp(c *gin.Context) {
+	appID := c.Param("id")
+	if !isSafeAppID(appID) {
+		c.JSON(http.StatusBadRequest, gin.H{"error": "invalid app id"})
+		return
+	}
+	reports, err := s.ListCrashReports(appID)
+	if err != nil {
+		c.JSON(http.StatusInternalServerError, gin.H{"error": "crash report list failed"})
+		return
+	}
+	c.JSON(http.StatusOK, reports)
+}
+
+func (s *Store) clearCrashReportsEp(c *gin.Context) {
+	appID := c.Param("id")
+	if !isSafeAppID(appID) {
+		c.JSON(http.StatusBadRequest, gin.H{"error": "invalid app id"})
+		return
+	}
+	if err := s.DeleteCrashReports(appID); err != nil {
+		c.JSON(http.StatusInternalServerError, gin.H{"error": "crash report delete failed"})
+		return
+	}
+	c.JSON(http.StatusOK, gin.H{"status": "deleted"})
+}
@@ -0,0 +1,121 @@
+package main
+
+import (
+	"bytes"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+	"time"
+)
+
+func postTestCrashReport(t *testing.T, router http.Handler, token, appID, body string) int {
+	t.Helper()
+
+	req := httptest.NewRequest(http.MethodPost, "/apps/"+appID+"/crash-reports", bytes.NewBufferString(body))
+	req.Header.Set("Authorization", "Bearer "+token)
+	req.Header.Set("Content-Type", "application/json")
+	rec := httptest.NewRecorder()
+	router.ServeHTTP(rec, req)
+	return rec.Code
+}
+
+func TestCrashReportsRoundTrip(t *testing.T) {
+	store := newTestStore(t)
+	router := newTestRouterWithConfig(store, &Config{Admins: []string{"admin"}})
+	adminToken := newTestSession(t, store, "admin")
+	userToken := newTestSession(t, store, "u1")
+
+	if code := postTestCrashReport(t, router, userToken, "demo", `{"app_version":"1.0","os":"linux"}`); code != http.StatusBadRequest {
+		t.Fatalf("expected report without exit status to be rejected, got %d", code)
+	}
+
+	output := strings.Repeat("x", maxCrashOutputBytes) + "tail"
+	payload, _ := json.Marshal(map[string]any{
+		"app_version":      "1.0",
+		"exit_code":        139,
+		"duration_seconds": 4,
+		"os":               "linux",
+		"client_version":   "1.0.2",
+		"output":           output,
+	})
+	if code := postTestCrashReport(t, router, userToken, "demo", string(payload)); code != http.StatusOK {
+		t.Fatalf("expected 200 posting crash report, got %d", code)
+	}
+	if code := postTestCrashReport(t, router, userToken, "demo", `{"app_version":"1.1","signal":11}`); code != http.StatusOK {
+		t.Fatalf("expected 200 posting crash report, got %d", code)
+	}
+
+	req := httptest.NewRequest(http.MethodGet, "/admin/apps/demo/crash-reports", nil)
+	req.Header.Set("Authorization", "Bearer "+userToken)
+	rec := httptest.NewRecorder()
+	router.ServeHTTP(rec, req)
+	if rec.Code != http.StatusForbidden {
+		t.Fatalf("expected 403 for non-admin, got %d", rec.Code)
+	}
+
+	req = httptest.NewRequest(http.MethodGet, "/admin/apps/demo/crash-reports", nil)
+	req.Header.Set("Authorization", "Bearer "+adminToken)
+	rec = httptest.NewRecorder()
+	router.ServeHTTP(rec, req)
+	if rec.Code != http.StatusOK {
+		t.Fatalf("expected 200 listing crash reports, got %d", rec.Code)
+	}
+	var reports []CrashReport
+	if err := json.Unmarshal(rec.Body.Bytes(), &reports); err != nil {
+		t.Fatalf("invalid crash reports payload: %v", err)
+	}
+	if len(reports) != 2 {
+		t.Fatalf("expected 2 reports, got %d", len(reports))
+	}
+	if reports[0].AppVersion != "1.1" || reports[0].Signal == nil || *reports[0].Signal != 11 {
+		t.Fatalf("expected newest report first, got %+v", reports[0])
+	}
+	older := reports[1]
+	if older.ExitCode == nil || *older.ExitCode != 139 || older.Username != "u1" {
+		t.Fatalf("unexpected report: %+v", older)
+	}
+	if len(older.Output) != maxCrashOutputBytes || !strings.HasSuffix(older.Output, "tail") {
+		t.Fatalf("expected output to keep its last %d bytes, got %d", maxCrashOutputBytes, len(older.Output))
+	}
+
+	req = httptest.NewRequest(http.MethodDelete, "/admin/apps/demo/crash-reports", nil)
+	req.Header.Set("Authorization", "Bearer "+adminToken)
+	rec = httptest.NewRecorder()
+	router.ServeHTTP(rec, req)
+	if rec.Code != http.StatusOK {
+		t.Fatalf("expected 200 clearing crash reports, got %d", rec.Code)
+	}
+	if left, err := store.ListCrashReports("demo"); err != nil || len(left) != 0 {
+		t.Fatalf("expected no reports left, got %d (%v)", len(left), err)
+	}
+}
+
+func TestCrashReportsStayWithTheirApp(t *testing.T) {
+	store := newTestStore(t)
+
+	for _, appID := range []string{"foo", "foo:bar"} {
+		code := 1
+		report := CrashReport{ID: appID + "-report", AppID: appID, ExitCode: &code}
+		if err := store.AddCrashReport(report, time.Now()); err != nil {
+			t.Fatalf("failed to add crash report: %v", err)
+		}
+	}
+
+	reports, err := store.ListCrashReports("foo")
+	if err != nil {
+		t.Fatalf("failed to list crash reports: %v", err)
+	}
+	if len(reports) != 1 || reports[0].AppID != "foo" {
+		t.Fatalf("expected only the report of foo, got %+v", reports)
+	}
+
+	if err := store.DeleteCrashReports("foo"); err != nil {
+		t.Fatalf("failed to delete crash reports: %v", err)
+	}
+	reports, err = store.ListCrashReports("foo:bar")
+	if err != nil || len(reports) != 1 {
+		t.Fatalf("expected the report of foo:bar to survive, got %d (%v)", len(reports), err)
+	}
+}
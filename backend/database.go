@@ -117,8 +117,10 @@ func (s *Store) CountSessions() (int, error) {
 	return count, err
 }
 
+// ClearSessions logs everyone out. Refresh tokens go too, or clients would
+// just renew their sessions. Returns the number of sessions removed.
 func (s *Store) ClearSessions() (int, error) {
-	var keys [][]byte
+	var sessionKeys, refreshKeys [][]byte
 	err := s.db.View(func(txn *badger.Txn) error {
 		it := txn.NewIterator(badger.DefaultIteratorOptions)
 		defer it.Close()
@@ -126,7 +128,12 @@ func (s *Store) ClearSessions() (int, error) {
 		prefix := []byte("session:")
 		for it.Seek(prefix); it.ValidForPrefix(prefix); it.Next() {
 			key := it.Item().KeyCopy(nil)
-			keys = append(keys, key)
+			sessionKeys = append(sessionKeys, key)
+		}
+		prefix = []byte("refresh:")
+		for it.Seek(prefix); it.ValidForPrefix(prefix); it.Next() {
+			key := it.Item().KeyCopy(nil)
+			refreshKeys = append(refreshKeys, key)
 		}
 		return nil
 	})
@@ -136,7 +143,12 @@ func (s *Store) ClearSessions() (int, error) {
 
 	removed := 0
 	err = s.db.Update(func(txn *badger.Txn) error {
-		for _, key := range keys {
+		for _, key := range refreshKeys {
+			if err := txn.Delete(key); err != nil {
+				return err
+			}
+		}
+		for _, key := range sessionKeys {
 			if err := txn.Delete(key); err != nil {
 				return err
 			}
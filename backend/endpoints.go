@@ -34,15 +34,19 @@ func RegisterRoutes(router *gin.Engine, store *Store, cfg *Config) {
 		})
 	})
 
-	// Init OAuth
-	InitDiscordOAuth(cfg)
-
 	// OAuth routes
 	auth := router.Group("/auth")
 	{
-		auth.GET("/discord/login", DiscordLoginHandler(cfg))
-		auth.GET("/discord/callback", DiscordCallbackHandler(store, cfg))
+		for _, id := range oauthProviderIDs {
+			auth.POST("/"+id+"/login", OAuthLoginHandler(cfg, id))
+			auth.GET("/"+id+"/callback", OAuthCallbackHandler(store, cfg, id))
+		}
+		auth.POST("/local/login", LocalLoginHandler(store, cfg))
+		auth.POST("/local/register", LocalRegisterHandler(store, cfg))
 		auth.POST("/logout", LogoutHandler(store))
+		auth.POST("/token", TokenHandler(store, cfg))
+		auth.POST("/refresh", RefreshHandler(store, cfg))
+		auth.GET("/session", AuthMiddleware(store), SessionHandler(store))
 	}
 
 	// Feature flags
@@ -50,6 +54,7 @@ func RegisterRoutes(router *gin.Engine, store *Store, cfg *Config) {
 		c.JSON(http.StatusOK, gin.H{
 			"chat_enabled":            cfg.ChatEnabled(),
 			"login_password_required": cfg.LoginPasswordRequired(),
+			"login_providers":         cfg.LoginProviders(),
 		})
 	})
 
@@ -70,8 +75,15 @@ func RegisterRoutes(router *gin.Engine, store *Store, cfg *Config) {
 		apps.GET("", listAppsHandler)
 		apps.GET("/playtime", store.getPlaytimeEp)
 		apps.POST("/:id/playtime", store.postPlaytimeEp)
+		apps.POST("/:id/crash-reports", store.postCrashReportEp)
+		apps.GET("/:id/save", store.getCloudSaveEp)
+		apps.GET("/:id/save/archive", store.getCloudSaveArchiveEp)
+		apps.PUT("/:id/save", store.putCloudSaveEp)
 		apps.GET("/:id/config", getAppConfigHandler)
 		apps.GET("/:id/archive", getAppArchiveHandler)
+		apps.GET("/:id/versions", listAppVersionsHandler)
+		apps.GET("/:id/versions/:vid/config", getAppVersionConfigHandler)
+		apps.GET("/:id/versions/:vid/archive", getAppVersionArchiveHandler)
 		apps.POST("/refresh", func(c *gin.Context) {
 			// Ensure apps directory exists; listing always reads from disk.
 			_ = os.MkdirAll(appsDir, 0755)
@@ -197,12 +209,19 @@ func RegisterRoutes(router *gin.Engine, store *Store, cfg *Config) {
 			c.JSON(http.StatusOK, gin.H{"status": "reloaded"})
 		})
 		admin.PUT("/apps/upload/:id", func(c *gin.Context) {
-			if err := uploadAppHandler(c); err != nil {
+			if err := uploadAppHandler(c, store); err != nil {
 				c.JSON(http.StatusBadRequest, gin.H{"error": err.Error()})
 				return
 			}
 			c.JSON(http.StatusOK, gin.H{"status": "uploaded"})
 		})
+		admin.DELETE("/apps/upload/:id", func(c *gin.Context) {
+			if err := discardAppUpload(strings.TrimSpace(c.Param("id"))); err != nil {
+				c.JSON(http.StatusBadRequest, gin.H{"error": err.Error()})
+				return
+			}
+			c.JSON(http.StatusOK, gin.H{"status": "discarded"})
+		})
 		admin.GET("/apps/:id/config", func(c *gin.Context) {
 			content, err := readAppConfig(c.Param("id"))
 			if err != nil {
@@ -225,6 +244,22 @@ func RegisterRoutes(router *gin.Engine, store *Store, cfg *Config) {
 			}
 			c.JSON(http.StatusOK, gin.H{"status": "saved"})
 		})
+		admin.POST("/apps/:id/versions/:vid/promote", func(c *gin.Context) {
+			if err := promoteAppVersion(c.Param("id"), c.Param("vid")); err != nil {
+				c.JSON(http.StatusBadRequest, gin.H{"error": err.Error()})
+				return
+			}
+			c.JSON(http.StatusOK, gin.H{"status": "promoted"})
+		})
+		admin.DELETE("/apps/:id/versions/:vid", func(c *gin.Context) {
+			if err := deleteAppVersion(c.Param("id"), c.Param("vid")); err != nil {
+				c.JSON(http.StatusBadRequest, gin.H{"error": err.Error()})
+				return
+			}
+			c.JSON(http.StatusOK, gin.H{"status": "deleted"})
+		})
+		admin.GET("/apps/:id/crash-reports", store.listCrashReportsEp)
+		admin.DELETE("/apps/:id/crash-reports", store.clearCrashReportsEp)
 		admin.DELETE("/apps/:id", func(c *gin.Context) {
 			if err := deleteApp(c.Param("id")); err != nil {
 				c.JSON(http.StatusBadRequest, gin.H{"error": err.Error()})
@@ -351,20 +386,13 @@ func (s *Store) chatWsEp(c *gin.Context, chatHub *ChatHub) {
 		return
 	}
 
-	token := c.Query("token")
-	if token == "" {
-		c.JSON(http.StatusUnauthorized, gin.H{"error": "missing token"})
-		return
-	}
-
-	userID, err := s.GetUserFromSession(token)
+	conn, err := chatWsUpgrader.Upgrade(c.Writer, c.Request, nil)
 	if err != nil {
-		c.JSON(http.StatusUnauthorized, gin.H{"error": "invalid session"})
 		return
 	}
 
-	conn, err := chatWsUpgrader.Upgrade(c.Writer, c.Request, nil)
-	if err != nil {
+	userID, ok := s.authenticateWs(conn)
+	if !ok {
 		return
 	}
 
@@ -0,0 +1,248 @@
+package main
+
+import (
+	"encoding/json"
+	"errors"
+	"net/http"
+	"regexp"
+	"strconv"
+	"strings"
+	"sync"
+	"time"
+
+	"github.com/dgraph-io/badger"
+	"github.com/gin-gonic/gin"
+	"golang.org/x/crypto/bcrypt"
+)
+
+// Local accounts log in with a username and password stored on this
+// server, for members without an account at an external provider.
+
+const minLocalPasswordLength = 8
+
+var localUsernamePattern = regexp.MustCompile(`^[A-Za-z0-9_.-]{3,32}$`)
+
+var errLocalAccountExists = errors.New("account already exists")
+
+// Failed logins for a username from one address before further attempts
+// have to wait. The wait doubles with every failure after that, up to
+// localLoginMaxDelay.
+const (
+	localLoginFreeAttempts = 5
+	localLoginBaseDelay    = time.Second
+	localLoginMaxDelay     = 5 * time.Minute
+)
+
+// dummyPasswordHash is compared against when a username doesn't exist, so a
+// failed login takes as long whether or not the account is there.
+var dummyPasswordHash = sync.OnceValue(func() []byte {
+	hash, _ := bcrypt.GenerateFromPassword([]byte("not a real password"), bcrypt.DefaultCost)
+	return hash
+})
+
+type localAccount struct {
+	UserID       string `json:"user_id"`
+	PasswordHash []byte `json:"password_hash"`
+}
+
+// Usernames are unique regardless of case.
+func localAccountKey(username string) []byte {
+	return []byte("account:" + strings.ToLower(username))
+}
+
+// CreateLocalAccount stores a new account and its user. User IDs are
+// prefixed with "local:" so they can't collide with other providers.
+func (s *Store) CreateLocalAccount(username string, password string) (User, error) {
+	hash, err := bcrypt.GenerateFromPassword([]byte(password), bcrypt.DefaultCost)
+	if err != nil {
+		return User{}, err
+	}
+	user := User{
+		ID:       "local:" + strings.ToLower(username),
+		Username: username,
+	}
+	data, err := json.Marshal(localAccount{UserID: user.ID, PasswordHash: hash})
+	if err != nil {
+		return User{}, err
+	}
+
+	err = s.db.Update(func(txn *badger.Txn) error {
+		key := localAccountKey(username)
+		if _, err := txn.Get(key); err == nil {
+			return errLocalAccountExists
+		} else if err != badger.ErrKeyNotFound {
+			return err
+		}
+		return txn.Set(key, data)
+	})
+	if err != nil {
+		return User{}, err
+	}
+
+	return user, s.UpsertUser(user)
+}
+
+// VerifyLocalAccount returns the user ID for a username and password.
+func (s *Store) VerifyLocalAccount(username string, password string) (string, error) {
+	var account localAccount
+
+	err := s.db.View(func(txn *badger.Txn) error {
+		item, err := txn.Get(localAccountKey(username))
+		if err != nil {
+			return err
+		}
+		return item.Value(func(val []byte) error {
+			return json.Unmarshal(val, &account)
+		})
+	})
+	if err == badger.ErrKeyNotFound {
+		_ = bcrypt.CompareHashAndPassword(dummyPasswordHash(), []byte(password))
+		return "", err
+	}
+	if err != nil {
+		return "", err
+	}
+
+	if err := bcrypt.CompareHashAndPassword(account.PasswordHash, []byte(password)); err != nil {
+		return "", err
+	}
+	return account.UserID, nil
+}
+
+type loginFailures struct {
+	count int
+	last  time.Time
+	until time.Time
+}
+
+// loginBackoff slows down password guessing against one username from one
+// address. It lives in memory, so a restart forgets it.
+type loginBackoff struct {
+	mu       sync.Mutex
+	failures map[string]loginFailures
+}
+
+func newLoginBackoff() *loginBackoff {
+	return &loginBackoff{failures: map[string]loginFailures{}}
+}
+
+// wait returns how long key has to wait before its next attempt.
+func (b *loginBackoff) wait(key string, now time.Time) time.Duration {
+	b.mu.Lock()
+	defer b.mu.Unlock()
+	return b.failures[key].until.Sub(now)
+}
+
+func (b *loginBackoff) fail(key string, now time.Time) {
+	b.mu.Lock()
+	defer b.mu.Unlock()
+	// Entries without a failure for longer than the longest wait are
+	// dropped as new failures come in.
+	for other, failures := range b.failures {
+		if now.Sub(failures.last) > localLoginMaxDelay {
+			delete(b.failures, other)
+		}
+	}
+	failures := b.failures[key]
+	failures.count++
+	failures.last = now
+	if extra := failures.count - localLoginFreeAttempts; extra >= 0 {
+		delay := localLoginMaxDelay
+		if extra < 20 {
+			delay = min(localLoginBaseDelay<<extra, localLoginMaxDelay)
+		}
+		failures.until = now.Add(delay)
+	}
+	b.failures[key] = failures
+}
+
+func (b *loginBackoff) reset(key string) {
+	b.mu.Lock()
+	defer b.mu.Unlock()
+	delete(b.failures, key)
+}
+
+func LocalLoginHandler(store *Store, cfg *Config) gin.HandlerFunc {
+	backoff := newLoginBackoff()
+	return func(c *gin.Context) {
+		if !cfg.LocalAccountsEnabled() {
+			c.JSON(http.StatusNotFound, gin.H{"error": "login provider not enabled"})
+			return
+		}
+
+		var body struct {
+			Username string `json:"username"`
+			Password string `json:"password"`
+		}
+		if err := c.ShouldBindJSON(&body); err != nil || body.Username == "" || body.Password == "" {
+			c.JSON(http.StatusBadRequest, gin.H{"error": "missing username or password"})
+			return
+		}
+
+		key := strings.ToLower(body.Username) + "\x00" + c.ClientIP()
+		if wait := backoff.wait(key, time.Now()); wait > 0 {
+			c.Header("Retry-After", strconv.Itoa(int(wait/time.Second)+1))
+			c.JSON(http.StatusTooManyRequests, gin.H{"error": "too many login attempts"})
+			return
+		}
+
+		userID, err := store.VerifyLocalAccount(body.Username, body.Password)
+		if err != nil {
+			backoff.fail(key, time.Now())
+			c.JSON(http.StatusUnauthorized, gin.H{"error": "invalid username or password"})
+			return
+		}
+		backoff.reset(key)
+
+		issueSession(c, store, cfg, userID)
+	}
+}
+
+// LocalRegisterHandler creates an account and logs it in. Like a first
+// login with an external provider, it needs the access password if the
+// server has one.
+func LocalRegisterHandler(store *Store, cfg *Config) gin.HandlerFunc {
+	return func(c *gin.Context) {
+		if !cfg.LocalAccountsEnabled() {
+			c.JSON(http.StatusNotFound, gin.H{"error": "login provider not enabled"})
+			return
+		}
+
+		var body struct {
+			Username       string `json:"username"`
+			Password       string `json:"password"`
+			AccessPassword string `json:"access_password"`
+		}
+		if err := c.ShouldBindJSON(&body); err != nil {
+			c.JSON(http.StatusBadRequest, gin.H{"error": "invalid request"})
+			return
+		}
+
+		if cfg.LoginPasswordRequired() {
+			if body.AccessPassword == "" || body.AccessPassword != cfg.LoginPassword() {
+				c.JSON(http.StatusUnauthorized, gin.H{"error": "invalid access password"})
+				return
+			}
+		}
+		if !localUsernamePattern.MatchString(body.Username) {
+			c.JSON(http.StatusBadRequest, gin.H{"error": "invalid username"})
+			return
+		}
+		if len(body.Password) < minLocalPasswordLength {
+			c.JSON(http.StatusBadRequest, gin.H{"error": "password too short"})
+			return
+		}
+
+		user, err := store.CreateLocalAccount(body.Username, body.Password)
+		if errors.Is(err, errLocalAccountExists) {
+			c.JSON(http.StatusConflict, gin.H{"error": "username taken"})
+			return
+		}
+		if err != nil {
+			c.JSON(http.StatusInternalServerError, gin.H{"error": "account create failed"})
+			return
+		}
+
+		issueSession(c, store, cfg, user.ID)
+	}
+}
@@ -0,0 +1,248 @@
+package main
+
+import (
+	"context"
+	"encoding/json"
+	"fmt"
+	"net/http"
+	"strings"
+
+	"github.com/gin-gonic/gin"
+	"golang.org/x/oauth2"
+)
+
+// Login provider kinds, which tell the client what to show for a provider.
+const (
+	// loginKindRedirect providers have their own login page the client is
+	// sent to.
+	loginKindRedirect = "redirect"
+	// loginKindPassword providers take a username and password form.
+	loginKindPassword = "password"
+)
+
+// oauthProviderIDs are the providers logging in through the OAuth2
+// authorization code flow. Each gets /auth/<id>/login and
+// /auth/<id>/callback.
+var oauthProviderIDs = []string{"discord", "oidc"}
+
+type LoginProvider struct {
+	ID   string `json:"id"`
+	Name string `json:"name"`
+	Kind string `json:"kind"`
+}
+
+type oauthProvider struct {
+	config *oauth2.Config
+	// fetchUser identifies the user with the provider's access token.
+	fetchUser func(client *http.Client) (User, error)
+}
+
+// oauthProviderFor builds provider id from the current config, so a config
+// reload takes effect on the next login. ok is false when the provider is
+// not set up.
+func oauthProviderFor(cfg *Config, id string) (*oauthProvider, bool) {
+	switch id {
+	case "discord":
+		discord := cfg.DiscordSettings()
+		if discord.ClientID == "" {
+			return nil, false
+		}
+		return &oauthProvider{
+			config: &oauth2.Config{
+				ClientID:     discord.ClientID,
+				ClientSecret: discord.ClientSecret,
+				RedirectURL:  discord.RedirectURI,
+				Scopes:       discord.Scopes,
+				Endpoint: oauth2.Endpoint{
+					AuthURL:  "https://discord.com/api/oauth2/authorize",
+					TokenURL: "https://discord.com/api/oauth2/token",
+				},
+			},
+			fetchUser: fetchDiscordUser,
+		}, true
+	case "oidc":
+		oidc := cfg.OIDCSettings()
+		if oidc.ClientID == "" {
+			return nil, false
+		}
+		scopes := oidc.Scopes
+		if len(scopes) == 0 {
+			scopes = []string{"openid", "profile", "email"}
+		}
+		return &oauthProvider{
+			config: &oauth2.Config{
+				ClientID:     oidc.ClientID,
+				ClientSecret: oidc.ClientSecret,
+				RedirectURL:  oidc.RedirectURI,
+				Scopes:       scopes,
+				Endpoint: oauth2.Endpoint{
+					AuthURL:  oidc.AuthURL,
+					TokenURL: oidc.TokenURL,
+				},
+			},
+			fetchUser: func(client *http.Client) (User, error) {
+				return fetchOIDCUser(client, oidc.UserInfoURL)
+			},
+		}, true
+	}
+	return nil, false
+}
+
+func fetchDiscordUser(client *http.Client) (User, error) {
+	var discordUser struct {
+		ID       string `json:"id"`
+		Username string `json:"username"`
+	}
+	if err := getUserInfo(client, "https://discord.com/api/users/@me", &discordUser); err != nil {
+		return User{}, err
+	}
+	if discordUser.ID == "" {
+		return User{}, fmt.Errorf("discord user has no id")
+	}
+	return User{ID: discordUser.ID, Username: discordUser.Username}, nil
+}
+
+// fetchOIDCUser reads the standard OpenID Connect userinfo claims. IDs are
+// prefixed with "oidc:" so they can't collide with Discord IDs.
+func fetchOIDCUser(client *http.Client, url string) (User, error) {
+	var claims struct {
+		Sub               string `json:"sub"`
+		PreferredUsername string `json:"preferred_username"`
+		Name              string `json:"name"`
+		Email             string `json:"email"`
+	}
+	if err := getUserInfo(client, url, &claims); err != nil {
+		return User{}, err
+	}
+	if claims.Sub == "" {
+		return User{}, fmt.Errorf("userinfo has no sub claim")
+	}
+	username := claims.PreferredUsername
+	for _, fallback := range []string{claims.Name, claims.Email, claims.Sub} {
+		if username != "" {
+			break
+		}
+		username = fallback
+	}
+	return User{ID: "oidc:" + claims.Sub, Username: username}, nil
+}
+
+func getUserInfo(client *http.Client, url string, out any) error {
+	resp, err := client.Get(url)
+	if err != nil {
+		return err
+	}
+	defer resp.Body.Close()
+	if resp.StatusCode != http.StatusOK {
+		return fmt.Errorf("user info request failed with status %d", resp.StatusCode)
+	}
+	return json.NewDecoder(resp.Body).Decode(out)
+}
+
+// OAuthLoginHandler starts a login with an OAuth provider and returns the
+// provider's login page for the client to open.
+func OAuthLoginHandler(cfg *Config, id string) gin.HandlerFunc {
+	return func(c *gin.Context) {
+		provider, ok := oauthProviderFor(cfg, id)
+		if !ok {
+			c.JSON(http.StatusNotFound, gin.H{"error": "login provider not enabled"})
+			return
+		}
+
+		var body struct {
+			Redirect            string `json:"redirect"`
+			CodeChallenge       string `json:"code_challenge"`
+			CodeChallengeMethod string `json:"code_challenge_method"`
+			Password            string `json:"password"`
+		}
+		if err := c.ShouldBindJSON(&body); err != nil {
+			c.JSON(400, gin.H{"error": "invalid request"})
+			return
+		}
+		if body.Redirect == "" {
+			c.JSON(400, gin.H{"error": "missing redirect"})
+			return
+		}
+		// The challenge is the unpadded base64url SHA-256 of the verifier
+		// the client keeps until it exchanges the login code.
+		if body.CodeChallengeMethod != "S256" || len(body.CodeChallenge) != 43 {
+			c.JSON(400, gin.H{"error": "invalid code challenge"})
+			return
+		}
+
+		if cfg.LoginPasswordRequired() {
+			if body.Password == "" || body.Password != cfg.LoginPassword() {
+				c.JSON(http.StatusUnauthorized, gin.H{"error": "invalid access password"})
+				return
+			}
+		}
+
+		nonce := loginChallenges.Create(loginChallengeTTL, loginChallenge{
+			provider:      id,
+			codeChallenge: body.CodeChallenge,
+		})
+		state := nonce + "|" + body.Redirect
+		c.JSON(http.StatusOK, gin.H{"url": provider.config.AuthCodeURL(state)})
+	}
+}
+
+// OAuthCallbackHandler is where the provider sends the browser back to
+// after login. It creates the user and redirects to the client with a
+// one-time code.
+func OAuthCallbackHandler(store *Store, cfg *Config, id string) gin.HandlerFunc {
+	return func(c *gin.Context) {
+		provider, ok := oauthProviderFor(cfg, id)
+		if !ok {
+			c.JSON(http.StatusNotFound, gin.H{"error": "login provider not enabled"})
+			return
+		}
+
+		code := c.Query("code")
+		state := c.Query("state")
+
+		if code == "" || state == "" {
+			c.JSON(400, gin.H{"error": "missing code/state"})
+			return
+		}
+
+		// Extract redirect from state
+		parts := strings.SplitN(state, "|", 2)
+		if len(parts) != 2 {
+			c.JSON(400, gin.H{"error": "invalid state"})
+			return
+		}
+		nonce := parts[0]
+		redirect := parts[1]
+		challenge, ok := loginChallenges.Consume(nonce)
+		if !ok || challenge.provider != id {
+			c.JSON(http.StatusUnauthorized, gin.H{"error": "invalid login request"})
+			return
+		}
+
+		token, err := provider.config.Exchange(context.Background(), code)
+		if err != nil {
+			c.JSON(500, gin.H{"error": "token exchange failed"})
+			return
+		}
+
+		user, err := provider.fetchUser(provider.config.Client(context.Background(), token))
+		if err != nil {
+			c.JSON(500, gin.H{"error": "user lookup failed"})
+			return
+		}
+
+		if err := store.UpsertUser(user); err != nil {
+			c.JSON(500, gin.H{"error": "user save failed"})
+			return
+		}
+
+		// Redirect browser → gui application. Only a short-lived code goes
+		// in the URL; the client trades it for a session with its verifier.
+		loginCode := loginCodes.Create(loginCodeTTL, loginChallenge{
+			provider:      id,
+			codeChallenge: challenge.codeChallenge,
+			userID:        user.ID,
+		})
+		c.Redirect(302, redirect+"?code="+loginCode)
+	}
+}
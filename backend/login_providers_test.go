@@ -0,0 +1,178 @@
+package main
+
+import (
+	"bytes"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"testing"
+	"time"
+)
+
+func postJSON(router http.Handler, path string, body string) *httptest.ResponseRecorder {
+	req := httptest.NewRequest(http.MethodPost, path, bytes.NewBufferString(body))
+	req.Header.Set("Content-Type", "application/json")
+	rec := httptest.NewRecorder()
+	router.ServeHTTP(rec, req)
+	return rec
+}
+
+func TestFeaturesListLoginProviders(t *testing.T) {
+	store := newTestStore(t)
+
+	features := func(cfg *Config) []LoginProvider {
+		t.Helper()
+		router := newTestRouterWithConfig(store, cfg)
+		req := httptest.NewRequest(http.MethodGet, "/features", nil)
+		rec := httptest.NewRecorder()
+		router.ServeHTTP(rec, req)
+		if rec.Code != http.StatusOK {
+			t.Fatalf("expected 200, got %d", rec.Code)
+		}
+		var resp struct {
+			LoginProviders []LoginProvider `json:"login_providers"`
+		}
+		if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+			t.Fatalf("failed to decode response: %v", err)
+		}
+		if resp.LoginProviders == nil {
+			t.Fatalf("expected a list, got %s", rec.Body.String())
+		}
+		return resp.LoginProviders
+	}
+
+	if providers := features(&Config{}); len(providers) != 0 {
+		t.Fatalf("expected no providers, got %v", providers)
+	}
+
+	providers := features(&Config{
+		Discord:       DiscordConfig{ClientID: "discord-client"},
+		OIDC:          OIDCConfig{Name: "Company SSO", ClientID: "oidc-client"},
+		LocalAccounts: LocalAccountsConfig{Enabled: true},
+	})
+	expected := []LoginProvider{
+		{ID: "discord", Name: "Discord", Kind: loginKindRedirect},
+		{ID: "oidc", Name: "Company SSO", Kind: loginKindRedirect},
+		{ID: "local", Name: "Username and password", Kind: loginKindPassword},
+	}
+	if len(providers) != len(expected) {
+		t.Fatalf("expected %v, got %v", expected, providers)
+	}
+	for i := range expected {
+		if providers[i] != expected[i] {
+			t.Fatalf("expected %v, got %v", expected[i], providers[i])
+		}
+	}
+}
+
+func TestDisabledProvidersAreRejected(t *testing.T) {
+	store := newTestStore(t)
+	router := newTestRouter(store)
+
+	challenge := `{"redirect":"http://app","code_challenge":"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa","code_challenge_method":"S256"}`
+	for _, path := range []string{"/auth/discord/login", "/auth/oidc/login"} {
+		if rec := postJSON(router, path, challenge); rec.Code != http.StatusNotFound {
+			t.Fatalf("expected 404 for %s, got %d", path, rec.Code)
+		}
+	}
+	account := `{"username":"tester","password":"longenough"}`
+	for _, path := range []string{"/auth/local/login", "/auth/local/register"} {
+		if rec := postJSON(router, path, account); rec.Code != http.StatusNotFound {
+			t.Fatalf("expected 404 for %s, got %d", path, rec.Code)
+		}
+	}
+}
+
+func TestLocalAccounts(t *testing.T) {
+	store := newTestStore(t)
+	router := newTestRouterWithConfig(store, &Config{
+		LocalAccounts: LocalAccountsConfig{Enabled: true},
+		Access:        AccessConfig{Password: "secret"},
+	})
+
+	register := []struct {
+		name       string
+		body       string
+		expectCode int
+	}{
+		{name: "wrong access password", body: `{"username":"tester","password":"longenough","access_password":"nope"}`, expectCode: http.StatusUnauthorized},
+		{name: "invalid username", body: `{"username":"a b","password":"longenough","access_password":"secret"}`, expectCode: http.StatusBadRequest},
+		{name: "short password", body: `{"username":"tester","password":"short","access_password":"secret"}`, expectCode: http.StatusBadRequest},
+		{name: "ok", body: `{"username":"Tester","password":"longenough","access_password":"secret"}`, expectCode: http.StatusOK},
+		{name: "taken", body: `{"username":"tester","password":"longenough","access_password":"secret"}`, expectCode: http.StatusConflict},
+	}
+	for _, tc := range register {
+		t.Run("register "+tc.name, func(t *testing.T) {
+			if rec := postJSON(router, "/auth/local/register", tc.body); rec.Code != tc.expectCode {
+				t.Fatalf("expected %d, got %d", tc.expectCode, rec.Code)
+			}
+		})
+	}
+
+	if rec := postJSON(router, "/auth/local/login", `{"username":"tester","password":"wrongpass"}`); rec.Code != http.StatusUnauthorized {
+		t.Fatalf("expected 401 for wrong password, got %d", rec.Code)
+	}
+	if rec := postJSON(router, "/auth/local/login", `{"username":"nobody","password":"longenough"}`); rec.Code != http.StatusUnauthorized {
+		t.Fatalf("expected 401 for unknown user, got %d", rec.Code)
+	}
+
+	rec := postJSON(router, "/auth/local/login", `{"username":"tester","password":"longenough"}`)
+	if rec.Code != http.StatusOK {
+		t.Fatalf("expected 200, got %d", rec.Code)
+	}
+	var resp struct {
+		Token string `json:"token"`
+	}
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatalf("failed to decode response: %v", err)
+	}
+	userID, err := store.GetUserFromSession(resp.Token)
+	if err != nil || userID != "local:tester" {
+		t.Fatalf("expected session for local:tester, got %q (%v)", userID, err)
+	}
+	user, err := store.GetUser(userID)
+	if err != nil || user.Username != "Tester" {
+		t.Fatalf("expected user Tester, got %v (%v)", user, err)
+	}
+}
+
+func TestLocalLoginBacksOff(t *testing.T) {
+	store := newTestStore(t)
+	router := newTestRouterWithConfig(store, &Config{
+		LocalAccounts: LocalAccountsConfig{Enabled: true},
+	})
+	if rec := postJSON(router, "/auth/local/register", `{"username":"tester","password":"longenough"}`); rec.Code != http.StatusOK {
+		t.Fatalf("expected 200 registering, got %d", rec.Code)
+	}
+
+	for i := 0; i < localLoginFreeAttempts; i++ {
+		if rec := postJSON(router, "/auth/local/login", `{"username":"Tester","password":"wrongpass"}`); rec.Code != http.StatusUnauthorized {
+			t.Fatalf("expected 401 for attempt %d, got %d", i+1, rec.Code)
+		}
+	}
+	rec := postJSON(router, "/auth/local/login", `{"username":"tester","password":"longenough"}`)
+	if rec.Code != http.StatusTooManyRequests || rec.Header().Get("Retry-After") == "" {
+		t.Fatalf("expected 429 with Retry-After after repeated failures, got %d", rec.Code)
+	}
+	if rec := postJSON(router, "/auth/local/login", `{"username":"other","password":"longenough"}`); rec.Code != http.StatusUnauthorized {
+		t.Fatalf("expected other usernames to be unaffected, got %d", rec.Code)
+	}
+}
+
+func TestLoginBackoffResetsOnSuccess(t *testing.T) {
+	backoff := newLoginBackoff()
+	now := time.Now()
+	for i := 0; i <= localLoginFreeAttempts; i++ {
+		backoff.fail("tester", now)
+	}
+	if wait := backoff.wait("tester", now); wait != 2*localLoginBaseDelay {
+		t.Fatalf("expected the wait to double, got %v", wait)
+	}
+	if wait := backoff.wait("tester", now.Add(localLoginMaxDelay)); wait > 0 {
+		t.Fatalf("expected the wait to run out, got %v", wait)
+	}
+	backoff.reset("tester")
+	if wait := backoff.wait("tester", now); wait > 0 {
+		t.Fatalf("expected no wait after a reset, got %v", wait)
+	}
+}
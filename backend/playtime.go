@@ -21,6 +21,11 @@ func playtimePrefix(userID string) []byte {
 	return []byte("playtime:" + userID + ":")
 }
 
+// Marks a client session as counted, so a retried upload isn't added twice.
+func playtimeSessionKey(userID, appID, sessionID string) []byte {
+	return []byte("playtime_session:" + userID + ":" + appID + ":" + sessionID)
+}
+
 func (s *Store) GetPlaytime(userID, appID string) (*PlaytimeEntry, error) {
 	var entry PlaytimeEntry
 	err := s.db.View(func(txn *badger.Txn) error {
@@ -38,7 +43,9 @@ func (s *Store) GetPlaytime(userID, appID string) (*PlaytimeEntry, error) {
 	return &entry, nil
 }
 
-func (s *Store) AddPlaytime(userID, appID string, seconds int64, endedAt time.Time) (*PlaytimeEntry, error) {
+// AddPlaytime adds a session to the user's total. A non-empty sessionID that
+// was already added leaves the total unchanged.
+func (s *Store) AddPlaytime(userID, appID, sessionID string, seconds int64, endedAt time.Time) (*PlaytimeEntry, error) {
 	if seconds <= 0 {
 		seconds = 0
 	}
@@ -49,12 +56,26 @@ func (s *Store) AddPlaytime(userID, appID string, seconds int64, endedAt time.Ti
 	}
 
 	err := s.db.Update(func(txn *badger.Txn) error {
+		duplicate := false
+		if sessionID != "" {
+			_, err := txn.Get(playtimeSessionKey(userID, appID, sessionID))
+			if err == nil {
+				duplicate = true
+			} else if err != badger.ErrKeyNotFound {
+				return err
+			}
+		}
+
 		item, err := txn.Get(playtimeKey(userID, appID))
 		if err == nil {
 			var current PlaytimeEntry
 			if err := item.Value(func(val []byte) error {
 				return json.Unmarshal(val, &current)
 			}); err == nil {
+				if duplicate {
+					next = current
+					return nil
+				}
 				next.TotalSeconds = current.TotalSeconds + seconds
 				if endedAt.Unix() > current.LastPlayed {
 					next.LastPlayed = endedAt.Unix()
@@ -66,6 +87,12 @@ func (s *Store) AddPlaytime(userID, appID string, seconds int64, endedAt time.Ti
 			return err
 		}
 
+		if sessionID != "" {
+			if err := txn.Set(playtimeSessionKey(userID, appID, sessionID), []byte{}); err != nil {
+				return err
+			}
+		}
+
 		data, err := json.Marshal(next)
 		if err != nil {
 			return err
@@ -7,6 +7,9 @@ import (
 	"github.com/gin-gonic/gin"
 )
 
+// Client session ids are short; anything longer is not one of ours.
+const maxPlaytimeSessionIDLen = 128
+
 func (s *Store) getPlaytimeEp(c *gin.Context) {
 	userID, ok := getUserID(c)
 	if !ok {
@@ -31,19 +34,24 @@ func (s *Store) postPlaytimeEp(c *gin.Context) {
 		return
 	}
 	var body struct {
-		Seconds int64 `json:"seconds"`
-		EndedAt int64 `json:"ended_at"`
+		Seconds   int64  `json:"seconds"`
+		EndedAt   int64  `json:"ended_at"`
+		SessionID string `json:"session_id"`
 	}
 	if err := c.ShouldBindJSON(&body); err != nil || body.Seconds <= 0 {
 		c.JSON(http.StatusBadRequest, gin.H{"error": "invalid playtime payload"})
 		return
 	}
+	if len(body.SessionID) > maxPlaytimeSessionIDLen {
+		c.JSON(http.StatusBadRequest, gin.H{"error": "invalid session id"})
+		return
+	}
 
 	endedAt := time.Now()
 	if body.EndedAt > 0 {
 		endedAt = time.Unix(body.EndedAt, 0)
 	}
-	entry, err := s.AddPlaytime(userID, appID, body.Seconds, endedAt)
+	entry, err := s.AddPlaytime(userID, appID, body.SessionID, body.Seconds, endedAt)
 	if err != nil {
 		c.JSON(http.StatusInternalServerError, gin.H{"error": "playtime update failed"})
 		return
@@ -2,6 +2,7 @@ package main
 
 import (
 	"bytes"
+	"encoding/json"
 	"net/http"
 	"net/http/httptest"
 	"testing"
@@ -77,3 +78,39 @@ func TestPlaytimeEndpointsHandleAuth(t *testing.T) {
 		}
 	})
 }
+
+func TestPlaytimeSessionIsCountedOnce(t *testing.T) {
+	store := newTestStore(t)
+	router := newTestRouter(store)
+	token := newTestSession(t, store, "u1")
+
+	post := func(body string) PlaytimeEntry {
+		t.Helper()
+		req := httptest.NewRequest(http.MethodPost, "/apps/test-app/playtime", bytes.NewBufferString(body))
+		req.Header.Set("Authorization", "Bearer "+token)
+		req.Header.Set("Content-Type", "application/json")
+		rec := httptest.NewRecorder()
+		router.ServeHTTP(rec, req)
+		if rec.Code != http.StatusOK {
+			t.Fatalf("expected 200, got %d", rec.Code)
+		}
+		var entry PlaytimeEntry
+		if err := json.Unmarshal(rec.Body.Bytes(), &entry); err != nil {
+			t.Fatalf("invalid playtime payload: %v", err)
+		}
+		return entry
+	}
+
+	if entry := post(`{"seconds":60,"session_id":"s1"}`); entry.TotalSeconds != 60 {
+		t.Fatalf("expected 60 seconds, got %d", entry.TotalSeconds)
+	}
+	if entry := post(`{"seconds":60,"session_id":"s1"}`); entry.TotalSeconds != 60 {
+		t.Fatalf("expected retried session to be ignored, got %d", entry.TotalSeconds)
+	}
+	if entry := post(`{"seconds":30,"session_id":"s2"}`); entry.TotalSeconds != 90 {
+		t.Fatalf("expected 90 seconds, got %d", entry.TotalSeconds)
+	}
+	if entry := post(`{"seconds":10}`); entry.TotalSeconds != 100 {
+		t.Fatalf("expected upload without session id to count, got %d", entry.TotalSeconds)
+	}
+}
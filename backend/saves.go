@@ -0,0 +1,197 @@
+package main
+
+import (
+	"encoding/hex"
+	"encoding/json"
+	"errors"
+	"io"
+	"net/http"
+	"os"
+	"path/filepath"
+	"strings"
+	"time"
+
+	"github.com/dgraph-io/badger"
+	"github.com/gin-gonic/gin"
+)
+
+// Cloud saves are kept per user and app as saves/<user>/<app>.tar.gz, with
+// the metadata in the store. Only the latest upload is kept.
+var savesDir = "./saves"
+
+// Save archives larger than this are rejected.
+const maxCloudSaveBytes = 256 << 20
+
+type CloudSave struct {
+	AppID      string `json:"app_id"`
+	Hash       string `json:"hash"`
+	Size       int64  `json:"size"`
+	UploadedAt int64  `json:"uploaded_at"`
+	Device     string `json:"device,omitempty"`
+}
+
+func cloudSaveKey(userID, appID string) []byte {
+	return []byte("save:" + userID + ":" + appID)
+}
+
+func cloudSavePath(userID, appID string) string {
+	return filepath.Join(savesDir, userID, appID+".tar.gz")
+}
+
+// isSaveHash accepts the hex SHA-256 digests clients send.
+func isSaveHash(hash string) bool {
+	if len(hash) != 64 {
+		return false
+	}
+	_, err := hex.DecodeString(hash)
+	return err == nil
+}
+
+func (s *Store) GetCloudSave(userID, appID string) (*CloudSave, error) {
+	var save CloudSave
+	err := s.db.View(func(txn *badger.Txn) error {
+		item, err := txn.Get(cloudSaveKey(userID, appID))
+		if err != nil {
+			return err
+		}
+		return item.Value(func(val []byte) error {
+			return json.Unmarshal(val, &save)
+		})
+	})
+	if err != nil {
+		return nil, err
+	}
+	return &save, nil
+}
+
+func (s *Store) PutCloudSave(userID string, save CloudSave) error {
+	return s.db.Update(func(txn *badger.Txn) error {
+		data, err := json.Marshal(save)
+		if err != nil {
+			return err
+		}
+		return txn.Set(cloudSaveKey(userID, save.AppID), data)
+	})
+}
+
+// cloudSaveParams checks the user and app ids, since both end up in a path.
+func cloudSaveParams(c *gin.Context) (string, string, bool) {
+	userID, ok := getUserID(c)
+	if !ok {
+		return "", "", false
+	}
+	appID := c.Param("id")
+	if !isSafeAppID(appID) {
+		c.JSON(http.StatusBadRequest, gin.H{"error": "invalid app id"})
+		return "", "", false
+	}
+	if !isSafeAppID(userID) {
+		c.JSON(http.StatusBadRequest, gin.H{"error": "invalid user id"})
+		return "", "", false
+	}
+	return userID, appID, true
+}
+
+func (s *Store) getCloudSaveEp(c *gin.Context) {
+	userID, appID, ok := cloudSaveParams(c)
+	if !ok {
+		return
+	}
+	save, err := s.GetCloudSave(userID, appID)
+	if errors.Is(err, badger.ErrKeyNotFound) {
+		c.JSON(http.StatusNotFound, gin.H{"error": "no cloud save"})
+		return
+	}
+	if err != nil {
+		c.JSON(http.StatusInternalServerError, gin.H{"error": "cloud save lookup failed"})
+		return
+	}
+	c.JSON(http.StatusOK, save)
+}
+
+func (s *Store) getCloudSaveArchiveEp(c *gin.Context) {
+	userID, appID, ok := cloudSaveParams(c)
+	if !ok {
+		return
+	}
+	file, err := os.Open(cloudSavePath(userID, appID))
+	if err != nil {
+		c.JSON(http.StatusNotFound, gin.H{"error": "no cloud save"})
+		return
+	}
+	defer file.Close()
+
+	stat, err := file.Stat()
+	if err != nil {
+		c.JSON(http.StatusInternalServerError, gin.H{"error": "cloud save not readable"})
+		return
+	}
+	c.Header("Content-Type", "application/gzip")
+	http.ServeContent(c.Writer, c.Request, appID+".tar.gz", stat.ModTime(), file)
+}
+
+// putCloudSaveEp stores a new save archive. X-Save-Hash is the hash of the
+// save's contents and X-Save-Base the hash the client last synced; if the
+// cloud save has moved on since then, both sides changed and the upload is
+// refused with 409 unless force=1 is set.
+func (s *Store) putCloudSaveEp(c *gin.Context) {
+	userID, appID, ok := cloudSaveParams(c)
+	if !ok {
+		return
+	}
+	hash := strings.ToLower(strings.TrimSpace(c.GetHeader("X-Save-Hash")))
+	if !isSaveHash(hash) {
+		c.JSON(http.StatusBadRequest, gin.H{"error": "invalid save hash"})
+		return
+	}
+	base := strings.ToLower(strings.TrimSpace(c.GetHeader("X-Save-Base")))
+
+	current, err := s.GetCloudSave(userID, appID)
+	if err != nil && !errors.Is(err, badger.ErrKeyNotFound) {
+		c.JSON(http.StatusInternalServerError, gin.H{"error": "cloud save lookup failed"})
+		return
+	}
+	if current != nil && current.Hash != hash && current.Hash != base && c.Query("force") != "1" {
+		c.JSON(http.StatusConflict, gin.H{"error": "cloud save changed", "save": current})
+		return
+	}
+
+	if err := os.MkdirAll(filepath.Join(savesDir, userID), 0755); err != nil {
+		c.JSON(http.StatusInternalServerError, gin.H{"error": "failed to prepare saves dir"})
+		return
+	}
+	path := cloudSavePath(userID, appID)
+	partial := path + ".partial"
+	dst, err := os.Create(partial)
+	if err != nil {
+		c.JSON(http.StatusInternalServerError, gin.H{"error": "failed to create save file"})
+		return
+	}
+	size, err := io.Copy(dst, http.MaxBytesReader(c.Writer, c.Request.Body, maxCloudSaveBytes))
+	if closeErr := dst.Close(); err == nil {
+		err = closeErr
+	}
+	if err != nil {
+		_ = os.Remove(partial)
+		c.JSON(http.StatusBadRequest, gin.H{"error": "failed to read save archive"})
+		return
+	}
+	if err := os.Rename(partial, path); err != nil {
+		_ = os.Remove(partial)
+		c.JSON(http.StatusInternalServerError, gin.H{"error": "failed to store save archive"})
+		return
+	}
+
+	save := CloudSave{
+		AppID:      appID,
+		Hash:       hash,
+		Size:       size,
+		UploadedAt: time.Now().Unix(),
+		Device:     strings.TrimSpace(c.GetHeader("X-Save-Device")),
+	}
+	if err := s.PutCloudSave(userID, save); err != nil {
+		c.JSON(http.StatusInternalServerError, gin.H{"error": "cloud save update failed"})
+		return
+	}
+	c.JSON(http.StatusOK, save)
+}
@@ -0,0 +1,107 @@
+package main
+
+import (
+	"bytes"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+)
+
+func putTestSave(router http.Handler, token, appID, hash, base, query, body string) *httptest.ResponseRecorder {
+	req := httptest.NewRequest(http.MethodPut, "/apps/"+appID+"/save"+query, bytes.NewBufferString(body))
+	req.Header.Set("Authorization", "Bearer "+token)
+	req.Header.Set("X-Save-Hash", hash)
+	if base != "" {
+		req.Header.Set("X-Save-Base", base)
+	}
+	rec := httptest.NewRecorder()
+	router.ServeHTTP(rec, req)
+	return rec
+}
+
+func TestCloudSaveRoundTrip(t *testing.T) {
+	store := newTestStore(t)
+	router := newTestRouter(store)
+	token := newTestSession(t, store, "user-1")
+	useTempSavesDir(t)
+
+	req := httptest.NewRequest(http.MethodGet, "/apps/demo/save", nil)
+	req.Header.Set("Authorization", "Bearer "+token)
+	rec := httptest.NewRecorder()
+	router.ServeHTTP(rec, req)
+	if rec.Code != http.StatusNotFound {
+		t.Fatalf("expected 404 before any upload, got %d", rec.Code)
+	}
+
+	hash := strings.Repeat("a", 64)
+	rec = putTestSave(router, token, "demo", hash, "", "", "save-archive")
+	if rec.Code != http.StatusOK {
+		t.Fatalf("expected 200 on upload, got %d: %s", rec.Code, rec.Body.String())
+	}
+
+	req = httptest.NewRequest(http.MethodGet, "/apps/demo/save", nil)
+	req.Header.Set("Authorization", "Bearer "+token)
+	rec = httptest.NewRecorder()
+	router.ServeHTTP(rec, req)
+	var save CloudSave
+	if err := json.Unmarshal(rec.Body.Bytes(), &save); err != nil {
+		t.Fatalf("invalid save payload: %v", err)
+	}
+	if save.Hash != hash || save.Size != int64(len("save-archive")) {
+		t.Fatalf("unexpected save metadata: %+v", save)
+	}
+
+	req = httptest.NewRequest(http.MethodGet, "/apps/demo/save/archive", nil)
+	req.Header.Set("Authorization", "Bearer "+token)
+	rec = httptest.NewRecorder()
+	router.ServeHTTP(rec, req)
+	if rec.Code != http.StatusOK || rec.Body.String() != "save-archive" {
+		t.Fatalf("unexpected archive response %d: %q", rec.Code, rec.Body.String())
+	}
+
+	other := newTestSession(t, store, "user-2")
+	req = httptest.NewRequest(http.MethodGet, "/apps/demo/save", nil)
+	req.Header.Set("Authorization", "Bearer "+other)
+	rec = httptest.NewRecorder()
+	router.ServeHTTP(rec, req)
+	if rec.Code != http.StatusNotFound {
+		t.Fatalf("expected saves to be per user, got %d", rec.Code)
+	}
+}
+
+func TestCloudSaveRejectsConflictingUpload(t *testing.T) {
+	store := newTestStore(t)
+	router := newTestRouter(store)
+	token := newTestSession(t, store, "user-1")
+	useTempSavesDir(t)
+
+	first := strings.Repeat("a", 64)
+	second := strings.Repeat("b", 64)
+	third := strings.Repeat("c", 64)
+	if rec := putTestSave(router, token, "demo", first, "", "", "one"); rec.Code != http.StatusOK {
+		t.Fatalf("expected 200, got %d", rec.Code)
+	}
+	if rec := putTestSave(router, token, "demo", second, first, "", "two"); rec.Code != http.StatusOK {
+		t.Fatalf("expected 200 for an upload based on the cloud save, got %d", rec.Code)
+	}
+
+	rec := putTestSave(router, token, "demo", third, first, "", "three")
+	if rec.Code != http.StatusConflict {
+		t.Fatalf("expected 409 for a stale base, got %d", rec.Code)
+	}
+	var conflict struct {
+		Save CloudSave `json:"save"`
+	}
+	if err := json.Unmarshal(rec.Body.Bytes(), &conflict); err != nil || conflict.Save.Hash != second {
+		t.Fatalf("expected the current save in the conflict, got %s", rec.Body.String())
+	}
+
+	if rec := putTestSave(router, token, "demo", third, first, "?force=1", "three"); rec.Code != http.StatusOK {
+		t.Fatalf("expected forced upload to succeed, got %d", rec.Code)
+	}
+	if rec := putTestSave(router, token, "demo", "not-a-hash", "", "", "x"); rec.Code != http.StatusBadRequest {
+		t.Fatalf("expected 400 for an invalid hash, got %d", rec.Code)
+	}
+}
@@ -12,20 +12,13 @@ func (s *Store) socialWsEp(c *gin.Context, hub *SocialHub) {
 		return
 	}
 
-	token := c.Query("token")
-	if token == "" {
-		c.JSON(http.StatusUnauthorized, gin.H{"error": "missing token"})
-		return
-	}
-
-	userID, err := s.GetUserFromSession(token)
+	conn, err := chatWsUpgrader.Upgrade(c.Writer, c.Request, nil)
 	if err != nil {
-		c.JSON(http.StatusUnauthorized, gin.H{"error": "invalid session"})
 		return
 	}
 
-	conn, err := chatWsUpgrader.Upgrade(c.Writer, c.Request, nil)
-	if err != nil {
+	userID, ok := s.authenticateWs(conn)
+	if !ok {
 		return
 	}
 
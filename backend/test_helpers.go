@@ -21,9 +21,47 @@ func newTestStore(t *testing.T) *Store {
 }
 
 func newTestRouter(store *Store) *gin.Engine {
+	return newTestRouterWithConfig(store, &Config{})
+}
+
+func newTestRouterWithConfig(store *Store, cfg *Config) *gin.Engine {
 	gin.SetMode(gin.TestMode)
 	router := gin.New()
-	cfg := &Config{}
 	RegisterRoutes(router, store, cfg)
 	return router
 }
+
+func newTestSession(t *testing.T, store *Store, userID string) string {
+	t.Helper()
+
+	if err := store.UpsertUser(User{ID: userID, Username: userID}); err != nil {
+		t.Fatalf("failed to upsert user: %v", err)
+	}
+	token, err := store.CreateSession(userID, 0)
+	if err != nil {
+		t.Fatalf("failed to create session: %v", err)
+	}
+	return token
+}
+
+// useTempAppsDir points app storage at a per-test directory.
+func useTempAppsDir(t *testing.T) {
+	t.Helper()
+
+	previous := appsDir
+	appsDir = t.TempDir()
+	t.Cleanup(func() {
+		appsDir = previous
+	})
+}
+
+// useTempSavesDir points cloud save storage at a per-test directory.
+func useTempSavesDir(t *testing.T) {
+	t.Helper()
+
+	previous := savesDir
+	savesDir = t.TempDir()
+	t.Cleanup(func() {
+		savesDir = previous
+	})
+}
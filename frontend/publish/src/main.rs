@@ -0,0 +1,270 @@
+use std::{
+    io::Write,
+    path::PathBuf,
+    process::ExitCode,
+    sync::{Arc, Mutex},
+};
+
+use base64::{engine::general_purpose, Engine as _};
+use gaggle_shared::{
+    net::build_http_url_checked,
+    packaging::{
+        archive_body, build_archive, dir_total_size, ProgressCallback, UploadProgressState,
+    },
+};
+use tokio_util::sync::CancellationToken;
+
+mod manifest;
+
+use manifest::Manifest;
+
+const USAGE: &str = "\
+Usage: gaggle-publish --server <address> [options] [folder]
+
+Packages an app folder and uploads it to a Gaggle server.
+
+Options:
+  -s, --server <address>   Server address (host, host:port or URL)
+  -p, --port <port>        Server port when the address has none [default: 2121]
+  -t, --token <token>      Admin session token [env: GAGGLE_ADMIN_TOKEN]
+  -m, --manifest <path>    App manifest [default: gaggle.toml]
+  -h, --help               Show this help
+
+The manifest is a TOML file with id, name, description, version and
+executable. An optional `folder` key points at the folder to package,
+relative to the manifest; a folder given on the command line wins.";
+
+struct Args {
+    server: String,
+    port: String,
+    token: String,
+    manifest: PathBuf,
+    folder: Option<PathBuf>,
+}
+
+fn parse_args() -> Result<Option<Args>, String> {
+    let mut server = None;
+    let mut port = "2121".to_string();
+    let mut token = std::env::var("GAGGLE_ADMIN_TOKEN").ok();
+    let mut manifest = PathBuf::from("gaggle.toml");
+    let mut folder = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .ok_or_else(|| format!("Missing value for {}.", name))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-s" | "--server" => server = Some(value("--server")?),
+            "-p" | "--port" => port = value("--port")?,
+            "-t" | "--token" => token = Some(value("--token")?),
+            "-m" | "--manifest" => manifest = PathBuf::from(value("--manifest")?),
+            other if other.starts_with('-') => {
+                return Err(format!("Unknown option {}.", other));
+            }
+            other => {
+                if folder.is_some() {
+                    return Err("Only one folder can be published at a time.".to_string());
+                }
+                folder = Some(PathBuf::from(other));
+            }
+        }
+    }
+
+    let server = server.ok_or_else(|| "Missing --server.".to_string())?;
+    let token = token
+        .filter(|t| !t.trim().is_empty())
+        .ok_or_else(|| "Missing admin token (--token or GAGGLE_ADMIN_TOKEN).".to_string())?;
+
+    Ok(Some(Args {
+        server,
+        port,
+        token,
+        manifest,
+        folder,
+    }))
+}
+
+/// Renders a single-line progress bar on stderr, redrawing only when the
+/// whole percentage changes.
+struct ProgressLine {
+    stage: &'static str,
+    last_pct: Option<u64>,
+}
+
+impl ProgressLine {
+    fn draw(&mut self, sent: u64, total: u64) {
+        let pct = (sent.saturating_mul(100) / total.max(1)).min(100);
+        if self.last_pct == Some(pct) {
+            return;
+        }
+        self.last_pct = Some(pct);
+        let filled = (pct / 4) as usize;
+        let mut stderr = std::io::stderr();
+        let _ = write!(
+            stderr,
+            "\r{:<12} [{}{}] {:>3}%  {} / {}",
+            self.stage,
+            "#".repeat(filled),
+            "-".repeat(25 - filled),
+            pct,
+            format_bytes(sent),
+            format_bytes(total)
+        );
+        let _ = stderr.flush();
+    }
+
+    fn finish(&mut self) {
+        if self.last_pct.is_some() {
+            eprintln!();
+        }
+    }
+}
+
+fn progress_callback(line: &Arc<Mutex<ProgressLine>>) -> ProgressCallback {
+    let line = line.clone();
+    Arc::new(move |sent, total| line.lock().unwrap().draw(sent, total))
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+async fn publish(args: Args) -> Result<(), String> {
+    let manifest = Manifest::load(&args.manifest)?;
+    let folder = manifest.resolve_folder(&args.manifest, args.folder.clone());
+    if !folder.is_dir() {
+        return Err(format!("Folder not found: {}", folder.display()));
+    }
+    let config_toml = manifest.config_toml()?;
+    let id = manifest.id.trim().to_string();
+    let url = build_http_url_checked(
+        &args.server,
+        &args.port,
+        &format!("/admin/apps/upload/{}", id),
+    )?;
+
+    eprintln!(
+        "Publishing {} ({}) from {}",
+        manifest.name.trim(),
+        id,
+        folder.display()
+    );
+
+    let temp_dir = std::env::temp_dir().join(format!("gaggle_publish_{}", id));
+    if temp_dir.exists() {
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
+    std::fs::create_dir_all(&temp_dir).map_err(|_| "Failed to prepare temp folder.".to_string())?;
+    let archive_path = temp_dir.join(format!("{}.tar.gz", id));
+
+    let result = async {
+        let cancel = CancellationToken::new();
+
+        let line = Arc::new(Mutex::new(ProgressLine {
+            stage: "Compressing",
+            last_pct: None,
+        }));
+        let total_size = dir_total_size(&folder)?;
+        let progress_state = Arc::new(Mutex::new(UploadProgressState::new(total_size)));
+        {
+            let on_progress = progress_callback(&line);
+            let folder = folder.clone();
+            let archive_path = archive_path.clone();
+            let cancel = cancel.clone();
+            tokio::task::spawn_blocking(move || {
+                build_archive(
+                    &folder,
+                    &archive_path,
+                    &progress_state,
+                    &on_progress,
+                    &cancel,
+                )
+            })
+            .await
+            .map_err(|_| "Compression task panicked.".to_string())??;
+        }
+        {
+            let mut line = line.lock().unwrap();
+            line.draw(total_size.max(1), total_size.max(1));
+            line.finish();
+        }
+
+        let line = Arc::new(Mutex::new(ProgressLine {
+            stage: "Uploading",
+            last_pct: None,
+        }));
+        let body = archive_body(&archive_path, progress_callback(&line), cancel).await?;
+        let resp = reqwest::Client::new()
+            .put(url)
+            .bearer_auth(args.token.trim())
+            .header(
+                "X-App-Config",
+                general_purpose::STANDARD.encode(&config_toml),
+            )
+            .header("Content-Type", "application/octet-stream")
+            .body(body)
+            .send()
+            .await;
+        line.lock().unwrap().finish();
+        let resp = resp.map_err(|e| format!("Upload failed: {}", e))?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let detail = resp
+                .bytes()
+                .await
+                .ok()
+                .and_then(|body| serde_json::from_slice::<serde_json::Value>(&body).ok())
+                .and_then(|v| v.get("error").and_then(|e| e.as_str()).map(str::to_string));
+            return Err(match detail {
+                Some(detail) => format!("Upload failed (HTTP {}): {}", status, detail),
+                None => format!("Upload failed (HTTP {}).", status),
+            });
+        }
+        Ok(())
+    }
+    .await;
+
+    std::fs::remove_dir_all(&temp_dir).ok();
+    result
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match publish(args).await {
+        Ok(()) => {
+            eprintln!("Published.");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
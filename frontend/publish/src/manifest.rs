@@ -0,0 +1,140 @@
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+/// App manifest read by `gaggle-publish`. Everything except `id` and
+/// `folder` ends up in the app config uploaded alongside the archive, so
+/// extra keys (launch profiles, hooks, ...) pass through untouched.
+#[derive(Deserialize)]
+pub struct Manifest {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub executable: Option<String>,
+    /// Folder to package, relative to the manifest file.
+    #[serde(default)]
+    pub folder: Option<PathBuf>,
+    #[serde(flatten)]
+    pub extra: toml::Table,
+}
+
+impl Manifest {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let raw = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read manifest {}: {}", path.display(), e))?;
+        let manifest: Manifest = toml::from_str(&raw)
+            .map_err(|e| format!("Invalid manifest {}: {}", path.display(), e))?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let id = self.id.trim();
+        if id.is_empty() {
+            return Err("Manifest is missing an app id.".to_string());
+        }
+        if id.contains('/') || id.contains('\\') || id.contains("..") {
+            return Err(format!("App id \"{}\" is not safe.", id));
+        }
+        if self.name.trim().is_empty() {
+            return Err("Manifest is missing an app name.".to_string());
+        }
+        Ok(())
+    }
+
+    /// Folder to package: the CLI override wins, then `folder` from the
+    /// manifest, then the manifest's own directory.
+    pub fn resolve_folder(&self, manifest_path: &Path, cli_folder: Option<PathBuf>) -> PathBuf {
+        if let Some(folder) = cli_folder {
+            return folder;
+        }
+        let base = manifest_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        match &self.folder {
+            Some(folder) => base.join(folder),
+            None => base,
+        }
+    }
+
+    /// The app config TOML the server stores next to the archive.
+    pub fn config_toml(&self) -> Result<String, String> {
+        let mut table = self.extra.clone();
+        table.insert("name".to_string(), self.name.trim().into());
+        for (key, value) in [
+            ("description", &self.description),
+            ("version", &self.version),
+            ("executable", &self.executable),
+        ] {
+            if let Some(value) = value.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+                table.insert(key.to_string(), value.into());
+            }
+        }
+        toml::to_string(&table).map_err(|_| "Failed to encode app config.".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(raw: &str) -> Result<Manifest, String> {
+        let manifest: Manifest = toml::from_str(raw).map_err(|e| e.to_string())?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    #[test]
+    fn rejects_missing_or_unsafe_ids() {
+        for id in ["", "  ", "a/b", "a\\b", "..", "up..dir"] {
+            let raw = format!("id = {:?}\nname = \"Demo\"\n", id);
+            assert!(parse(&raw).is_err(), "{id:?} was accepted");
+        }
+        assert!(parse("id = \"demo\"\nname = \" \"\n").is_err());
+        assert!(parse("id = \"demo\"\nname = \"Demo\"\n").is_ok());
+    }
+
+    #[test]
+    fn folder_prefers_the_cli_override() {
+        let manifest = parse("id = \"demo\"\nname = \"Demo\"\nfolder = \"build\"\n").unwrap();
+        let path = Path::new("project").join("gaggle.toml");
+        assert_eq!(
+            manifest.resolve_folder(&path, Some(PathBuf::from("elsewhere"))),
+            PathBuf::from("elsewhere")
+        );
+        assert_eq!(
+            manifest.resolve_folder(&path, None),
+            Path::new("project").join("build")
+        );
+
+        let manifest = parse("id = \"demo\"\nname = \"Demo\"\n").unwrap();
+        assert_eq!(
+            manifest.resolve_folder(&path, None),
+            PathBuf::from("project")
+        );
+    }
+
+    #[test]
+    fn config_keeps_extra_keys_and_drops_publish_ones() {
+        let manifest = parse(
+            "id = \"demo\"\nname = \" Demo \"\nversion = \"1.2\"\ndescription = \"\"\n\
+             folder = \"build\"\ntrack_process = \"game.exe\"\n\n\
+             [[profiles]]\nname = \"Editor\"\nexecutable = \"editor.exe\"\n",
+        )
+        .unwrap();
+        let config: toml::Table = toml::from_str(&manifest.config_toml().unwrap()).unwrap();
+
+        assert_eq!(config["name"].as_str(), Some("Demo"));
+        assert_eq!(config["version"].as_str(), Some("1.2"));
+        assert_eq!(config["track_process"].as_str(), Some("game.exe"));
+        assert_eq!(config["profiles"][0]["name"].as_str(), Some("Editor"));
+        for key in ["id", "folder", "description", "executable"] {
+            assert!(!config.contains_key(key), "{key} was kept");
+        }
+    }
+}
@@ -0,0 +1,4 @@
+pub mod net;
+
+#[cfg(feature = "packaging")]
+pub mod packaging;
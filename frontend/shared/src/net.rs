@@ -0,0 +1,420 @@
+use std::{fmt, net::Ipv6Addr};
+
+/// Port the backend listens on unless configured otherwise.
+pub const DEFAULT_PORT: &str = "2121";
+
+/// Where a Gaggle server lives: scheme, host, port and the path prefix it is
+/// served under when it sits behind a reverse proxy.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ServerAddress {
+    secure: bool,
+    /// Lowercased; IPv6 literals are kept without brackets.
+    host: String,
+    port: u16,
+    /// Empty, or starts with `/` and has no trailing `/`.
+    path: String,
+}
+
+impl ServerAddress {
+    /// Parses a server address as typed by a player: a URL
+    /// (`https://host/gaggle`), `host:port`, `[::1]:2121` or a bare host.
+    ///
+    /// An explicit scheme is trusted and uses its own default port. Without a
+    /// scheme, the address is reached over plain HTTP on its port or
+    /// `default_port` (HTTPS if that port is 443). With no port anywhere, a
+    /// bare host is reached over HTTPS on 443.
+    pub fn parse(input: &str, default_port: &str) -> Result<Self, String> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Err("Server address is empty.".to_string());
+        }
+
+        let lower = input.to_ascii_lowercase();
+        let (scheme, rest) = if let Some(rest) = strip_scheme(&lower, input, "https") {
+            (Some(true), rest)
+        } else if let Some(rest) = strip_scheme(&lower, input, "http") {
+            (Some(false), rest)
+        } else if input.contains("://") {
+            return Err(format!("Unsupported server URL: {}", input));
+        } else {
+            (None, input)
+        };
+
+        if rest.contains(['?', '#']) {
+            return Err("Server address can't contain a query or fragment.".to_string());
+        }
+        let (authority, path) = match rest.find('/') {
+            Some(index) => rest.split_at(index),
+            None => (rest, ""),
+        };
+        if authority.contains('@') {
+            return Err("Server address can't contain credentials.".to_string());
+        }
+        let (host, port) = split_host_port(authority)?;
+
+        let (secure, port) = match (scheme, port) {
+            (Some(secure), Some(port)) => (secure, port),
+            (Some(secure), None) => (secure, if secure { 443 } else { 80 }),
+            (None, Some(port)) => (port == 443, port),
+            (None, None) => match default_port.trim() {
+                "" => (true, 443),
+                port => {
+                    let port = parse_port(port)?;
+                    (port == 443, port)
+                }
+            },
+        };
+
+        let path = path.split('/').filter(|segment| !segment.is_empty()).fold(
+            String::new(),
+            |mut acc, segment| {
+                acc.push('/');
+                acc.push_str(segment);
+                acc
+            },
+        );
+        if path.chars().any(char::is_whitespace) {
+            return Err("Server path can't contain spaces.".to_string());
+        }
+
+        Ok(Self {
+            secure,
+            host,
+            port,
+            path,
+        })
+    }
+
+    pub fn is_secure(&self) -> bool {
+        self.secure
+    }
+
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Path prefix in front of every API path, e.g. `/gaggle`.
+    pub fn path_prefix(&self) -> &str {
+        &self.path
+    }
+
+    pub fn http_url(&self, path: &str) -> String {
+        self.url(if self.secure { "https" } else { "http" }, path)
+    }
+
+    pub fn ws_url(&self, path: &str) -> String {
+        self.url(if self.secure { "wss" } else { "ws" }, path)
+    }
+
+    fn url(&self, scheme: &str, path: &str) -> String {
+        format!(
+            "{}://{}{}/{}",
+            scheme,
+            self.authority(),
+            self.path,
+            path.trim_start_matches('/')
+        )
+    }
+
+    fn authority(&self) -> String {
+        let host = if self.host.contains(':') {
+            format!("[{}]", self.host)
+        } else {
+            self.host.clone()
+        };
+        let default_port = if self.secure { 443 } else { 80 };
+        if self.port == default_port {
+            host
+        } else {
+            format!("{}:{}", host, self.port)
+        }
+    }
+}
+
+/// The canonical base URL, which parses back to the same address.
+impl fmt::Display for ServerAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scheme = if self.secure { "https" } else { "http" };
+        write!(f, "{}://{}{}", scheme, self.authority(), self.path)
+    }
+}
+
+/// Strips `scheme://` (any case), also accepting the `http//` typo.
+fn strip_scheme<'a>(lower: &str, input: &'a str, scheme: &str) -> Option<&'a str> {
+    [format!("{}://", scheme), format!("{}//", scheme)]
+        .iter()
+        .find(|prefix| lower.starts_with(prefix.as_str()))
+        .map(|prefix| &input[prefix.len()..])
+}
+
+fn split_host_port(authority: &str) -> Result<(String, Option<u16>), String> {
+    let invalid = || format!("Invalid server address: {}", authority);
+    if let Some(rest) = authority.strip_prefix('[') {
+        let (host, after) = rest.split_once(']').ok_or_else(invalid)?;
+        let host: Ipv6Addr = host.parse().map_err(|_| invalid())?;
+        let port = match after {
+            "" => None,
+            after => Some(parse_port(after.strip_prefix(':').ok_or_else(invalid)?)?),
+        };
+        return Ok((host.to_string(), port));
+    }
+    // More than one colon without brackets only makes sense as an IPv6
+    // literal with no port.
+    if authority.matches(':').count() > 1 {
+        let host: Ipv6Addr = authority.parse().map_err(|_| invalid())?;
+        return Ok((host.to_string(), None));
+    }
+    let (host, port) = match authority.split_once(':') {
+        Some((host, port)) => (host, Some(parse_port(port)?)),
+        None => (authority, None),
+    };
+    let valid = !host.is_empty()
+        && host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_'));
+    if !valid {
+        return Err(invalid());
+    }
+    Ok((host.to_ascii_lowercase(), port))
+}
+
+fn parse_port(port: &str) -> Result<u16, String> {
+    match port.trim().parse::<u16>() {
+        Ok(port) if port != 0 => Ok(port),
+        _ => Err(format!("Invalid server port: {}", port)),
+    }
+}
+
+/// Identifies a server by its canonical base URL, so host and port both
+/// count. Addresses that don't parse are kept as typed.
+pub fn server_key(server_ip: &str, server_port: &str) -> String {
+    ServerAddress::parse(server_ip, server_port)
+        .map(|server| server.to_string())
+        .unwrap_or_else(|_| server_ip.trim().to_string())
+}
+
+pub fn build_http_url(server_ip: &str, server_port: &str, path: &str) -> String {
+    match build_http_url_checked(server_ip, server_port, path) {
+        Ok(url) => url,
+        Err(err) => {
+            eprintln!("URL build error: {}", err);
+            "http://localhost/".to_string()
+        }
+    }
+}
+
+pub fn build_http_url_checked(
+    server_ip: &str,
+    server_port: &str,
+    path: &str,
+) -> Result<String, String> {
+    ServerAddress::parse(server_ip, server_port).map(|address| address.http_url(path))
+}
+
+pub fn build_ws_url(server_ip: &str, server_port: &str, path: &str) -> String {
+    match ServerAddress::parse(server_ip, server_port) {
+        Ok(address) => address.ws_url(path),
+        Err(err) => {
+            eprintln!("URL build error: {}", err);
+            "ws://localhost/".to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> ServerAddress {
+        ServerAddress::parse(input, DEFAULT_PORT).unwrap()
+    }
+
+    #[test]
+    fn explicit_scheme_uses_its_default_port() {
+        let address = parse("https://Gaggle.Example.com");
+        assert!(address.is_secure());
+        assert_eq!(address.host(), "gaggle.example.com");
+        assert_eq!(address.port(), 443);
+        assert_eq!(address.to_string(), "https://gaggle.example.com");
+
+        let address = parse("HTTP://example.com/");
+        assert!(!address.is_secure());
+        assert_eq!(address.port(), 80);
+        assert_eq!(address.to_string(), "http://example.com");
+    }
+
+    #[test]
+    fn explicit_scheme_keeps_its_port() {
+        let address = parse("https://example.com:8443");
+        assert_eq!(address.port(), 8443);
+        assert_eq!(address.http_url("ping"), "https://example.com:8443/ping");
+        assert_eq!(address.ws_url("chat/ws"), "wss://example.com:8443/chat/ws");
+    }
+
+    #[test]
+    fn fixes_missing_colon_in_scheme() {
+        assert_eq!(
+            parse("http//localhost:2121").to_string(),
+            "http://localhost:2121"
+        );
+        assert_eq!(
+            parse("https//example.com").to_string(),
+            "https://example.com"
+        );
+    }
+
+    #[test]
+    fn host_without_scheme_uses_default_port() {
+        let address = parse("192.168.1.20");
+        assert!(!address.is_secure());
+        assert_eq!(address.port(), 2121);
+        assert_eq!(address.http_url("apps"), "http://192.168.1.20:2121/apps");
+
+        let address = ServerAddress::parse("example.com", "8080").unwrap();
+        assert_eq!(address.http_url("apps"), "http://example.com:8080/apps");
+    }
+
+    #[test]
+    fn host_without_scheme_or_port_falls_back_to_https() {
+        let address = ServerAddress::parse("example.com", "").unwrap();
+        assert!(address.is_secure());
+        assert_eq!(address.to_string(), "https://example.com");
+    }
+
+    #[test]
+    fn host_with_port_overrides_default_port() {
+        let address = parse("localhost:3000");
+        assert!(!address.is_secure());
+        assert_eq!(address.port(), 3000);
+        assert_eq!(address.to_string(), "http://localhost:3000");
+
+        assert!(parse("example.com:443").is_secure());
+    }
+
+    #[test]
+    fn keeps_reverse_proxy_path_prefix() {
+        let address = parse("https://example.com/gaggle/");
+        assert_eq!(address.path_prefix(), "/gaggle");
+        assert_eq!(address.http_url("/apps"), "https://example.com/gaggle/apps");
+        assert_eq!(
+            address.ws_url("social/ws?token=abc"),
+            "wss://example.com/gaggle/social/ws?token=abc"
+        );
+
+        let address = parse("example.com:8080//tools//gaggle");
+        assert_eq!(address.path_prefix(), "/tools/gaggle");
+        assert_eq!(
+            address.http_url("features"),
+            "http://example.com:8080/tools/gaggle/features"
+        );
+    }
+
+    #[test]
+    fn parses_bracketed_ipv6() {
+        let address = parse("[::1]:2121");
+        assert_eq!(address.host(), "::1");
+        assert_eq!(address.port(), 2121);
+        assert_eq!(address.http_url("ping"), "http://[::1]:2121/ping");
+        assert_eq!(address.ws_url("chat/ws"), "ws://[::1]:2121/chat/ws");
+
+        let address = parse("https://[2001:DB8::1]/gaggle");
+        assert_eq!(address.host(), "2001:db8::1");
+        assert_eq!(address.port(), 443);
+        assert_eq!(address.to_string(), "https://[2001:db8::1]/gaggle");
+
+        let address = parse("[fe80::1]");
+        assert_eq!(address.http_url("ping"), "http://[fe80::1]:2121/ping");
+    }
+
+    #[test]
+    fn parses_bare_ipv6_without_port() {
+        let address = parse("fe80::1");
+        assert_eq!(address.host(), "fe80::1");
+        assert_eq!(address.port(), 2121);
+        assert_eq!(address.to_string(), "http://[fe80::1]:2121");
+
+        let address = parse("http://::1");
+        assert_eq!(address.host(), "::1");
+        assert_eq!(address.port(), 80);
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for input in [
+            "https://example.com",
+            "http://localhost:2121",
+            "https://example.com:8443/gaggle",
+            "http://[::1]:2121/a/b",
+            "example.com",
+            "10.0.0.5:9000",
+        ] {
+            let address = parse(input);
+            assert_eq!(parse(&address.to_string()), address, "{}", input);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_addresses() {
+        for input in [
+            "",
+            "   ",
+            "ftp://example.com",
+            "https://",
+            "example.com:0",
+            "example.com:70000",
+            "example.com:abc",
+            "[::1",
+            "[::1]x",
+            "[not-ipv6]:2121",
+            "fe80::zz",
+            "user@example.com",
+            "https://example.com/?x=1",
+            "https://example.com/#top",
+            "exa mple.com",
+            "https://example.com/my gaggle",
+        ] {
+            assert!(
+                ServerAddress::parse(input, DEFAULT_PORT).is_err(),
+                "{:?} should be rejected",
+                input
+            );
+        }
+        assert!(ServerAddress::parse("example.com", "nope").is_err());
+    }
+
+    #[test]
+    fn legacy_helpers_use_the_same_rules() {
+        assert_eq!(
+            build_http_url("https://example.com/gaggle", DEFAULT_PORT, "apps"),
+            "https://example.com/gaggle/apps"
+        );
+        assert_eq!(
+            build_ws_url("[::1]:4000", DEFAULT_PORT, "/social/ws"),
+            "ws://[::1]:4000/social/ws"
+        );
+        assert_eq!(
+            build_http_url_checked("localhost", "8080", "ping").unwrap(),
+            "http://localhost:8080/ping"
+        );
+        assert!(build_http_url_checked("", DEFAULT_PORT, "ping").is_err());
+    }
+
+    #[test]
+    fn server_key_tells_ports_apart() {
+        assert_eq!(server_key("example.com", "2121"), "http://example.com:2121");
+        assert_ne!(
+            server_key("example.com", "2121"),
+            server_key("example.com", "2122")
+        );
+        // A canonical base URL is its own key.
+        assert_eq!(
+            server_key("http://example.com:2121", "9999"),
+            "http://example.com:2121"
+        );
+        assert_eq!(server_key(" not a host ", "2121"), "not a host");
+    }
+}
@@ -0,0 +1,336 @@
+use std::{
+    fs,
+    io::Read,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use futures_util::StreamExt;
+use tokio_util::{io::ReaderStream, sync::CancellationToken};
+
+/// Called with `(sent, total)` bytes while an archive is built or streamed.
+pub type ProgressCallback = Arc<dyn Fn(u64, u64) + Send + Sync>;
+
+#[derive(Clone)]
+pub struct UploadProgressState {
+    pub sent: u64,
+    pub total: u64,
+    pub last_emit: Instant,
+}
+
+impl UploadProgressState {
+    pub fn new(total: u64) -> Self {
+        Self {
+            sent: 0,
+            total,
+            last_emit: Instant::now(),
+        }
+    }
+}
+
+pub struct CountingReader<R: Read> {
+    inner: R,
+    state: Arc<Mutex<UploadProgressState>>,
+    on_progress: ProgressCallback,
+    cancel: CancellationToken,
+}
+
+impl<R: Read> CountingReader<R> {
+    pub fn new(
+        inner: R,
+        state: Arc<Mutex<UploadProgressState>>,
+        on_progress: ProgressCallback,
+        cancel: CancellationToken,
+    ) -> Self {
+        Self {
+            inner,
+            state,
+            on_progress,
+            cancel,
+        }
+    }
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.cancel.is_cancelled() {
+            return Err(std::io::Error::other("upload cancelled"));
+        }
+        let n = self.inner.read(buf)?;
+        if n > 0 {
+            let mut state = self.state.lock().unwrap();
+            state.sent = state.sent.saturating_add(n as u64);
+            let total = if state.total == 0 { 1 } else { state.total };
+            if state.last_emit.elapsed() >= Duration::from_millis(150) {
+                (self.on_progress)(state.sent, total);
+                state.last_emit = Instant::now();
+            }
+        }
+        Ok(n)
+    }
+}
+
+pub fn add_dir_to_tar(
+    builder: &mut tar::Builder<flate2::write::GzEncoder<std::fs::File>>,
+    base: &Path,
+    path: &Path,
+    progress: &Arc<Mutex<UploadProgressState>>,
+    on_progress: &ProgressCallback,
+    cancel: &CancellationToken,
+) -> Result<(), String> {
+    let entries = fs::read_dir(path).map_err(|_| "Failed to read folder.".to_string())?;
+    for entry in entries {
+        if cancel.is_cancelled() {
+            return Err("Upload cancelled.".to_string());
+        }
+        let entry = entry.map_err(|_| "Failed to read folder.".to_string())?;
+        let entry_path = entry.path();
+        let rel = entry_path
+            .strip_prefix(base)
+            .map_err(|_| "Failed to build archive path.".to_string())?;
+        if entry_path.is_dir() {
+            builder
+                .append_dir(rel, &entry_path)
+                .map_err(|_| "Failed to add folder to archive.".to_string())?;
+            add_dir_to_tar(builder, base, &entry_path, progress, on_progress, cancel)?;
+        } else {
+            let metadata = fs::metadata(&entry_path)
+                .map_err(|_| "Failed to read file for archive.".to_string())?;
+            let mut header = tar::Header::new_gnu();
+            header.set_size(metadata.len());
+            header.set_mode(0o644);
+            header.set_mtime(
+                metadata
+                    .modified()
+                    .ok()
+                    .and_then(|m| m.duration_since(std::time::UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0),
+            );
+            header.set_cksum();
+
+            let file = fs::File::open(&entry_path)
+                .map_err(|_| "Failed to read file for archive.".to_string())?;
+            let mut reader =
+                CountingReader::new(file, progress.clone(), on_progress.clone(), cancel.clone());
+            builder
+                .append_data(&mut header, rel, &mut reader)
+                .map_err(|_| {
+                    if cancel.is_cancelled() {
+                        "Upload cancelled.".to_string()
+                    } else {
+                        "Failed to add file to archive.".to_string()
+                    }
+                })?;
+        }
+    }
+    Ok(())
+}
+
+pub fn dir_total_size(path: &Path) -> Result<u64, String> {
+    let mut total = 0u64;
+    let entries = fs::read_dir(path).map_err(|_| "Failed to read folder.".to_string())?;
+    for entry in entries {
+        let entry = entry.map_err(|_| "Failed to read folder.".to_string())?;
+        let entry_path = entry.path();
+        if entry_path.is_dir() {
+            total = total.saturating_add(dir_total_size(&entry_path)?);
+        } else {
+            total = total.saturating_add(
+                fs::metadata(&entry_path)
+                    .map_err(|_| "Failed to read file size.".to_string())?
+                    .len(),
+            );
+        }
+    }
+    Ok(total)
+}
+
+/// Packs `folder` into a gzip'd tarball at `archive_path`. Blocking; run it
+/// on a worker thread.
+pub fn build_archive(
+    folder: &Path,
+    archive_path: &Path,
+    progress: &Arc<Mutex<UploadProgressState>>,
+    on_progress: &ProgressCallback,
+    cancel: &CancellationToken,
+) -> Result<(), String> {
+    let tar_file =
+        fs::File::create(archive_path).map_err(|_| "Failed to create archive.".to_string())?;
+
+    let encoder = flate2::write::GzEncoder::new(tar_file, flate2::Compression::fast());
+    let mut builder = tar::Builder::new(encoder);
+
+    add_dir_to_tar(&mut builder, folder, folder, progress, on_progress, cancel)?;
+
+    let encoder = builder
+        .into_inner()
+        .map_err(|_| "Failed to finalize tar.".to_string())?;
+
+    encoder
+        .finish()
+        .map_err(|_| "Failed to finalize gzip.".to_string())?;
+
+    Ok(())
+}
+
+/// Opens a finished archive as a streaming request body that reports
+/// progress per chunk and errors out (aborting the request) once `cancel`
+/// fires.
+pub async fn archive_body(
+    archive_path: &Path,
+    on_progress: ProgressCallback,
+    cancel: CancellationToken,
+) -> Result<reqwest::Body, String> {
+    let file = tokio::fs::File::open(archive_path)
+        .await
+        .map_err(|_| "Failed to open archive for upload.".to_string())?;
+    let total = file.metadata().await.map(|m| m.len().max(1)).unwrap_or(1);
+
+    let sent = Arc::new(Mutex::new(0u64));
+    let stream = ReaderStream::new(file).map(move |chunk| {
+        if cancel.is_cancelled() {
+            return Err(std::io::Error::other("upload cancelled"));
+        }
+        if let Ok(ref bytes) = chunk {
+            let mut sent_guard = sent.lock().unwrap();
+            *sent_guard += bytes.len() as u64;
+            on_progress(*sent_guard, total);
+        }
+        chunk
+    });
+    Ok(reqwest::Body::wrap_stream(stream))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{
+        collections::BTreeMap,
+        path::PathBuf,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    /// A fresh folder with `a.txt` and `sub/b.bin`, 5 + 3 bytes.
+    fn app_folder() -> PathBuf {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "gaggle-packaging-{}-{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("app").join("sub")).unwrap();
+        fs::write(dir.join("app").join("a.txt"), "hello").unwrap();
+        fs::write(dir.join("app").join("sub").join("b.bin"), [1, 2, 3]).unwrap();
+        dir
+    }
+
+    fn no_progress() -> ProgressCallback {
+        Arc::new(|_, _| {})
+    }
+
+    fn progress_state(total: u64) -> Arc<Mutex<UploadProgressState>> {
+        Arc::new(Mutex::new(UploadProgressState::new(total)))
+    }
+
+    /// Files in the archive at `path`, by name.
+    fn unpack(path: &Path) -> BTreeMap<String, Vec<u8>> {
+        let file = fs::File::open(path).unwrap();
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+        let mut files = BTreeMap::new();
+        for entry in archive.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let name = entry.path().unwrap().to_string_lossy().replace('\\', "/");
+            let mut data = Vec::new();
+            entry.read_to_end(&mut data).unwrap();
+            files.insert(name, data);
+        }
+        files
+    }
+
+    #[test]
+    fn total_size_covers_nested_files() {
+        let dir = app_folder();
+        assert_eq!(dir_total_size(&dir.join("app")).unwrap(), 8);
+        assert!(dir_total_size(&dir.join("missing")).is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn archive_holds_the_folder_contents() {
+        let dir = app_folder();
+        let archive = dir.join("app.tar.gz");
+        let progress = progress_state(8);
+        build_archive(
+            &dir.join("app"),
+            &archive,
+            &progress,
+            &no_progress(),
+            &CancellationToken::new(),
+        )
+        .unwrap();
+
+        let files = unpack(&archive);
+        assert_eq!(files.len(), 2);
+        assert_eq!(files["a.txt"], b"hello");
+        assert_eq!(files["sub/b.bin"], [1, 2, 3]);
+        assert_eq!(progress.lock().unwrap().sent, 8);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn cancelled_archive_stops() {
+        let dir = app_folder();
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let result = build_archive(
+            &dir.join("app"),
+            &dir.join("app.tar.gz"),
+            &progress_state(8),
+            &no_progress(),
+            &cancel,
+        );
+        assert_eq!(result, Err("Upload cancelled.".to_string()));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn counting_reader_reports_progress() {
+        let state = progress_state(4);
+        // Backdated so the first read reports right away.
+        state.lock().unwrap().last_emit = Instant::now() - Duration::from_secs(1);
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let on_progress: ProgressCallback = {
+            let reports = reports.clone();
+            Arc::new(move |sent, total| reports.lock().unwrap().push((sent, total)))
+        };
+        let mut reader = CountingReader::new(
+            &b"data"[..],
+            state.clone(),
+            on_progress,
+            CancellationToken::new(),
+        );
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).unwrap();
+        assert_eq!(data, b"data");
+        assert_eq!(state.lock().unwrap().sent, 4);
+        assert_eq!(reports.lock().unwrap().first(), Some(&(4, 4)));
+    }
+
+    #[test]
+    fn counting_reader_fails_once_cancelled() {
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let mut reader =
+            CountingReader::new(&b"data"[..], progress_state(4), no_progress(), cancel);
+        let mut data = Vec::new();
+        assert!(reader.read_to_end(&mut data).is_err());
+        assert!(data.is_empty());
+    }
+}
@@ -0,0 +1,68 @@
+//! `--run <app-id> [--profile <name>]` on the command line.
+//!
+//! The first instance keeps the request until the Library asks for it with
+//! `take_pending_launch`; a second instance forwards its arguments through
+//! the single-instance plugin and exits. Either way the Library starts the
+//! app like its Run button does, so the session is tracked as usual.
+
+use std::sync::Mutex as StdMutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchRequest {
+    pub id: String,
+    pub profile: Option<String>,
+}
+
+#[derive(Default)]
+pub struct PendingLaunch(StdMutex<Option<LaunchRequest>>);
+
+/// Finds `--run` in `args`, which include the program name. Both
+/// `--run id` and `--run=id` are accepted; anything else is ignored.
+pub fn parse_args(args: &[String]) -> Option<LaunchRequest> {
+    let mut id = None;
+    let mut profile = None;
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        if let Some(value) = arg.strip_prefix("--run=") {
+            id = Some(value.to_string());
+        } else if arg == "--run" {
+            id = iter.next().cloned();
+        } else if let Some(value) = arg.strip_prefix("--profile=") {
+            profile = Some(value.to_string());
+        } else if arg == "--profile" {
+            profile = iter.next().cloned();
+        }
+    }
+    let id = id
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())?;
+    Some(LaunchRequest {
+        id,
+        profile: profile
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty()),
+    })
+}
+
+/// Queues a launch and tells the Library to pick it up.
+pub fn dispatch(app: &AppHandle, request: LaunchRequest) {
+    *app.state::<PendingLaunch>().0.lock().unwrap() = Some(request);
+    let _ = app.emit("cli_launch", ());
+}
+
+pub fn take(app: &AppHandle) -> Option<LaunchRequest> {
+    app.state::<PendingLaunch>().0.lock().unwrap().take()
+}
+
+/// Brings the existing window forward when another instance was started.
+pub fn focus_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
@@ -0,0 +1,64 @@
+//! Crash detection for tracked runs.
+//!
+//! A run counts as a crash when the spawned process exits with a non-zero
+//! code or is killed by a signal within `CRASH_WINDOW` of starting, unless
+//! the user stopped it. Longer sessions that end badly are usually the player
+//! quitting in some unusual way, so they aren't reported.
+
+use std::{path::Path, time::Duration};
+
+use serde::Serialize;
+
+use crate::{launch, run_logs};
+
+pub const CRASH_WINDOW: Duration = Duration::from_secs(60);
+/// How much of the session log goes into a report.
+const OUTPUT_TAIL_LINES: usize = 200;
+
+/// Everything the Library needs to send a crash report, so it doesn't have
+/// to look anything up after the fact.
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashInfo {
+    pub app_name: String,
+    pub app_version: String,
+    pub signal: Option<i32>,
+    pub output: String,
+    pub os: String,
+}
+
+pub fn is_crash(
+    exit_code: Option<i32>,
+    signal: Option<i32>,
+    duration: Duration,
+    stop_requested: bool,
+) -> bool {
+    if stop_requested || duration > CRASH_WINDOW {
+        return false;
+    }
+    matches!(exit_code, Some(code) if code != 0) || signal.is_some()
+}
+
+pub fn collect(app_dir: &Path, id: &str, log_name: Option<&str>, signal: Option<i32>) -> CrashInfo {
+    let config = launch::load_app_config(app_dir, id).unwrap_or_default();
+    let output = log_name
+        .and_then(|name| run_logs::read_log(app_dir, name).ok())
+        .map(|log| tail_lines(&log, OUTPUT_TAIL_LINES))
+        .unwrap_or_default();
+    CrashInfo {
+        app_name: if config.name.trim().is_empty() {
+            id.to_string()
+        } else {
+            config.name
+        },
+        app_version: config.version,
+        signal,
+        output,
+        os: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+    }
+}
+
+fn tail_lines(text: &str, count: usize) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    lines[lines.len().saturating_sub(count)..].join("\n")
+}
@@ -0,0 +1,365 @@
+//! App lifecycle hooks declared in the app TOML:
+//!
+//! ```toml
+//! [hooks]
+//! post_install = { command = "tools/setup.sh", args = ["--quiet"] }
+//! pre_launch = { command = "tools/sync-config.sh", timeout_seconds = 30 }
+//! post_exit = { command = "tools/cleanup.sh" }
+//! ```
+//!
+//! Commands resolve inside the app's `content/` folder like executables do.
+//! Hooks run arbitrary code, so none of them run until the user approved the
+//! exact set; the approval is a fingerprint of the hooks stored locally, and
+//! any change to them needs a new approval. The scripts themselves only
+//! arrive with the archive, so the first install after an approval pins
+//! their contents too, and an update that changes a script revokes it.
+
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager};
+
+use crate::{launch, proc_tree, run_logs};
+
+const APPROVALS_FILE: &str = "hook_approvals.json";
+/// Written once the post-install hook succeeded, so a failed one is retried
+/// before the next launch.
+const POST_INSTALL_MARKER: &str = ".post_install_done";
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+const MAX_TIMEOUT: Duration = Duration::from_secs(600);
+
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq)]
+pub struct Hooks {
+    #[serde(default)]
+    pub post_install: Option<Hook>,
+    #[serde(default)]
+    pub pre_launch: Option<Hook>,
+    #[serde(default)]
+    pub post_exit: Option<Hook>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct Hook {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub timeout_seconds: Option<u64>,
+}
+
+#[derive(Clone, Copy)]
+pub enum HookStage {
+    PostInstall,
+    PreLaunch,
+    PostExit,
+}
+
+impl HookStage {
+    fn key(self) -> &'static str {
+        match self {
+            HookStage::PostInstall => "post-install",
+            HookStage::PreLaunch => "pre-launch",
+            HookStage::PostExit => "post-exit",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            HookStage::PostInstall => "Post-install",
+            HookStage::PreLaunch => "Pre-launch",
+            HookStage::PostExit => "Post-exit",
+        }
+    }
+}
+
+/// A hook as shown in the install dialog.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookSummary {
+    pub stage: String,
+    pub command: String,
+    pub args: Vec<String>,
+    pub timeout_seconds: u64,
+}
+
+impl Hooks {
+    pub fn is_empty(&self) -> bool {
+        self.post_install.is_none() && self.pre_launch.is_none() && self.post_exit.is_none()
+    }
+
+    pub fn get(&self, stage: HookStage) -> Option<&Hook> {
+        match stage {
+            HookStage::PostInstall => self.post_install.as_ref(),
+            HookStage::PreLaunch => self.pre_launch.as_ref(),
+            HookStage::PostExit => self.post_exit.as_ref(),
+        }
+    }
+
+    pub fn summary(&self) -> Vec<HookSummary> {
+        [
+            HookStage::PostInstall,
+            HookStage::PreLaunch,
+            HookStage::PostExit,
+        ]
+        .into_iter()
+        .filter_map(|stage| {
+            self.get(stage).map(|hook| HookSummary {
+                stage: stage.label().to_string(),
+                command: hook.command.clone(),
+                args: hook.args.clone(),
+                timeout_seconds: timeout(hook).as_secs(),
+            })
+        })
+        .collect()
+    }
+
+    fn fingerprint(&self) -> String {
+        let raw = serde_json::to_vec(self).unwrap_or_default();
+        format!("{:x}", Sha256::digest(raw))
+    }
+
+    /// Hash of the script every hook points to, as installed in `app_dir`.
+    fn scripts_fingerprint(&self, app_dir: &Path) -> String {
+        let content_dir = app_dir.join("content");
+        let mut hasher = Sha256::new();
+        for stage in [
+            HookStage::PostInstall,
+            HookStage::PreLaunch,
+            HookStage::PostExit,
+        ] {
+            let Some(hook) = self.get(stage) else {
+                continue;
+            };
+            hasher.update(stage.key());
+            let script = launch::resolve_content_path(&content_dir, &hook.command, "hook command")
+                .ok()
+                .and_then(|path| fs::read(path).ok());
+            match script {
+                Some(raw) => hasher.update(Sha256::digest(raw)),
+                None => hasher.update(b"missing"),
+            }
+        }
+        format!("{:x}", hasher.finalize())
+    }
+}
+
+fn timeout(hook: &Hook) -> Duration {
+    hook.timeout_seconds
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_TIMEOUT)
+        .min(MAX_TIMEOUT)
+}
+
+/// Parses the hooks out of an app config that hasn't been installed yet.
+pub fn parse(config: &str) -> Result<Hooks, String> {
+    toml::from_str::<launch::AppConfig>(config)
+        .map(|config| config.hooks)
+        .map_err(|_| "App config is invalid.".to_string())
+}
+
+fn approvals_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|_| "Failed to locate settings folder.".to_string())?;
+    Ok(dir.join(APPROVALS_FILE))
+}
+
+fn read_approvals(app: &AppHandle) -> HashMap<String, String> {
+    approvals_path(app)
+        .ok()
+        .and_then(|path| fs::read(path).ok())
+        .and_then(|raw| serde_json::from_slice(&raw).ok())
+        .unwrap_or_default()
+}
+
+/// Whether `hooks` were approved for app `id`, before its scripts are
+/// installed. An app without hooks needs nothing.
+pub fn is_approved(app: &AppHandle, id: &str, hooks: &Hooks) -> bool {
+    hooks.is_empty()
+        || read_approvals(app)
+            .get(id)
+            .and_then(|approval| approval.split(':').next())
+            == Some(hooks.fingerprint().as_str())
+}
+
+/// Whether `hooks` may run for the app installed in `app_dir`. Approvals are
+/// stored as `<hooks>` until the scripts are pinned as `<hooks>:<scripts>`;
+/// changed scripts revoke the approval, so the next install asks again.
+pub fn verify_installed(app: &AppHandle, id: &str, hooks: &Hooks, app_dir: &Path) -> bool {
+    if hooks.is_empty() {
+        return true;
+    }
+    let mut approvals = read_approvals(app);
+    let Some(approval) = approvals.get(id) else {
+        return false;
+    };
+    let (approved_hooks, pinned) = match approval.split_once(':') {
+        Some((approved_hooks, pinned)) => (approved_hooks, Some(pinned)),
+        None => (approval.as_str(), None),
+    };
+    if approved_hooks != hooks.fingerprint() {
+        return false;
+    }
+    let scripts = hooks.scripts_fingerprint(app_dir);
+    match pinned {
+        Some(pinned) if pinned == scripts => true,
+        Some(_) => {
+            approvals.remove(id);
+            let _ = write_approvals(app, &approvals);
+            false
+        }
+        None => {
+            approvals.insert(
+                id.to_string(),
+                format!("{}:{}", hooks.fingerprint(), scripts),
+            );
+            write_approvals(app, &approvals).is_ok()
+        }
+    }
+}
+
+pub fn approve(app: &AppHandle, id: &str, hooks: &Hooks) -> Result<(), String> {
+    let mut approvals = read_approvals(app);
+    approvals.insert(id.to_string(), hooks.fingerprint());
+    write_approvals(app, &approvals)
+}
+
+fn write_approvals(app: &AppHandle, approvals: &HashMap<String, String>) -> Result<(), String> {
+    let path = approvals_path(app)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|_| "Failed to create settings folder.".to_string())?;
+    }
+    let raw = serde_json::to_vec_pretty(approvals)
+        .map_err(|_| "Failed to encode hook approvals.".to_string())?;
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, raw).map_err(|_| "Failed to save hook approval.".to_string())?;
+    fs::rename(&tmp, &path).map_err(|_| "Failed to save hook approval.".to_string())
+}
+
+pub fn post_install_pending(app_dir: &Path) -> bool {
+    !app_dir.join(POST_INSTALL_MARKER).exists()
+}
+
+pub fn reset_post_install(app_dir: &Path) {
+    let _ = fs::remove_file(app_dir.join(POST_INSTALL_MARKER));
+}
+
+/// Runs the post-install hook if it hasn't succeeded yet for this install.
+pub fn run_post_install(app_dir: &Path, hooks: &Hooks) -> Result<(), String> {
+    if !post_install_pending(app_dir) {
+        return Ok(());
+    }
+    if let Some(hook) = &hooks.post_install {
+        run(app_dir, HookStage::PostInstall, hook)?;
+    }
+    fs::write(app_dir.join(POST_INSTALL_MARKER), b"")
+        .map_err(|_| "Failed to record post-install hook.".to_string())
+}
+
+/// Runs one hook from the app's `content/` folder and waits for it, killing
+/// it after its timeout. Output goes to `logs/hook-<timestamp>-<stage>.log`.
+pub fn run(app_dir: &Path, stage: HookStage, hook: &Hook) -> Result<(), String> {
+    let content_dir = app_dir.join("content");
+    let program = launch::resolve_content_path(&content_dir, &hook.command, "hook command")?;
+    if !program.exists() {
+        return Err(format!("{} hook command not found.", stage.label()));
+    }
+
+    let logs_dir = run_logs::logs_dir(app_dir);
+    let _ = fs::create_dir_all(&logs_dir);
+    let log_name = format!(
+        "hook-{}-{}.log",
+        chrono::Local::now().format("%Y%m%d-%H%M%S"),
+        stage.key()
+    );
+    let log = File::create(logs_dir.join(&log_name)).ok();
+    let pipe = || {
+        log.as_ref()
+            .and_then(|file| file.try_clone().ok())
+            .map(Stdio::from)
+            .unwrap_or_else(Stdio::null)
+    };
+    run_logs::prune_hook_logs(&logs_dir);
+
+    let mut command = Command::new(&program);
+    command
+        .args(&hook.args)
+        .current_dir(&content_dir)
+        .env("GAGGLE_HOOK", stage.key())
+        .env("GAGGLE_APP_DIR", app_dir)
+        .stdin(Stdio::null())
+        .stdout(pipe())
+        .stderr(pipe());
+    // Own process group, so a timeout also stops what a script started.
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    let mut child = command
+        .spawn()
+        .map_err(|_| format!("Failed to start {} hook.", stage.key()))?;
+
+    let limit = timeout(hook);
+    let start = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) if status.success() => return Ok(()),
+            Ok(Some(status)) => {
+                let code = status
+                    .code()
+                    .map(|code| format!("exit code {}", code))
+                    .unwrap_or_else(|| "a signal".to_string());
+                return Err(format!(
+                    "{} hook failed with {}. See {} in the app's logs.",
+                    stage.label(),
+                    code,
+                    log_name
+                ));
+            }
+            Ok(None) if start.elapsed() >= limit => {
+                proc_tree::kill(Some(child.id()), &[]);
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!(
+                    "{} hook timed out after {}s.",
+                    stage.label(),
+                    limit.as_secs()
+                ));
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(100)),
+            Err(_) => return Err(format!("Failed to wait for {} hook.", stage.key())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripts_fingerprint_follows_script_contents() {
+        let app_dir = std::env::temp_dir().join(format!("gaggle-hooks-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&app_dir);
+        fs::create_dir_all(app_dir.join("content/tools")).unwrap();
+        let hooks = parse("[hooks]\npost_install = { command = \"tools/setup.sh\" }\n").unwrap();
+
+        let missing = hooks.scripts_fingerprint(&app_dir);
+        fs::write(app_dir.join("content/tools/setup.sh"), "echo one\n").unwrap();
+        let first = hooks.scripts_fingerprint(&app_dir);
+        assert_ne!(missing, first);
+        assert_eq!(first, hooks.scripts_fingerprint(&app_dir));
+
+        fs::write(app_dir.join("content/tools/setup.sh"), "echo two\n").unwrap();
+        assert_ne!(first, hooks.scripts_fingerprint(&app_dir));
+        fs::remove_dir_all(&app_dir).unwrap();
+    }
+}
@@ -0,0 +1,182 @@
+//! Builds the HTTP clients every request to a Gaggle server goes through, so
+//! they all honour the network settings: proxy, timeouts, user agent and an
+//! extra trusted CA per server. Settings are stored in
+//! `network_settings.json` in the app config dir.
+//!
+//! Clients are cached per server and reused, so requests share connections;
+//! saving the settings drops the cache.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::PathBuf,
+    sync::Mutex,
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+const SETTINGS_FILE: &str = "network_settings.json";
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+/// Longest silence allowed mid-response. Not a limit on the whole request,
+/// which for a large download can take hours.
+const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(60);
+const PROXY_SCHEMES: [&str; 4] = ["http", "https", "socks5", "socks5h"];
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkSettings {
+    /// `http://`, `https://`, `socks5://` or `socks5h://` URL. Empty uses the
+    /// system's proxy environment variables.
+    #[serde(default)]
+    pub proxy: String,
+    #[serde(default)]
+    pub connect_timeout_seconds: Option<u64>,
+    #[serde(default)]
+    pub read_timeout_seconds: Option<u64>,
+    /// Empty sends `Gaggle/<version>`.
+    #[serde(default)]
+    pub user_agent: String,
+    /// PEM certificates trusted on top of the system ones, keyed by the
+    /// server's base URL.
+    #[serde(default)]
+    pub server_ca: BTreeMap<String, String>,
+}
+
+#[derive(Default)]
+pub struct HttpClients {
+    clients: Mutex<HashMap<String, reqwest::Client>>,
+}
+
+/// Key the per-server settings and clients are stored under.
+pub use crate::net::server_key;
+
+fn settings_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|_| "Failed to locate settings folder.".to_string())?;
+    Ok(dir.join(SETTINGS_FILE))
+}
+
+pub fn load(app: &AppHandle) -> NetworkSettings {
+    settings_path(app)
+        .ok()
+        .and_then(|path| fs::read(path).ok())
+        .and_then(|raw| serde_json::from_slice(&raw).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(app: &AppHandle, settings: &NetworkSettings) -> Result<(), String> {
+    validate(settings)?;
+    let path = settings_path(app)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|_| "Failed to create settings folder.".to_string())?;
+    }
+    let raw = serde_json::to_vec_pretty(settings)
+        .map_err(|_| "Failed to encode network settings.".to_string())?;
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, raw).map_err(|_| "Failed to save network settings.".to_string())?;
+    fs::rename(&tmp, &path).map_err(|_| "Failed to save network settings.".to_string())?;
+    app.state::<HttpClients>().clients.lock().unwrap().clear();
+    Ok(())
+}
+
+/// Checks the settings before they are saved so a typo shows up in Settings
+/// instead of as a failed download.
+pub fn validate(settings: &NetworkSettings) -> Result<(), String> {
+    proxy(settings)?;
+    if settings.connect_timeout_seconds == Some(0) || settings.read_timeout_seconds == Some(0) {
+        return Err("Timeouts must be at least one second.".to_string());
+    }
+    if !settings.user_agent.is_empty()
+        && reqwest::header::HeaderValue::from_str(settings.user_agent.trim()).is_err()
+    {
+        return Err("User agent contains characters that can't be sent.".to_string());
+    }
+    for (server, pem) in &settings.server_ca {
+        certificates(pem).map_err(|err| format!("{}: {}", server, err))?;
+    }
+    Ok(())
+}
+
+fn proxy(settings: &NetworkSettings) -> Result<Option<reqwest::Proxy>, String> {
+    let url = settings.proxy.trim();
+    if url.is_empty() {
+        return Ok(None);
+    }
+    let scheme = url
+        .split_once("://")
+        .map(|(scheme, _)| scheme.to_ascii_lowercase());
+    if !scheme.is_some_and(|scheme| PROXY_SCHEMES.contains(&scheme.as_str())) {
+        return Err(
+            "Proxy must start with http://, https://, socks5:// or socks5h://.".to_string(),
+        );
+    }
+    reqwest::Proxy::all(url)
+        .map(Some)
+        .map_err(|_| "Proxy address is invalid.".to_string())
+}
+
+fn certificates(pem: &str) -> Result<Vec<reqwest::Certificate>, String> {
+    if pem.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    match reqwest::Certificate::from_pem_bundle(pem.as_bytes()) {
+        Ok(certs) if !certs.is_empty() => Ok(certs),
+        _ => Err("CA certificate is not valid PEM.".to_string()),
+    }
+}
+
+fn build(
+    app: &AppHandle,
+    settings: &NetworkSettings,
+    key: &str,
+) -> Result<reqwest::Client, String> {
+    let user_agent = match settings.user_agent.trim() {
+        "" => format!("Gaggle/{}", app.package_info().version),
+        custom => custom.to_string(),
+    };
+    let mut builder = reqwest::Client::builder()
+        .user_agent(user_agent)
+        .connect_timeout(
+            settings
+                .connect_timeout_seconds
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_CONNECT_TIMEOUT),
+        )
+        .read_timeout(
+            settings
+                .read_timeout_seconds
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_READ_TIMEOUT),
+        );
+    if let Some(proxy) = proxy(settings)? {
+        builder = builder.proxy(proxy);
+    }
+    if let Some(pem) = settings.server_ca.get(key) {
+        for cert in certificates(pem)? {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+    builder
+        .build()
+        .map_err(|_| "Failed to set up the HTTP client.".to_string())
+}
+
+/// The client for requests to a server, built on first use.
+pub fn client(
+    app: &AppHandle,
+    server_ip: &str,
+    server_port: &str,
+) -> Result<reqwest::Client, String> {
+    let key = server_key(server_ip, server_port);
+    let clients = app.state::<HttpClients>();
+    if let Some(client) = clients.clients.lock().unwrap().get(&key) {
+        return Ok(client.clone());
+    }
+    let client = build(app, &load(app), &key)?;
+    clients.clients.lock().unwrap().insert(key, client.clone());
+    Ok(client)
+}
@@ -0,0 +1,187 @@
+//! Turns an installed app's config into the command the run manager spawns.
+//!
+//! Launch profiles live in the app TOML as `[[launch]]` tables. The client
+//! always reads them from the config that was downloaded with the app, never
+//! from the frontend, so a profile can only run what the admin published.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Component, Path, PathBuf},
+    process::Command,
+};
+
+use serde::Deserialize;
+
+use crate::{hooks::Hooks, saves::SaveLocation};
+
+#[derive(Deserialize, Default)]
+pub struct AppConfig {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+    /// Name of the process that means "the app is running", for launchers
+    /// that hand off to a differently named game binary.
+    #[serde(default)]
+    pub track_process: Option<String>,
+    #[serde(default)]
+    pub launch: Vec<LaunchProfile>,
+    #[serde(default)]
+    pub hooks: Hooks,
+    /// Set on mod packages: the app whose `content/` they are layered over.
+    #[serde(default)]
+    pub base_app: Option<String>,
+    #[serde(default)]
+    pub saves: Vec<SaveLocation>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct LaunchProfile {
+    pub name: String,
+    pub executable: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// Relative to the app's `content/` folder.
+    #[serde(default)]
+    pub working_dir: Option<String>,
+    /// Overrides the app-level `track_process` for this profile.
+    #[serde(default)]
+    pub track_process: Option<String>,
+}
+
+pub struct LaunchPlan {
+    pub program: PathBuf,
+    pub args: Vec<String>,
+    pub env: Vec<(String, String)>,
+    pub working_dir: PathBuf,
+    pub profile: Option<String>,
+    pub track_process: Option<String>,
+}
+
+impl LaunchPlan {
+    pub fn command(&self) -> Command {
+        let mut command = Command::new(&self.program);
+        command
+            .args(&self.args)
+            .envs(self.env.iter().cloned())
+            .current_dir(&self.working_dir);
+        // Own process group, so the whole tree can be found (and stopped)
+        // after a launcher exits.
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            command.process_group(0);
+        }
+        command
+    }
+}
+
+pub fn load_app_config(app_dir: &Path, id: &str) -> Result<AppConfig, String> {
+    let raw = fs::read_to_string(app_dir.join(format!("{}.toml", id)))
+        .map_err(|_| "App config not found.".to_string())?;
+    toml::from_str(&raw).map_err(|_| "App config is invalid.".to_string())
+}
+
+/// Joins a config-provided relative path onto `content_dir`, rejecting
+/// absolute paths and anything that climbs out of the folder.
+pub fn resolve_content_path(content_dir: &Path, rel: &str, what: &str) -> Result<PathBuf, String> {
+    if rel.trim().is_empty() {
+        return Err(format!("Missing {} path.", what));
+    }
+    let rel = Path::new(rel.trim());
+    if rel.is_absolute() {
+        return Err(format!("{} must be a relative path.", capitalize(what)));
+    }
+    let mut clean = PathBuf::new();
+    for component in rel.components() {
+        match component {
+            Component::Normal(part) => clean.push(part),
+            Component::CurDir => {}
+            _ => return Err(format!("{} path is not safe.", capitalize(what))),
+        }
+    }
+    Ok(content_dir.join(clean))
+}
+
+/// Resolves what to run for an app: the named profile from its local
+/// config, or the plain `executable` when no profile is picked.
+pub fn plan_launch(
+    app_dir: &Path,
+    id: &str,
+    executable: &str,
+    profile: Option<&str>,
+) -> Result<LaunchPlan, String> {
+    let content_dir = app_dir.join("content");
+
+    let Some(profile_name) = profile.map(str::trim).filter(|name| !name.is_empty()) else {
+        // The config is optional here; it only adds `track_process`.
+        let track_process = load_app_config(app_dir, id)
+            .ok()
+            .and_then(|config| config.track_process);
+        let program = resolve_content_path(&content_dir, executable, "executable")?;
+        if !program.exists() {
+            return Err("Executable not found.".to_string());
+        }
+        return Ok(LaunchPlan {
+            program,
+            args: Vec::new(),
+            env: Vec::new(),
+            working_dir: content_dir,
+            profile: None,
+            track_process,
+        });
+    };
+
+    let config = load_app_config(app_dir, id)?;
+    let profile = config
+        .launch
+        .iter()
+        .find(|candidate| candidate.name.trim() == profile_name)
+        .ok_or_else(|| {
+            "Launch profile not found. Reinstall the app to pick up new profiles.".to_string()
+        })?;
+
+    let program = resolve_content_path(&content_dir, &profile.executable, "executable")?;
+    if !program.exists() {
+        return Err("Executable not found.".to_string());
+    }
+    let working_dir = match profile.working_dir.as_deref() {
+        Some(dir) if !dir.trim().is_empty() => {
+            resolve_content_path(&content_dir, dir, "working directory")?
+        }
+        _ => content_dir,
+    };
+    if !working_dir.is_dir() {
+        return Err("Working directory not found.".to_string());
+    }
+
+    let mut env: Vec<(String, String)> = profile
+        .env
+        .iter()
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    env.sort();
+
+    Ok(LaunchPlan {
+        program,
+        args: profile.args.clone(),
+        env,
+        working_dir,
+        profile: Some(profile.name.trim().to_string()),
+        track_process: profile
+            .track_process
+            .clone()
+            .or_else(|| config.track_process.clone()),
+    })
+}
+
+fn capitalize(value: &str) -> String {
+    let mut chars = value.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
@@ -0,0 +1,340 @@
+//! Per-user launch overrides, stored locally in `launch_settings.json` in the
+//! app config dir. Unlike launch profiles these never come from the server.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::launch::LaunchPlan;
+
+const SETTINGS_FILE: &str = "launch_settings.json";
+/// Placeholder a wrapper can use to put the game command somewhere other
+/// than the end, e.g. `env DXVK_HUD=1 %command% --no-splash`.
+const COMMAND_PLACEHOLDER: &str = "%command%";
+const DEFAULT_STOP_GRACE: Duration = Duration::from_secs(10);
+const MAX_STOP_GRACE: Duration = Duration::from_secs(300);
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchSettings {
+    /// Wrapper used by every app that doesn't set its own.
+    #[serde(default)]
+    pub default_wrapper: String,
+    #[serde(default)]
+    pub apps: HashMap<String, AppLaunchSettings>,
+    /// How long Stop waits after asking an app to exit before killing it.
+    #[serde(default)]
+    pub stop_grace_seconds: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AppLaunchSettings {
+    /// Appended to the executable's (or profile's) own arguments.
+    #[serde(default)]
+    pub extra_args: String,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// `None` uses the default wrapper, an empty string disables wrapping.
+    #[serde(default)]
+    pub wrapper: Option<String>,
+    /// Files or folders under `content/` snapshotted along with the app's
+    /// declared saves.
+    #[serde(default)]
+    pub save_paths: Vec<String>,
+}
+
+impl LaunchSettings {
+    pub fn stop_grace(&self) -> Duration {
+        self.stop_grace_seconds
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_STOP_GRACE)
+            .min(MAX_STOP_GRACE)
+    }
+
+    pub fn wrapper_for(&self, id: &str) -> &str {
+        match self.apps.get(id).and_then(|app| app.wrapper.as_deref()) {
+            Some(wrapper) => wrapper,
+            None => &self.default_wrapper,
+        }
+    }
+}
+
+fn settings_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|_| "Failed to locate settings folder.".to_string())?;
+    Ok(dir.join(SETTINGS_FILE))
+}
+
+fn read_settings(path: &Path) -> LaunchSettings {
+    fs::read(path)
+        .ok()
+        .and_then(|raw| serde_json::from_slice(&raw).ok())
+        .unwrap_or_default()
+}
+
+pub fn load(app: &AppHandle) -> LaunchSettings {
+    settings_path(app)
+        .map(|path| read_settings(&path))
+        .unwrap_or_default()
+}
+
+pub fn save(app: &AppHandle, settings: &LaunchSettings) -> Result<(), String> {
+    let path = settings_path(app)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|_| "Failed to create settings folder.".to_string())?;
+    }
+    let raw = serde_json::to_vec_pretty(settings)
+        .map_err(|_| "Failed to encode launch settings.".to_string())?;
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, raw).map_err(|_| "Failed to save launch settings.".to_string())?;
+    fs::rename(&tmp, &path).map_err(|_| "Failed to save launch settings.".to_string())
+}
+
+/// Validates settings before they are saved so a typo shows up in the
+/// dialog instead of at launch time.
+pub fn validate(settings: &LaunchSettings) -> Result<(), String> {
+    split_command_line(&settings.default_wrapper).map_err(|e| format!("Default wrapper: {}", e))?;
+    for app in settings.apps.values() {
+        split_command_line(&app.extra_args).map_err(|e| format!("Extra arguments: {}", e))?;
+        if let Some(wrapper) = &app.wrapper {
+            split_command_line(wrapper).map_err(|e| format!("Wrapper: {}", e))?;
+        }
+        if app
+            .env
+            .keys()
+            .any(|key| key.trim().is_empty() || key.contains('=') || key.contains('\0'))
+        {
+            return Err("Environment variable names must not be empty or contain '='.".to_string());
+        }
+        for path in &app.save_paths {
+            crate::saves::resolve_location(Path::new(""), path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Applies the user's overrides for app `id` on top of `plan`. The wrapper,
+/// if any, becomes the spawned program, so it is what gets tracked.
+/// Returns whether the command was wrapped.
+pub fn apply(plan: &mut LaunchPlan, settings: &LaunchSettings, id: &str) -> Result<bool, String> {
+    if let Some(app) = settings.apps.get(id) {
+        plan.args.extend(split_command_line(&app.extra_args)?);
+        for (key, value) in &app.env {
+            plan.env.retain(|(existing, _)| existing != key);
+            plan.env.push((key.clone(), value.clone()));
+        }
+    }
+
+    let wrapper = split_command_line(settings.wrapper_for(id))?;
+    if wrapper.is_empty() {
+        return Ok(false);
+    }
+
+    let mut command = vec![plan.program.to_string_lossy().to_string()];
+    command.append(&mut plan.args);
+
+    let mut parts = Vec::new();
+    let mut placed = false;
+    for part in wrapper {
+        if part == COMMAND_PLACEHOLDER {
+            parts.extend(command.iter().cloned());
+            placed = true;
+        } else {
+            parts.push(part);
+        }
+    }
+    if !placed {
+        parts.extend(command);
+    }
+
+    let mut parts = parts.into_iter();
+    plan.program = PathBuf::from(parts.next().unwrap_or_default());
+    plan.args = parts.collect();
+    Ok(true)
+}
+
+/// Splits a command line the way a POSIX shell would for plain words:
+/// whitespace separates arguments and single or double quotes group them.
+/// A backslash only escapes quotes, whitespace and itself, so Windows paths
+/// such as `C:\Games\app.exe` survive unquoted.
+pub fn split_command_line(input: &str) -> Result<Vec<String>, String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    let mut chars = input.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match (quote, ch) {
+            (Some('\''), '\'') => quote = None,
+            (Some('\''), _) => current.push(ch),
+            (Some('"'), '"') => quote = None,
+            (_, '\\') => {
+                match chars.peek() {
+                    Some(&next)
+                        if next == '"' || next == '\'' || next == '\\' || next.is_whitespace() =>
+                    {
+                        current.push(next);
+                        chars.next();
+                    }
+                    _ => current.push(ch),
+                }
+                in_word = true;
+            }
+            (Some(_), _) => current.push(ch),
+            (None, '\'' | '"') => {
+                quote = Some(ch);
+                in_word = true;
+            }
+            (None, ch) if ch.is_whitespace() => {
+                if in_word {
+                    parts.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            (None, _) => {
+                current.push(ch);
+                in_word = true;
+            }
+        }
+    }
+
+    if quote.is_some() {
+        return Err("Unterminated quote.".to_string());
+    }
+    if in_word {
+        parts.push(current);
+    }
+    Ok(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(input: &str) -> Vec<String> {
+        split_command_line(input).unwrap()
+    }
+
+    fn plan() -> LaunchPlan {
+        LaunchPlan {
+            program: PathBuf::from("game.exe"),
+            args: vec!["--windowed".to_string()],
+            env: vec![("MODE".to_string(), "release".to_string())],
+            working_dir: PathBuf::from("content"),
+            profile: None,
+            track_process: None,
+        }
+    }
+
+    fn settings(wrapper: Option<&str>, extra_args: &str) -> LaunchSettings {
+        let app = AppLaunchSettings {
+            extra_args: extra_args.to_string(),
+            env: BTreeMap::from([("MODE".to_string(), "debug".to_string())]),
+            wrapper: wrapper.map(str::to_string),
+            save_paths: Vec::new(),
+        };
+        LaunchSettings {
+            default_wrapper: "gamemoderun".to_string(),
+            apps: HashMap::from([("demo".to_string(), app)]),
+            stop_grace_seconds: None,
+        }
+    }
+
+    #[test]
+    fn splits_on_whitespace() {
+        assert_eq!(split("  -a   -b\t-c "), ["-a", "-b", "-c"]);
+        assert!(split("   ").is_empty());
+    }
+
+    #[test]
+    fn quotes_group_words() {
+        assert_eq!(
+            split(r#"--name "Big Game" 'it''s' "#),
+            ["--name", "Big Game", "its"]
+        );
+        assert_eq!(split(r#"--empty "" x"#), ["--empty", "", "x"]);
+        assert_eq!(split(r#"'say "hi"' "it's""#), [r#"say "hi""#, "it's"]);
+        assert_eq!(split(r#"--opt="a b""#), ["--opt=a b"]);
+    }
+
+    #[test]
+    fn backslashes_escape_quotes_whitespace_and_themselves() {
+        assert_eq!(split(r#"a\ b \"c\" d\\e"#), ["a b", r#""c""#, r"d\e"]);
+        assert_eq!(split(r#""say \"hi\"""#), [r#"say "hi""#]);
+        // Nothing is escaped inside single quotes.
+        assert_eq!(split(r"'a\ b'"), [r"a\ b"]);
+    }
+
+    #[test]
+    fn windows_paths_survive() {
+        assert_eq!(split(r"C:\Games\app.exe -x"), [r"C:\Games\app.exe", "-x"]);
+        assert_eq!(
+            split(r#""C:\Program Files\Proton\proton" run"#),
+            [r"C:\Program Files\Proton\proton", "run"]
+        );
+    }
+
+    #[test]
+    fn unterminated_quotes_are_rejected() {
+        for input in [r#"--name "Big Game"#, "'open", r#"a \"b "c"#] {
+            assert_eq!(
+                split_command_line(input),
+                Err("Unterminated quote.".to_string()),
+                "{input} was accepted"
+            );
+        }
+    }
+
+    #[test]
+    fn wrapper_without_placeholder_goes_in_front() {
+        let mut plan = plan();
+        assert!(apply(&mut plan, &settings(None, "-fast \"x y\""), "demo").unwrap());
+        assert_eq!(plan.program, PathBuf::from("gamemoderun"));
+        assert_eq!(plan.args, ["game.exe", "--windowed", "-fast", "x y"]);
+        assert_eq!(plan.env, [("MODE".to_string(), "debug".to_string())]);
+    }
+
+    #[test]
+    fn wrapper_placeholder_places_the_command() {
+        let mut plan = plan();
+        let settings = settings(Some("env DXVK_HUD=1 %command% --no-splash"), "");
+        assert!(apply(&mut plan, &settings, "demo").unwrap());
+        assert_eq!(plan.program, PathBuf::from("env"));
+        assert_eq!(
+            plan.args,
+            ["DXVK_HUD=1", "game.exe", "--windowed", "--no-splash"]
+        );
+    }
+
+    #[test]
+    fn empty_wrapper_leaves_the_command_alone() {
+        let mut plan = plan();
+        assert!(!apply(&mut plan, &settings(Some(""), "-x"), "demo").unwrap());
+        assert_eq!(plan.program, PathBuf::from("game.exe"));
+        assert_eq!(plan.args, ["--windowed", "-x"]);
+
+        // Other apps still get the default wrapper, without the overrides.
+        let mut plan = self::plan();
+        assert!(apply(&mut plan, &settings(Some(""), "-x"), "other").unwrap());
+        assert_eq!(plan.program, PathBuf::from("gamemoderun"));
+        assert_eq!(plan.args, ["game.exe", "--windowed"]);
+        assert_eq!(plan.env, [("MODE".to_string(), "release".to_string())]);
+    }
+
+    #[test]
+    fn invalid_overrides_fail_the_launch() {
+        let mut plan = plan();
+        assert!(apply(&mut plan, &settings(None, "\"open"), "demo").is_err());
+        assert!(apply(&mut plan, &settings(Some("'open"), ""), "demo").is_err());
+    }
+}
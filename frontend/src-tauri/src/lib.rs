@@ -1,8 +1,7 @@
 use std::sync::Mutex as StdMutex;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fs,
-    io::Read,
     path::{Path, PathBuf},
     sync::{
         atomic::{AtomicBool, Ordering},
@@ -12,14 +11,33 @@ use std::{
 };
 
 use futures_util::StreamExt;
+use gaggle_shared::packaging::{
+    archive_body, build_archive, dir_total_size, ProgressCallback, UploadProgressState,
+};
 use reqwest::header::RANGE;
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Emitter, Manager, State};
 use tauri_plugin_opener::open_path;
 use tauri_plugin_updater::UpdaterExt;
 use tokio::{fs::OpenOptions, io::AsyncWriteExt, sync::Mutex};
-
-pub mod net;
+use tokio_util::sync::CancellationToken;
+
+pub use gaggle_shared::net;
+
+mod cli;
+mod crash;
+mod hooks;
+mod http_client;
+mod launch;
+mod launch_settings;
+mod mods;
+mod playtime_journal;
+mod proc_tree;
+mod run_logs;
+mod run_stats;
+mod saves;
+mod shortcut;
+mod snapshots;
 
 #[derive(Default)]
 struct DownloadManager {
@@ -27,15 +45,85 @@ struct DownloadManager {
     status: Arc<Mutex<HashMap<String, DownloadSnapshot>>>,
 }
 
+#[derive(Default)]
+struct UploadManager {
+    tasks: Mutex<HashMap<String, UploadTask>>,
+}
+
+#[derive(Clone)]
+struct UploadTask {
+    server_ip: String,
+    server_port: String,
+    token: String,
+    temp_dir: PathBuf,
+    cancel: CancellationToken,
+    /// Cancelled once the upload stopped and its temp folder is gone.
+    done: CancellationToken,
+}
+
 #[derive(Default)]
 struct RunManager {
     processes: StdMutex<HashMap<String, RunningProcess>>,
+    /// Apps whose launch is still running hooks and not in `processes` yet.
+    launching: StdMutex<HashSet<String>>,
+}
+
+impl RunManager {
+    /// Reserves `id` for a launch, so a second launch is refused before it
+    /// runs the hooks again. The reservation ends when the guard is dropped.
+    fn reserve(&self, id: &str) -> Result<LaunchReservation<'_>, String> {
+        let processes = self.processes.lock().unwrap();
+        let mut launching = self.launching.lock().unwrap();
+        if processes.contains_key(id) || !launching.insert(id.to_string()) {
+            return Err("App is already running.".to_string());
+        }
+        Ok(LaunchReservation {
+            launching: &self.launching,
+            id: id.to_string(),
+        })
+    }
+}
+
+struct LaunchReservation<'a> {
+    launching: &'a StdMutex<HashSet<String>>,
+    id: String,
+}
+
+impl Drop for LaunchReservation<'_> {
+    fn drop(&mut self) {
+        self.launching.lock().unwrap().remove(&self.id);
+    }
 }
 
 struct RunningProcess {
-    child: Arc<StdMutex<std::process::Child>>,
-    #[allow(dead_code)]
+    /// `None` for runs picked up after the OS default handler opened them.
+    child: Option<Arc<StdMutex<std::process::Child>>>,
+    /// Processes of the run that Stop may signal.
+    pids: Arc<StdMutex<Vec<u32>>>,
+    /// Set by the stop command so the exit isn't reported as a crash.
+    stop_requested: Arc<AtomicBool>,
     start: Instant,
+    /// Wall-clock start in unix seconds, for a UI that reconnects mid-run.
+    started_at: u64,
+    profile: Option<String>,
+}
+
+/// A tracked run as listed by `list_running_apps`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RunningApp {
+    id: String,
+    profile: Option<String>,
+    started_at: u64,
+    elapsed_seconds: u64,
+    stopping: bool,
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
 }
 
 #[derive(Clone)]
@@ -45,6 +133,8 @@ struct DownloadTask {
     archive_url: String,
     config_url: String,
     token: String,
+    /// Base URL of the server the app comes from.
+    server: String,
     dest_dir: PathBuf,
     app_dir: PathBuf,
     archive_path: PathBuf,
@@ -85,6 +175,8 @@ struct DownloadMeta {
     dest_dir: String,
     token: Option<String>,
     total: Option<u64>,
+    #[serde(default)]
+    server: String,
 }
 
 #[derive(Deserialize)]
@@ -96,6 +188,8 @@ struct DownloadRequest {
     config_url: String,
     dest_dir: String,
     token: String,
+    #[serde(default)]
+    server: String,
 }
 
 #[derive(Deserialize)]
@@ -189,139 +283,12 @@ struct UploadStage {
     stage: String,
 }
 
-#[derive(Clone)]
-struct UploadProgressState {
-    sent: u64,
-    total: u64,
-    last_emit: Instant,
-}
-
-struct CountingReader<R: Read> {
-    inner: R,
-    state: Arc<StdMutex<UploadProgressState>>,
+#[tauri::command]
+async fn upload_app(
+    request: UploadAppRequest,
+    state: State<'_, UploadManager>,
     app: AppHandle,
-    id: String,
-}
-
-impl<R: Read> CountingReader<R> {
-    fn new(
-        inner: R,
-        state: Arc<StdMutex<UploadProgressState>>,
-        app: AppHandle,
-        id: String,
-    ) -> Self {
-        Self {
-            inner,
-            state,
-            app,
-            id,
-        }
-    }
-}
-
-impl<R: Read> Read for CountingReader<R> {
-    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        let n = self.inner.read(buf)?;
-        if n > 0 {
-            let mut state = self.state.lock().unwrap();
-            state.sent = state.sent.saturating_add(n as u64);
-            let total = if state.total == 0 { 1 } else { state.total };
-            if state.last_emit.elapsed() >= Duration::from_millis(150) {
-                let pct = (state.sent as f64 / total as f64) * 100.0;
-                let _ = self.app.emit(
-                    "app_upload_progress",
-                    UploadProgress {
-                        id: self.id.clone(),
-                        sent: state.sent,
-                        total,
-                        pct,
-                    },
-                );
-                state.last_emit = Instant::now();
-            }
-        }
-        Ok(n)
-    }
-}
-
-fn add_dir_to_tar(
-    builder: &mut tar::Builder<flate2::write::GzEncoder<std::fs::File>>,
-    base: &Path,
-    path: &Path,
-    progress: &Arc<StdMutex<UploadProgressState>>,
-    app: &AppHandle,
-    id: &str,
 ) -> Result<(), String> {
-    let entries = fs::read_dir(path).map_err(|_| "Failed to read folder.".to_string())?;
-    for entry in entries {
-        let entry = entry.map_err(|_| "Failed to read folder.".to_string())?;
-        let entry_path = entry.path();
-        let rel = entry_path
-            .strip_prefix(base)
-            .map_err(|_| "Failed to build archive path.".to_string())?;
-        if entry_path.is_dir() {
-            builder
-                .append_dir(rel, &entry_path)
-                .map_err(|_| "Failed to add folder to archive.".to_string())?;
-            add_dir_to_tar(builder, base, &entry_path, progress, app, id)?;
-        } else {
-            let metadata = fs::metadata(&entry_path)
-                .map_err(|_| "Failed to read file for archive.".to_string())?;
-            let mut header = tar::Header::new_gnu();
-            header.set_size(metadata.len());
-            header.set_mode(0o644);
-            header.set_mtime(
-                metadata
-                    .modified()
-                    .ok()
-                    .and_then(|m| m.duration_since(std::time::UNIX_EPOCH).ok())
-                    .map(|d| d.as_secs())
-                    .unwrap_or(0),
-            );
-            header.set_cksum();
-
-            let file = fs::File::open(&entry_path)
-                .map_err(|_| "Failed to read file for archive.".to_string())?;
-            let mut reader =
-                CountingReader::new(file, progress.clone(), app.clone(), id.to_string());
-            builder
-                .append_data(&mut header, rel, &mut reader)
-                .map_err(|_| "Failed to add file to archive.".to_string())?;
-        }
-    }
-    Ok(())
-}
-
-fn dir_total_size(path: &Path) -> Result<u64, String> {
-    let mut total = 0u64;
-    let entries = fs::read_dir(path).map_err(|_| "Failed to read folder.".to_string())?;
-    for entry in entries {
-        let entry = entry.map_err(|_| "Failed to read folder.".to_string())?;
-        let entry_path = entry.path();
-        if entry_path.is_dir() {
-            total = total.saturating_add(dir_total_size(&entry_path)?);
-        } else {
-            total = total.saturating_add(
-                fs::metadata(&entry_path)
-                    .map_err(|_| "Failed to read file size.".to_string())?
-                    .len(),
-            );
-        }
-    }
-    Ok(total)
-}
-
-#[tauri::command]
-async fn upload_app(request: UploadAppRequest, app: AppHandle) -> Result<(), String> {
-    use base64::{engine::general_purpose, Engine as _};
-    use std::{
-        fs,
-        path::PathBuf,
-        sync::{Arc, Mutex as StdMutex},
-        time::Instant,
-    };
-    use tokio_util::io::ReaderStream;
-
     if request.token.trim().is_empty() {
         return Err("Missing auth token.".to_string());
     }
@@ -340,12 +307,50 @@ async fn upload_app(request: UploadAppRequest, app: AppHandle) -> Result<(), Str
         return Err("Folder not found.".to_string());
     }
 
+    let task = UploadTask {
+        server_ip: request.server_ip.clone(),
+        server_port: request.server_port.clone(),
+        token: request.token.clone(),
+        temp_dir: std::env::temp_dir().join(format!("gaggle_upload_{}", request.id)),
+        cancel: CancellationToken::new(),
+        done: CancellationToken::new(),
+    };
+    {
+        let mut tasks = state.tasks.lock().await;
+        if tasks.contains_key(&request.id) {
+            return Err("Upload already in progress.".to_string());
+        }
+        tasks.insert(request.id.clone(), task.clone());
+    }
+
+    let result = upload_app_task(&request, &folder, &task, &app).await;
+
+    fs::remove_dir_all(&task.temp_dir).ok();
+    {
+        let mut tasks = state.tasks.lock().await;
+        tasks.remove(&request.id);
+    }
+    task.done.cancel();
+    if task.cancel.is_cancelled() {
+        return Err("Upload cancelled.".to_string());
+    }
+    result
+}
+
+async fn upload_app_task(
+    request: &UploadAppRequest,
+    folder: &Path,
+    task: &UploadTask,
+    app: &AppHandle,
+) -> Result<(), String> {
+    use base64::{engine::general_purpose, Engine as _};
+
     // ---------- TEMP ARCHIVE ----------
-    let temp_dir = std::env::temp_dir().join(format!("gaggle_upload_{}", request.id));
+    let temp_dir = &task.temp_dir;
     if temp_dir.exists() {
-        fs::remove_dir_all(&temp_dir).ok();
+        fs::remove_dir_all(temp_dir).ok();
     }
-    fs::create_dir_all(&temp_dir).map_err(|_| "Failed to prepare temp folder.".to_string())?;
+    fs::create_dir_all(temp_dir).map_err(|_| "Failed to prepare temp folder.".to_string())?;
 
     let archive_path = temp_dir.join(format!("{}.tar.gz", request.id));
 
@@ -357,60 +362,50 @@ async fn upload_app(request: UploadAppRequest, app: AppHandle) -> Result<(), Str
         },
     );
 
-    let total_size = dir_total_size(&folder)?;
-    let progress_state = Arc::new(StdMutex::new(UploadProgressState {
-        sent: 0,
-        total: total_size,
-        last_emit: Instant::now(),
-    }));
+    let on_progress: ProgressCallback = {
+        let app = app.clone();
+        let id = request.id.clone();
+        Arc::new(move |sent, total| {
+            let _ = app.emit(
+                "app_upload_progress",
+                UploadProgress {
+                    id: id.clone(),
+                    sent,
+                    total,
+                    pct: (sent as f64 / total.max(1) as f64) * 100.0,
+                },
+            );
+        })
+    };
+
+    let total_size = dir_total_size(folder)?;
+    let progress_state = Arc::new(StdMutex::new(UploadProgressState::new(total_size)));
 
     {
-        let app_clone = app.clone();
-        let id_clone = request.id.clone();
-        let folder_clone = folder.clone();
-        let archive_path_clone = archive_path.clone();
-        let progress_state_clone = progress_state.clone();
-
-        tokio::task::spawn_blocking(move || -> Result<(), String> {
-            let tar_file = fs::File::create(&archive_path_clone)
-                .map_err(|_| "Failed to create archive.".to_string())?;
-
-            let encoder = flate2::write::GzEncoder::new(tar_file, flate2::Compression::fast());
-            let mut builder = tar::Builder::new(encoder);
-
-            add_dir_to_tar(
-                &mut builder,
-                &folder_clone,
-                &folder_clone,
-                &progress_state_clone,
-                &app_clone,
-                &id_clone,
-            )?;
-
-            let encoder = builder
-                .into_inner()
-                .map_err(|_| "Failed to finalize tar.".to_string())?;
-
-            encoder
-                .finish()
-                .map_err(|_| "Failed to finalize gzip.".to_string())?;
-
-            Ok(())
+        let folder = folder.to_path_buf();
+        let archive_path = archive_path.clone();
+        let on_progress = on_progress.clone();
+        let cancel = task.cancel.clone();
+
+        tokio::task::spawn_blocking(move || {
+            build_archive(
+                &folder,
+                &archive_path,
+                &progress_state,
+                &on_progress,
+                &cancel,
+            )
         })
         .await
         .map_err(|_| "Compression task panicked.".to_string())??;
     }
 
+    if task.cancel.is_cancelled() {
+        return Err("Upload cancelled.".to_string());
+    }
+
     let total = if total_size == 0 { 1 } else { total_size };
-    let _ = app.emit(
-        "app_upload_progress",
-        UploadProgress {
-            id: request.id.clone(),
-            sent: total,
-            total,
-            pct: 100.0,
-        },
-    );
+    on_progress(total, total);
 
     // ---------- STREAMING UPLOAD ----------
     let url = crate::net::build_http_url(
@@ -419,37 +414,11 @@ async fn upload_app(request: UploadAppRequest, app: AppHandle) -> Result<(), Str
         &format!("/admin/apps/upload/{}", request.id),
     );
 
-    let file = tokio::fs::File::open(&archive_path)
-        .await
-        .map_err(|_| "Failed to open archive for upload.".to_string())?;
-
-    let stream = ReaderStream::new(file);
-    let app_clone = app.clone();
-    let id_clone = request.id.clone();
-    let sent = Arc::new(StdMutex::new(0u64));
-    let progress_stream = stream.map(move |chunk| {
-        if let Ok(ref bytes) = chunk {
-            let mut sent_guard = sent.lock().unwrap();
-            *sent_guard += bytes.len() as u64;
-            let pct = (*sent_guard as f64 / total as f64) * 100.0;
-            let _ = app_clone.emit(
-                "app_upload_progress",
-                UploadProgress {
-                    id: id_clone.clone(),
-                    sent: *sent_guard,
-                    total,
-                    pct,
-                },
-            );
-        }
-
-        chunk
-    });
-    let body = reqwest::Body::wrap_stream(progress_stream);
+    let body = archive_body(&archive_path, on_progress, task.cancel.clone()).await?;
 
     let config_b64 = general_purpose::STANDARD.encode(&request.config_toml);
 
-    let client = reqwest::Client::new();
+    let client = http_client::client(app, &request.server_ip, &request.server_port)?;
 
     let _ = app.emit(
         "app_upload_stage",
@@ -459,18 +428,20 @@ async fn upload_app(request: UploadAppRequest, app: AppHandle) -> Result<(), Str
         },
     );
 
-    let resp = client
+    let send = client
         .put(url)
         .bearer_auth(&request.token)
         .header("X-App-Config", config_b64)
         .header("Content-Type", "application/octet-stream")
         .body(body)
-        .send()
+        .send();
+    let resp = task
+        .cancel
+        .run_until_cancelled(send)
         .await
+        .ok_or_else(|| "Upload cancelled.".to_string())?
         .map_err(|e| format!("Upload failed: {}", e))?;
 
-    fs::remove_dir_all(&temp_dir).ok();
-
     if !resp.status().is_success() {
         return Err(format!("Upload failed (HTTP {}).", resp.status()));
     }
@@ -478,6 +449,53 @@ async fn upload_app(request: UploadAppRequest, app: AppHandle) -> Result<(), Str
     Ok(())
 }
 
+#[tauri::command]
+async fn cancel_upload(
+    id: String,
+    state: State<'_, UploadManager>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let task = {
+        let tasks = state.tasks.lock().await;
+        tasks
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| "Upload not found.".to_string())?
+    };
+    task.cancel.cancel();
+    let _ = app.emit(
+        "app_upload_stage",
+        UploadStage {
+            id: id.clone(),
+            stage: "cancelled".to_string(),
+        },
+    );
+    // Compression may still be writing to the temp folder; the upload
+    // removes it once it has stopped.
+    task.done.cancelled().await;
+    fs::remove_dir_all(&task.temp_dir).ok();
+
+    // Let the server drop whatever it already received for this upload.
+    let url = crate::net::build_http_url(
+        &task.server_ip,
+        &task.server_port,
+        &format!("/admin/apps/upload/{}", id),
+    );
+    let resp = http_client::client(&app, &task.server_ip, &task.server_port)?
+        .delete(url)
+        .bearer_auth(&task.token)
+        .send()
+        .await
+        .map_err(|_| "Upload cancelled, but the server could not be reached.".to_string())?;
+    if !resp.status().is_success() {
+        return Err(format!(
+            "Upload cancelled, but the server kept the partial upload (HTTP {}).",
+            resp.status()
+        ));
+    }
+    Ok(())
+}
+
 #[tauri::command]
 async fn start_app_download(
     request: DownloadRequest,
@@ -505,6 +523,7 @@ async fn start_app_download(
         archive_url: request.archive_url,
         config_url: request.config_url,
         token: request.token,
+        server: request.server,
         dest_dir,
         app_dir,
         archive_path,
@@ -579,9 +598,16 @@ async fn resume_download(
     state: State<'_, DownloadManager>,
     app: AppHandle,
 ) -> Result<(), String> {
+    // The session may have been renewed since the download started, so the
+    // caller's token replaces the one the download was started with.
     let task = {
-        let tasks = state.tasks.lock().await;
-        tasks.get(&request.id).cloned()
+        let mut tasks = state.tasks.lock().await;
+        tasks.get_mut(&request.id).map(|task| {
+            if !request.token.trim().is_empty() {
+                task.token = request.token.clone();
+            }
+            task.clone()
+        })
     };
 
     let task = if let Some(task) = task {
@@ -597,7 +623,11 @@ async fn resume_download(
             .map_err(|_| "Failed to read download metadata.".to_string())?;
         let meta = serde_json::from_slice::<DownloadMeta>(&bytes)
             .map_err(|_| "Failed to parse download metadata.".to_string())?;
-        let token = meta.token.unwrap_or_else(|| request.token.clone());
+        let token = if request.token.trim().is_empty() {
+            meta.token.unwrap_or_default()
+        } else {
+            request.token.clone()
+        };
         if token.trim().is_empty() {
             return Err("Missing auth token.".to_string());
         }
@@ -609,6 +639,7 @@ async fn resume_download(
             archive_url: meta.archive_url,
             config_url: meta.config_url,
             token,
+            server: meta.server,
             dest_dir: PathBuf::from(meta.dest_dir),
             app_dir,
             archive_path,
@@ -756,8 +787,11 @@ struct RemoveAppRequest {
 }
 
 #[tauri::command]
-async fn remove_installed_app(request: RemoveAppRequest) -> Result<(), String> {
+async fn remove_installed_app(request: RemoveAppRequest, app: AppHandle) -> Result<(), String> {
     let app_dir = PathBuf::from(request.dest_dir).join(&request.id);
+    snapshot_saves(&app, &app_dir, &request.id, "uninstall")
+        .await
+        .map_err(|_| "Failed to back up saves, so the app was not removed.".to_string())?;
     if app_dir.exists() {
         tokio::fs::remove_dir_all(&app_dir)
             .await
@@ -797,6 +831,44 @@ async fn list_installed_apps(request: ListAppsRequest) -> Result<Vec<String>, St
     Ok(results)
 }
 
+const APP_SOURCE_FILE: &str = "source.json";
+
+/// Server each app in the install folder came from, keyed by app id. Apps
+/// still downloading are covered by their download metadata; apps installed
+/// before sources were recorded are left out.
+#[tauri::command]
+async fn list_app_sources(request: ListAppsRequest) -> Result<HashMap<String, String>, String> {
+    let path = PathBuf::from(request.dest_dir);
+    let mut results = HashMap::new();
+    if !path.exists() {
+        return Ok(results);
+    }
+    let mut entries = tokio::fs::read_dir(&path)
+        .await
+        .map_err(|_| "Failed to read install folder.".to_string())?;
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let Some(id) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        let app_dir = entry.path();
+        let server = match tokio::fs::read(app_dir.join(APP_SOURCE_FILE)).await {
+            Ok(bytes) => serde_json::from_slice::<AppSource>(&bytes)
+                .ok()
+                .map(|source| source.server),
+            Err(_) => match tokio::fs::read(app_dir.join("download.json")).await {
+                Ok(bytes) => serde_json::from_slice::<DownloadMeta>(&bytes)
+                    .ok()
+                    .map(|meta| meta.server),
+                Err(_) => None,
+            },
+        };
+        if let Some(server) = server.filter(|server| !server.is_empty()) {
+            results.insert(id, server);
+        }
+    }
+    Ok(results)
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct OpenAppFolderRequest {
@@ -810,6 +882,9 @@ struct RunAppExecutableRequest {
     id: String,
     dest_dir: String,
     executable: String,
+    /// Name of a `[[launch]]` profile from the app config.
+    #[serde(default)]
+    profile: Option<String>,
 }
 
 #[derive(Serialize, Clone)]
@@ -825,24 +900,32 @@ struct RunEvent {
     status: String,
     duration_seconds: u64,
     exit_code: Option<i32>,
+    profile: Option<String>,
+    /// Set on `stopped` when the run ended like a crash.
+    crash: Option<crash::CrashInfo>,
+    /// Error text for `hook_failed`.
+    message: Option<String>,
+    /// Set on `stopped` when usage was sampled during the run.
+    peak: Option<run_stats::PeakStats>,
 }
 
-fn resolve_executable(app_dir: &Path, executable: &str) -> Result<PathBuf, String> {
-    if executable.trim().is_empty() {
-        return Err("Missing executable path.".to_string());
-    }
-    let rel = Path::new(executable);
-    if rel.is_absolute() {
-        return Err("Executable must be a relative path.".to_string());
-    }
-    let mut clean = PathBuf::new();
-    for component in rel.components() {
-        match component {
-            std::path::Component::Normal(part) => clean.push(part),
-            _ => return Err("Executable path is not safe.".to_string()),
+impl RunEvent {
+    fn hook_failed(id: &str, profile: Option<String>, message: String) -> Self {
+        Self {
+            id: id.to_string(),
+            status: "hook_failed".to_string(),
+            duration_seconds: 0,
+            exit_code: None,
+            profile,
+            crash: None,
+            message: Some(message),
+            peak: None,
         }
     }
-    let full = app_dir.join("content").join(clean);
+}
+
+fn resolve_executable(app_dir: &Path, executable: &str) -> Result<PathBuf, String> {
+    let full = launch::resolve_content_path(&app_dir.join("content"), executable, "executable")?;
     if !full.exists() {
         return Err("Executable not found.".to_string());
     }
@@ -879,107 +962,814 @@ async fn run_app_executable_tracked(
     if !app_dir.exists() {
         return Err("App folder not found.".to_string());
     }
-    let exec_path = resolve_executable(&app_dir, &request.executable)?;
+    let mut plan = launch::plan_launch(
+        &app_dir,
+        &request.id,
+        &request.executable,
+        request.profile.as_deref(),
+    )?;
+    let settings = launch_settings::load(&app);
+    let wrapped = launch_settings::apply(&mut plan, &settings, &request.id)?;
+
+    // Held until the run is in `processes` or the launch has failed.
+    let _reservation = state.reserve(&request.id)?;
+    let app_hooks = launch::load_app_config(&app_dir, &request.id)
+        .map(|config| config.hooks)
+        .unwrap_or_default();
+    if !app_hooks.is_empty() {
+        if !hooks::verify_installed(&app, &request.id, &app_hooks, &app_dir) {
+            return Err(
+                "App hooks changed and need approval. Reinstall the app to review them."
+                    .to_string(),
+            );
+        }
+        let hook_dir = app_dir.clone();
+        let pre_hooks = app_hooks.clone();
+        let result = tauri::async_runtime::spawn_blocking(move || {
+            hooks::run_post_install(&hook_dir, &pre_hooks)?;
+            match &pre_hooks.pre_launch {
+                Some(hook) => hooks::run(&hook_dir, hooks::HookStage::PreLaunch, hook),
+                None => Ok(()),
+            }
+        })
+        .await
+        .unwrap_or_else(|_| Err("Failed to run app hooks.".to_string()));
+        if let Err(err) = result {
+            let _ = app.emit(
+                "app_run_event",
+                RunEvent::hook_failed(&request.id, plan.profile.clone(), err.clone()),
+            );
+            return Err(err);
+        }
+    }
+
     let content_dir = app_dir.join("content");
 
-    let mut map = state.processes.lock().unwrap();
-    if map.contains_key(&request.id) {
-        return Err("App is already running.".to_string());
-    }
+    // The lock is held from the running check to the insert so two launches
+    // can't both start the app, and released before a fallback launch is
+    // adopted, which waits.
+    let log;
+    let spawned = {
+        let mut map = state.processes.lock().unwrap();
+        if map.contains_key(&request.id) {
+            return Err("App is already running.".to_string());
+        }
 
-    let exec_path_for_fallback = exec_path.clone();
-    let child = match std::process::Command::new(&exec_path)
-        .current_dir(&content_dir)
-        .spawn()
-    {
-        Ok(child) => child,
-        Err(_) => {
+        // Logging is best effort: if the logs folder can't be written the app
+        // still starts, just with inherited stdio.
+        log = run_logs::SessionLog::create(&app_dir, &request.id, &plan).ok();
+        let mut command = plan.command();
+        if log.is_some() {
+            command
+                .stdout(std::process::Stdio::piped())
+                .stderr(std::process::Stdio::piped());
+        }
+
+        command.spawn().map(|mut child| {
+            let mut readers = Vec::new();
+            if let Some(log) = &log {
+                if let Some(stdout) = child.stdout.take() {
+                    readers.push(log.capture(stdout, "stdout"));
+                }
+                if let Some(stderr) = child.stderr.take() {
+                    readers.push(log.capture(stderr, "stderr"));
+                }
+            }
+
+            let tracker = proc_tree::ProcessTracker::new(
+                Some(child.id()),
+                &content_dir,
+                plan.track_process.clone(),
+            );
+            let start = Instant::now();
+            let child = Arc::new(StdMutex::new(child));
+            let pids = Arc::new(StdMutex::new(Vec::new()));
+            let stop_requested = Arc::new(AtomicBool::new(false));
+            map.insert(
+                request.id.clone(),
+                RunningProcess {
+                    child: Some(child.clone()),
+                    pids: pids.clone(),
+                    stop_requested: stop_requested.clone(),
+                    start,
+                    started_at: unix_now(),
+                    profile: plan.profile.clone(),
+                },
+            );
+            (child, readers, tracker, pids, stop_requested, start)
+        })
+    };
+
+    let (child, readers, tracker, pids, stop_requested, start) = match spawned {
+        Ok(run) => run,
+        Err(err) => {
+            if let Some(log) = &log {
+                log.finish_failed(&err);
+            }
+            if wrapped {
+                return Err("Failed to start the wrapper command.".to_string());
+            }
+            if plan.profile.is_some() || !plan.args.is_empty() {
+                return Err("Failed to launch executable.".to_string());
+            }
             // Fall back to default OS handler (e.g., README.txt).
-            open_path(exec_path_for_fallback, Option::<&str>::None)
+            open_path(plan.program.clone(), Option::<&str>::None)
                 .map_err(|_| "Failed to launch executable.".to_string())?;
-            return Ok(RunStartResult { tracked: false });
+            let tracked =
+                adopt_untracked_run(&app, &request.id, &app_dir, &plan, app_hooks.post_exit).await;
+            return Ok(RunStartResult { tracked });
         }
     };
 
-    let start = Instant::now();
-    let child = Arc::new(StdMutex::new(child));
-    map.insert(
-        request.id.clone(),
-        RunningProcess {
-            child: child.clone(),
+    spawn_run_watcher(
+        app,
+        request.id,
+        plan.profile,
+        RunWatch {
+            child: Some(child),
+            tracker,
+            pids,
+            stop_requested,
+            app_dir,
+            post_exit: app_hooks.post_exit,
+            log,
+            readers,
             start,
         },
     );
-    drop(map);
 
+    Ok(RunStartResult { tracked: true })
+}
+
+struct RunWatch {
+    child: Option<Arc<StdMutex<std::process::Child>>>,
+    tracker: proc_tree::ProcessTracker,
+    pids: Arc<StdMutex<Vec<u32>>>,
+    stop_requested: Arc<AtomicBool>,
+    app_dir: PathBuf,
+    post_exit: Option<hooks::Hook>,
+    log: Option<run_logs::SessionLog>,
+    readers: Vec<std::thread::JoinHandle<()>>,
+    start: Instant,
+}
+
+/// Emits `started`, then polls until neither the direct child nor any
+/// process in its tree is alive, and emits `stopped` with the duration.
+fn spawn_run_watcher(app: AppHandle, id: String, profile: Option<String>, watch: RunWatch) {
     let _ = app.emit(
         "app_run_event",
         RunEvent {
-            id: request.id.clone(),
+            id: id.clone(),
             status: "started".to_string(),
             duration_seconds: 0,
             exit_code: None,
+            profile: profile.clone(),
+            crash: None,
+            message: None,
+            peak: None,
         },
     );
 
-    let app_clone = app.clone();
-    let id_clone = request.id.clone();
     tauri::async_runtime::spawn_blocking(move || {
-        let exit_code = loop {
-            let status = {
-                let mut child = child.lock().unwrap();
-                child.try_wait()
-            };
-            match status {
-                Ok(Some(status)) => break status.code(),
-                Ok(None) => {
-                    std::thread::sleep(Duration::from_millis(250));
+        let RunWatch {
+            child,
+            mut tracker,
+            pids,
+            stop_requested,
+            app_dir,
+            post_exit,
+            log,
+            readers,
+            start,
+        } = watch;
+        // Exit status of the direct child; the run may continue without it.
+        let mut exit_code = None;
+        let mut signal = None;
+        let mut child_running = child.is_some();
+        let mut stats = run_stats::StatsSampler::new();
+        let mut last_sample: Option<Instant> = None;
+        loop {
+            if child_running {
+                let status = child.as_ref().unwrap().lock().unwrap().try_wait();
+                match status {
+                    Ok(Some(status)) => {
+                        exit_code = status.code();
+                        #[cfg(unix)]
+                        {
+                            use std::os::unix::process::ExitStatusExt;
+                            signal = status.signal();
+                        }
+                        child_running = false;
+                    }
+                    Ok(None) => {}
+                    Err(_) => child_running = false,
                 }
-                Err(_) => break None,
             }
-        };
+            let tree_alive = tracker.poll();
+            let tracked = tracker.pids();
+            *pids.lock().unwrap() = tracker.signal_pids();
+            if !child_running && !tree_alive {
+                break;
+            }
+            if last_sample.is_none_or(|at| at.elapsed() >= run_stats::SAMPLE_INTERVAL) {
+                last_sample = Some(Instant::now());
+                if let Some(sample) = stats.sample(&id, &tracked) {
+                    let _ = app.emit("app_run_stats", sample);
+                }
+            }
+            std::thread::sleep(Duration::from_millis(250));
+        }
 
-        let duration_seconds = start.elapsed().as_secs();
-        let _ = app_clone.emit(
+        let duration = start.elapsed();
+        // Journaled before `stopped` goes out so a sync it triggers sees it.
+        let server = app_source(&app_dir).unwrap_or_default();
+        let _ = playtime_journal::record(&app, &id, &server, duration);
+        if let Some(log) = &log {
+            log.finish(readers, exit_code, duration);
+            if let Some(peak) = stats.peak() {
+                log.record_peak(peak);
+            }
+        }
+        let crashed = child.is_some()
+            && crash::is_crash(
+                exit_code,
+                signal,
+                duration,
+                stop_requested.load(Ordering::SeqCst),
+            );
+        let crash = crashed
+            .then(|| crash::collect(&app_dir, &id, log.as_ref().map(|log| log.name()), signal));
+
+        let _ = app.emit(
             "app_run_event",
             RunEvent {
-                id: id_clone.clone(),
+                id: id.clone(),
                 status: "stopped".to_string(),
-                duration_seconds,
+                duration_seconds: duration.as_secs(),
                 exit_code,
+                profile: profile.clone(),
+                crash,
+                message: None,
+                peak: stats.peak().cloned(),
             },
         );
 
-        let state = app_clone.state::<RunManager>();
+        // Still counted as running until cleanup is done, so the app can't
+        // be started again underneath its post-exit hook.
+        if let Some(hook) = post_exit {
+            if let Err(err) = hooks::run(&app_dir, hooks::HookStage::PostExit, &hook) {
+                let _ = app.emit("app_run_event", RunEvent::hook_failed(&id, profile, err));
+            }
+        }
+        saves::sync_after_session(&app, &id, &app_dir);
+
+        let state = app.state::<RunManager>();
         let mut map = state.processes.lock().unwrap();
-        map.remove(&id_clone);
+        map.remove(&id);
     });
+}
 
-    Ok(RunStartResult { tracked: true })
+/// After the OS default handler opened an app, waits a few seconds for a
+/// matching process to show up and tracks it like a normal run.
+async fn adopt_untracked_run(
+    app: &AppHandle,
+    id: &str,
+    app_dir: &Path,
+    plan: &launch::LaunchPlan,
+    post_exit: Option<hooks::Hook>,
+) -> bool {
+    if !proc_tree::ProcessTracker::supported() {
+        return false;
+    }
+    let mut tracker =
+        proc_tree::ProcessTracker::new(None, &app_dir.join("content"), plan.track_process.clone());
+    let found = tauri::async_runtime::spawn_blocking(move || {
+        for _ in 0..20 {
+            if tracker.poll() {
+                return Some(tracker);
+            }
+            std::thread::sleep(Duration::from_millis(500));
+        }
+        None
+    })
+    .await
+    .ok()
+    .flatten();
+    let Some(tracker) = found else {
+        return false;
+    };
+
+    let start = Instant::now();
+    let pids = Arc::new(StdMutex::new(tracker.signal_pids()));
+    let stop_requested = Arc::new(AtomicBool::new(false));
+    {
+        let state = app.state::<RunManager>();
+        let mut map = state.processes.lock().unwrap();
+        if map.contains_key(id) {
+            return true;
+        }
+        map.insert(
+            id.to_string(),
+            RunningProcess {
+                child: None,
+                pids: pids.clone(),
+                stop_requested: stop_requested.clone(),
+                start,
+                started_at: unix_now(),
+                profile: plan.profile.clone(),
+            },
+        );
+    }
+    spawn_run_watcher(
+        app.clone(),
+        id.to_string(),
+        plan.profile.clone(),
+        RunWatch {
+            child: None,
+            tracker,
+            pids,
+            stop_requested,
+            app_dir: app_dir.to_path_buf(),
+            post_exit,
+            log: None,
+            readers: Vec::new(),
+            start,
+        },
+    );
+    true
 }
 
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct StopRunRequest {
+struct AppHooksRequest {
     id: String,
+    /// The app config TOML as served by the backend.
+    config: String,
 }
 
-#[tauri::command]
-fn stop_app_executable_tracked(
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AppHooksReview {
+    hooks: Vec<hooks::HookSummary>,
+    approved: bool,
+}
+
+/// Lists the hooks of an app about to be installed and whether the user
+/// already approved exactly these.
+#[tauri::command]
+fn inspect_app_hooks(request: AppHooksRequest, app: AppHandle) -> Result<AppHooksReview, String> {
+    let app_hooks = hooks::parse(&request.config)?;
+    Ok(AppHooksReview {
+        hooks: app_hooks.summary(),
+        approved: hooks::is_approved(&app, &request.id, &app_hooks),
+    })
+}
+
+#[tauri::command]
+fn approve_app_hooks(request: AppHooksRequest, app: AppHandle) -> Result<(), String> {
+    let app_hooks = hooks::parse(&request.config)?;
+    hooks::approve(&app, &request.id, &app_hooks)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AppLogsRequest {
+    id: String,
+    dest_dir: String,
+}
+
+#[tauri::command]
+fn list_app_logs(request: AppLogsRequest) -> Vec<run_logs::LogEntry> {
+    run_logs::list_logs(&PathBuf::from(request.dest_dir).join(request.id))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReadAppLogRequest {
+    id: String,
+    dest_dir: String,
+    name: String,
+}
+
+#[tauri::command]
+fn read_app_log(request: ReadAppLogRequest) -> Result<String, String> {
+    run_logs::read_log(
+        &PathBuf::from(request.dest_dir).join(request.id),
+        &request.name,
+    )
+}
+
+#[tauri::command]
+fn get_launch_settings(app: AppHandle) -> launch_settings::LaunchSettings {
+    launch_settings::load(&app)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SaveAppLaunchSettingsRequest {
+    id: String,
+    settings: launch_settings::AppLaunchSettings,
+    default_wrapper: String,
+    #[serde(default)]
+    stop_grace_seconds: Option<u64>,
+}
+
+/// Saves one app's overrides together with the global default wrapper and
+/// stop grace period, which the app settings dialog edits alongside them.
+#[tauri::command]
+fn save_app_launch_settings(
+    request: SaveAppLaunchSettingsRequest,
+    app: AppHandle,
+) -> Result<launch_settings::LaunchSettings, String> {
+    let mut settings = launch_settings::load(&app);
+    settings.default_wrapper = request.default_wrapper.trim().to_string();
+    settings.stop_grace_seconds = request.stop_grace_seconds;
+    let app_settings = request.settings;
+    if app_settings.extra_args.trim().is_empty()
+        && app_settings.env.is_empty()
+        && app_settings.wrapper.is_none()
+        && app_settings.save_paths.is_empty()
+    {
+        settings.apps.remove(&request.id);
+    } else {
+        settings.apps.insert(request.id, app_settings);
+    }
+    launch_settings::validate(&settings)?;
+    launch_settings::save(&app, &settings)?;
+    Ok(settings)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NetworkSettingsRequest {
+    server_ip: String,
+    server_port: String,
+}
+
+/// The network settings as the Settings screen edits them: the global ones
+/// plus the CA of the server it is showing.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NetworkSettingsForm {
+    proxy: String,
+    connect_timeout_seconds: Option<u64>,
+    read_timeout_seconds: Option<u64>,
+    user_agent: String,
+    ca_certificate: String,
+}
+
+#[tauri::command]
+fn get_network_settings(request: NetworkSettingsRequest, app: AppHandle) -> NetworkSettingsForm {
+    let settings = http_client::load(&app);
+    let key = http_client::server_key(&request.server_ip, &request.server_port);
+    NetworkSettingsForm {
+        ca_certificate: settings.server_ca.get(&key).cloned().unwrap_or_default(),
+        proxy: settings.proxy,
+        connect_timeout_seconds: settings.connect_timeout_seconds,
+        read_timeout_seconds: settings.read_timeout_seconds,
+        user_agent: settings.user_agent,
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SaveNetworkSettingsRequest {
+    server_ip: String,
+    server_port: String,
+    settings: NetworkSettingsForm,
+}
+
+#[tauri::command]
+fn save_network_settings(
+    request: SaveNetworkSettingsRequest,
+    app: AppHandle,
+) -> Result<(), String> {
+    let mut settings = http_client::load(&app);
+    let form = request.settings;
+    settings.proxy = form.proxy.trim().to_string();
+    settings.connect_timeout_seconds = form.connect_timeout_seconds;
+    settings.read_timeout_seconds = form.read_timeout_seconds;
+    settings.user_agent = form.user_agent.trim().to_string();
+    let key = http_client::server_key(&request.server_ip, &request.server_port);
+    if form.ca_certificate.trim().is_empty() {
+        settings.server_ca.remove(&key);
+    } else {
+        settings
+            .server_ca
+            .insert(key, form.ca_certificate.trim().to_string());
+    }
+    http_client::save(&app, &settings)
+}
+
+/// Runs the RunManager is tracking, so a reloaded Library can pick them up
+/// again.
+#[tauri::command]
+fn list_running_apps(state: State<'_, RunManager>) -> Vec<RunningApp> {
+    let map = state.processes.lock().unwrap();
+    map.iter()
+        .map(|(id, running)| RunningApp {
+            id: id.clone(),
+            profile: running.profile.clone(),
+            started_at: running.started_at,
+            elapsed_seconds: running.start.elapsed().as_secs(),
+            stopping: running.stop_requested.load(Ordering::SeqCst),
+        })
+        .collect()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StopRunRequest {
+    id: String,
+    /// Skip the grace period and kill the whole tree right away.
+    #[serde(default)]
+    force: bool,
+}
+
+/// Asks the app to exit, reports `stopping`, and kills whatever is left of
+/// its process tree once the grace period runs out. `force` kills at once.
+#[tauri::command]
+fn stop_app_executable_tracked(
     request: StopRunRequest,
     state: State<'_, RunManager>,
+    app: AppHandle,
 ) -> Result<(), String> {
     let map = state.processes.lock().unwrap();
     let running = map
         .get(&request.id)
         .ok_or_else(|| "App is not running.".to_string())?;
-    let mut child = running.child.lock().unwrap();
-    child
-        .kill()
-        .map_err(|_| "Failed to stop running app.".to_string())?;
+    let already_stopping = running.stop_requested.swap(true, Ordering::SeqCst);
+    if request.force {
+        if !force_stop(running) {
+            return Err("Failed to stop running app.".to_string());
+        }
+        return Ok(());
+    }
+    if already_stopping {
+        return Ok(());
+    }
+
+    let group = running
+        .child
+        .as_ref()
+        .map(|child| child.lock().unwrap().id());
+    let pids = running.pids.lock().unwrap().clone();
+    if !proc_tree::terminate(group, &pids) {
+        // Nothing took the polite request, so don't make the user wait.
+        if !force_stop(running) {
+            return Err("Failed to stop running app.".to_string());
+        }
+        return Ok(());
+    }
+
+    let _ = app.emit(
+        "app_run_event",
+        RunEvent {
+            id: request.id.clone(),
+            status: "stopping".to_string(),
+            duration_seconds: running.start.elapsed().as_secs(),
+            exit_code: None,
+            profile: None,
+            crash: None,
+            message: None,
+            peak: None,
+        },
+    );
+    let grace = launch_settings::load(&app).stop_grace();
+    let run_flag = running.stop_requested.clone();
+    drop(map);
+
+    std::thread::spawn(move || {
+        std::thread::sleep(grace);
+        let state = app.state::<RunManager>();
+        let map = state.processes.lock().unwrap();
+        // Only escalate against the same run, not one started since.
+        if let Some(running) = map.get(&request.id) {
+            if Arc::ptr_eq(&running.stop_requested, &run_flag) {
+                force_stop(running);
+            }
+        }
+    });
     Ok(())
 }
 
+fn force_stop(running: &RunningProcess) -> bool {
+    let mut stopped = false;
+    let mut group = None;
+    if let Some(child) = &running.child {
+        let mut child = child.lock().unwrap();
+        group = Some(child.id());
+        if matches!(child.try_wait(), Ok(None)) {
+            stopped = child.kill().is_ok();
+        }
+    }
+    let pids = running.pids.lock().unwrap().clone();
+    proc_tree::kill(group, &pids) || stopped
+}
+
+/// Hands a `--run` request from the command line to the Library, once.
+#[tauri::command]
+fn take_pending_launch(app: AppHandle) -> Option<cli::LaunchRequest> {
+    cli::take(&app)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateShortcutRequest {
+    id: String,
+    name: String,
+    #[serde(default)]
+    profile: Option<String>,
+}
+
+/// Creates a desktop shortcut that runs the app through `--run` and returns
+/// where it was saved.
+#[tauri::command]
+fn create_app_shortcut(request: CreateShortcutRequest, app: AppHandle) -> Result<String, String> {
+    shortcut::create(
+        &app,
+        &request.id,
+        &request.name,
+        request
+            .profile
+            .as_deref()
+            .filter(|name| !name.trim().is_empty()),
+    )
+    .map(|path| path.to_string_lossy().to_string())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncPlaytimeRequest {
+    server_ip: String,
+    server_port: String,
+    token: String,
+}
+
+/// Remembers where playtime goes and uploads the sessions still waiting in
+/// the journal. Returns how many were sent.
+#[tauri::command]
+async fn sync_playtime(request: SyncPlaytimeRequest, app: AppHandle) -> Result<usize, String> {
+    playtime_journal::set_target(
+        &app,
+        playtime_journal::SyncTarget {
+            server_ip: request.server_ip,
+            server_port: request.server_port,
+            token: request.token,
+        },
+    );
+    playtime_journal::sync(&app).await
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AppModsRequest {
+    id: String,
+    dest_dir: String,
+}
+
+#[tauri::command]
+fn list_app_mods(request: AppModsRequest) -> Result<mods::ModOverview, String> {
+    mods::list(&PathBuf::from(request.dest_dir), &request.id)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SetAppModsRequest {
+    id: String,
+    dest_dir: String,
+    /// Every mod in load order, with whether it is on.
+    mods: Vec<mods::ModEntry>,
+}
+
+/// Mods and save restores change files the app may have open, so they wait
+/// until it's stopped.
+fn ensure_not_running(state: &RunManager, id: &str, action: &str) -> Result<(), String> {
+    if state.processes.lock().unwrap().contains_key(id) {
+        return Err(format!("Stop the app before {}.", action));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn set_app_mods(
+    request: SetAppModsRequest,
+    state: State<'_, RunManager>,
+) -> Result<mods::ModOverview, String> {
+    ensure_not_running(&state, &request.id, "changing its mods")?;
+    tauri::async_runtime::spawn_blocking(move || {
+        mods::set(&PathBuf::from(request.dest_dir), &request.id, &request.mods)
+    })
+    .await
+    .unwrap_or_else(|_| Err("Failed to apply mods.".to_string()))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RemoveAppModRequest {
+    id: String,
+    mod_id: String,
+    dest_dir: String,
+}
+
+#[tauri::command]
+async fn remove_app_mod(
+    request: RemoveAppModRequest,
+    state: State<'_, RunManager>,
+    app: AppHandle,
+) -> Result<mods::ModOverview, String> {
+    ensure_not_running(&state, &request.id, "changing its mods")?;
+    mods::check_app_id(&request.id)?;
+    // Taking the mod out rewrites files in the base's `content/`, where
+    // saves may live.
+    let base_dir = PathBuf::from(&request.dest_dir).join(&request.id);
+    snapshot_saves(&app, &base_dir, &request.id, "update")
+        .await
+        .map_err(|_| "Failed to back up saves, so the mod was not removed.".to_string())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        mods::remove(
+            &PathBuf::from(request.dest_dir),
+            &request.id,
+            &request.mod_id,
+        )
+    })
+    .await
+    .unwrap_or_else(|_| Err("Failed to remove mod.".to_string()))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CloudSaveRequest {
+    id: String,
+    dest_dir: String,
+}
+
+/// Compares the local save with the cloud one; the Library calls this right
+/// before a launch.
+#[tauri::command]
+async fn check_cloud_save(
+    request: CloudSaveRequest,
+    app: AppHandle,
+) -> Result<saves::CloudSaveStatus, String> {
+    let app_dir = PathBuf::from(request.dest_dir).join(&request.id);
+    saves::check(&app, &app_dir, &request.id).await
+}
+
+#[tauri::command]
+async fn restore_cloud_save(
+    request: CloudSaveRequest,
+    state: State<'_, RunManager>,
+    app: AppHandle,
+) -> Result<(), String> {
+    ensure_not_running(&state, &request.id, "restoring its save")?;
+    let app_dir = PathBuf::from(request.dest_dir).join(&request.id);
+    saves::restore(&app, &app_dir, &request.id).await
+}
+
+/// Keeps this PC's save when both sides changed, replacing the cloud one.
+#[tauri::command]
+async fn upload_cloud_save(request: CloudSaveRequest, app: AppHandle) -> Result<(), String> {
+    let app_dir = PathBuf::from(request.dest_dir).join(&request.id);
+    saves::upload_forced(&app, &app_dir, &request.id).await
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SaveSnapshotsRequest {
+    id: String,
+}
+
+#[tauri::command]
+fn list_save_snapshots(
+    request: SaveSnapshotsRequest,
+    app: AppHandle,
+) -> Vec<snapshots::SnapshotInfo> {
+    snapshots::list(&app, &request.id)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RestoreSnapshotRequest {
+    id: String,
+    dest_dir: String,
+    name: String,
+}
+
+#[tauri::command]
+async fn restore_save_snapshot(
+    request: RestoreSnapshotRequest,
+    state: State<'_, RunManager>,
+    app: AppHandle,
+) -> Result<(), String> {
+    ensure_not_running(&state, &request.id, "restoring its save")?;
+    let app_dir = PathBuf::from(request.dest_dir).join(&request.id);
+    tauri::async_runtime::spawn_blocking(move || {
+        snapshots::restore(&app, &app_dir, &request.id, &request.name)
+    })
+    .await
+    .unwrap_or_else(|_| Err("Failed to restore snapshot.".to_string()))
+}
+
 async fn download_task(task: DownloadTask, app: AppHandle) -> Result<(), String> {
     if task.token.trim().is_empty() {
         task.in_progress.store(false, Ordering::SeqCst);
@@ -994,7 +1784,9 @@ async fn download_task(task: DownloadTask, app: AppHandle) -> Result<(), String>
         .await
         .map_err(|_| "Failed to create app directory.".to_string())?;
 
-    let client = reqwest::Client::new();
+    // Downloads from before sources were recorded have no server and get
+    // the client without a server CA.
+    let client = http_client::client(&app, &task.server, "")?;
 
     // Download config TOML (small)
     let cfg_resp = client
@@ -1003,6 +1795,12 @@ async fn download_task(task: DownloadTask, app: AppHandle) -> Result<(), String>
         .send()
         .await
         .map_err(|_| "Failed to download config.".to_string())?;
+    if !cfg_resp.status().is_success() {
+        return Err(format!(
+            "Failed to download config (HTTP {}).",
+            cfg_resp.status().as_u16()
+        ));
+    }
     let cfg_bytes = cfg_resp
         .bytes()
         .await
@@ -1030,7 +1828,7 @@ async fn download_task(task: DownloadTask, app: AppHandle) -> Result<(), String>
 
     if response.status().as_u16() == 416 {
         // Already fully downloaded
-        extracted_archive(&task.archive_path, &task.app_dir)?;
+        install_downloaded(&task, &app).await?;
         task.in_progress.store(false, Ordering::SeqCst);
         let _ = app.emit(
             "app_download_progress",
@@ -1046,6 +1844,15 @@ async fn download_task(task: DownloadTask, app: AppHandle) -> Result<(), String>
         return Ok(());
     }
 
+    // An expired session must not end up appended to the archive; the
+    // partial download is kept for a resume with a fresh token.
+    if !response.status().is_success() {
+        return Err(format!(
+            "Download failed (HTTP {}).",
+            response.status().as_u16()
+        ));
+    }
+
     if downloaded > 0 && response.status().as_u16() == 200 {
         // Server ignored range, restart download.
         downloaded = 0;
@@ -1197,9 +2004,7 @@ async fn download_task(task: DownloadTask, app: AppHandle) -> Result<(), String>
     )
     .await;
 
-    extracted_archive(&task.archive_path, &task.app_dir)?;
-    let _ = fs::remove_file(&task.archive_path);
-    let _ = fs::remove_file(task.app_dir.join("download.json"));
+    install_downloaded(&task, &app).await?;
     task.in_progress.store(false, Ordering::SeqCst);
 
     let _ = app.emit(
@@ -1229,7 +2034,22 @@ async fn download_task(task: DownloadTask, app: AppHandle) -> Result<(), String>
     Ok(())
 }
 
+/// Installs a fully downloaded archive, records where the app came from and
+/// drops the download leftovers.
+async fn install_downloaded(task: &DownloadTask, app: &AppHandle) -> Result<(), String> {
+    snapshot_before_install(task, app).await?;
+    extracted_archive(&task.archive_path, &task.app_dir)?;
+    run_install_hooks(task, app).await?;
+    apply_mods_after_install(task);
+    write_app_source(task);
+    let _ = fs::remove_file(&task.archive_path);
+    let _ = fs::remove_file(task.app_dir.join("download.json"));
+    Ok(())
+}
+
 fn extracted_archive(archive_path: &Path, app_dir: &Path) -> Result<(), String> {
+    hooks::reset_post_install(app_dir);
+    mods::restore_before_install(app_dir)?;
     let content_dir = app_dir.join("content");
     fs::create_dir_all(&content_dir)
         .map_err(|_| "Failed to create content directory.".to_string())?;
@@ -1242,6 +2062,56 @@ fn extracted_archive(archive_path: &Path, app_dir: &Path) -> Result<(), String>
     Ok(())
 }
 
+/// Runs the freshly installed app's post-install hook. Hooks the user hasn't
+/// approved fail the install instead of being skipped, since the app likely
+/// doesn't work without them.
+async fn run_install_hooks(task: &DownloadTask, app: &AppHandle) -> Result<(), String> {
+    let app_hooks = launch::load_app_config(&task.app_dir, &task.id)
+        .map(|config| config.hooks)
+        .unwrap_or_default();
+    if !hooks::verify_installed(app, &task.id, &app_hooks, &task.app_dir) {
+        return Err(
+            "App hooks changed and need approval. Install the app again to review them."
+                .to_string(),
+        );
+    }
+    let app_dir = task.app_dir.clone();
+    tauri::async_runtime::spawn_blocking(move || hooks::run_post_install(&app_dir, &app_hooks))
+        .await
+        .unwrap_or_else(|_| Err("Failed to run app hooks.".to_string()))
+}
+
+async fn snapshot_saves(
+    app: &AppHandle,
+    app_dir: &Path,
+    id: &str,
+    reason: &'static str,
+) -> Result<(), String> {
+    if !app_dir.join("content").is_dir() {
+        return Ok(());
+    }
+    let (app, app_dir, id) = (app.clone(), app_dir.to_path_buf(), id.to_string());
+    tauri::async_runtime::spawn_blocking(move || {
+        snapshots::take(&app, &app_dir, &id, reason).map(|_| ())
+    })
+    .await
+    .unwrap_or_else(|_| Err("Failed to save snapshot.".to_string()))
+}
+
+/// Unpacking over an existing install can overwrite saves kept in
+/// `content/`, so they are snapshotted first.
+async fn snapshot_before_install(task: &DownloadTask, app: &AppHandle) -> Result<(), String> {
+    snapshot_saves(app, &task.app_dir, &task.id, "update")
+        .await
+        .map_err(|_| "Failed to back up saves before installing.".to_string())
+}
+
+/// A failed overlay leaves the app as installed; the Mods panel applies it
+/// again on the next change.
+fn apply_mods_after_install(task: &DownloadTask) {
+    let _ = mods::after_install(&task.dest_dir, &task.id);
+}
+
 async fn update_status(
     status: &Arc<Mutex<HashMap<String, DownloadSnapshot>>>,
     snapshot: DownloadSnapshot,
@@ -1250,6 +2120,32 @@ async fn update_status(
     map.insert(snapshot.id.clone(), snapshot);
 }
 
+#[derive(Serialize, Deserialize)]
+struct AppSource {
+    server: String,
+}
+
+/// Server an installed app came from, if it was recorded.
+pub(crate) fn app_source(app_dir: &Path) -> Option<String> {
+    fs::read(app_dir.join(APP_SOURCE_FILE))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<AppSource>(&bytes).ok())
+        .map(|source| source.server)
+        .filter(|server| !server.is_empty())
+}
+
+/// Records which server an installed app came from, for `list_app_sources`.
+fn write_app_source(task: &DownloadTask) {
+    if task.server.is_empty() {
+        return;
+    }
+    if let Ok(data) = serde_json::to_vec_pretty(&AppSource {
+        server: task.server.clone(),
+    }) {
+        let _ = fs::write(task.app_dir.join(APP_SOURCE_FILE), data);
+    }
+}
+
 async fn write_download_meta(task: &DownloadTask, total: Option<u64>) -> Result<(), String> {
     let meta = DownloadMeta {
         id: task.id.clone(),
@@ -1259,6 +2155,7 @@ async fn write_download_meta(task: &DownloadTask, total: Option<u64>) -> Result<
         dest_dir: task.dest_dir.to_string_lossy().to_string(),
         token: Some(task.token.clone()),
         total,
+        server: task.server.clone(),
     };
     let path = task.app_dir.join("download.json");
     let data = serde_json::to_vec_pretty(&meta)
@@ -1272,10 +2169,29 @@ async fn write_download_meta(task: &DownloadTask, total: Option<u64>) -> Result<
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
+        // Must come first so a second instance exits before doing anything.
+        .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+            cli::focus_main_window(app);
+            if let Some(request) = cli::parse_args(&argv) {
+                cli::dispatch(app, request);
+            }
+        }))
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_opener::init())
         .manage(DownloadManager::default())
+        .manage(UploadManager::default())
         .manage(RunManager::default())
+        .manage(playtime_journal::PlaytimeSync::default())
+        .manage(http_client::HttpClients::default())
+        .manage(cli::PendingLaunch::default())
+        .setup(|app| {
+            let args: Vec<String> = std::env::args().collect();
+            if let Some(request) = cli::parse_args(&args) {
+                cli::dispatch(app.handle(), request);
+            }
+            playtime_journal::spawn_background_sync(app.handle().clone());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             get_app_version,
             check_and_install_update,
@@ -1289,12 +2205,49 @@ pub fn run() {
             list_downloads,
             remove_installed_app,
             list_installed_apps,
+            list_app_sources,
             open_app_folder,
             run_app_executable,
             run_app_executable_tracked,
             stop_app_executable_tracked,
-            upload_app
+            list_running_apps,
+            take_pending_launch,
+            create_app_shortcut,
+            sync_playtime,
+            list_app_mods,
+            set_app_mods,
+            remove_app_mod,
+            check_cloud_save,
+            restore_cloud_save,
+            upload_cloud_save,
+            list_save_snapshots,
+            restore_save_snapshot,
+            get_launch_settings,
+            get_network_settings,
+            save_network_settings,
+            list_app_logs,
+            read_app_log,
+            inspect_app_hooks,
+            approve_app_hooks,
+            save_app_launch_settings,
+            upload_app,
+            cancel_upload
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_launch_reservation_refuses_a_second_launch() {
+        let runs = RunManager::default();
+        let reservation = runs.reserve("demo").unwrap();
+        assert!(runs.reserve("demo").is_err());
+        assert!(runs.reserve("other").is_ok());
+        drop(reservation);
+        assert!(runs.reserve("demo").is_ok());
+    }
+}
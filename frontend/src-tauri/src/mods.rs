@@ -0,0 +1,626 @@
+//! Mod packages layered over a base app's `content/`.
+//!
+//! A mod is installed like any other app (`<install>/<mod>/content`) and
+//! names the app it belongs to with `base_app` in its config. Enabled mods
+//! are copied over `<install>/<base>/content` in load order, so when two
+//! mods ship the same file the later one wins. A base file is copied to
+//! `<base>/mod_backup/` before it is first overwritten, and files or folders
+//! a mod adds are recorded, so restoring puts the base back byte for byte.
+//!
+//! `<base>/mods.json` holds the load order, which mods are on, and every file
+//! the overlay currently owns. Any change restores the base first and then
+//! applies the enabled mods again from scratch.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::launch;
+
+const STATE_FILE: &str = "mods.json";
+const BACKUP_DIR: &str = "mod_backup";
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModEntry {
+    pub id: String,
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct AppliedFile {
+    owner: String,
+    /// Whether the base had this file; if not, restoring deletes it.
+    had_original: bool,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct ModState {
+    /// Load order; later entries win conflicts.
+    #[serde(default)]
+    mods: Vec<ModEntry>,
+    /// Files the overlay wrote, relative to `content/`.
+    #[serde(default)]
+    applied: BTreeMap<String, AppliedFile>,
+    /// Folders the overlay created, parents first.
+    #[serde(default)]
+    created_dirs: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModInfo {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub enabled: bool,
+    pub files: usize,
+}
+
+/// A file shipped by more than one enabled mod.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModConflict {
+    pub path: String,
+    /// In load order; the last one is the file that ends up in `content/`.
+    pub mods: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModOverview {
+    pub mods: Vec<ModInfo>,
+    pub conflicts: Vec<ModConflict>,
+}
+
+fn read_state(base_dir: &Path) -> ModState {
+    fs::read(base_dir.join(STATE_FILE))
+        .ok()
+        .and_then(|raw| serde_json::from_slice(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn write_state(base_dir: &Path, state: &ModState) -> Result<(), String> {
+    let path = base_dir.join(STATE_FILE);
+    let raw =
+        serde_json::to_vec_pretty(state).map_err(|_| "Failed to encode mod state.".to_string())?;
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, raw).map_err(|_| "Failed to save mod state.".to_string())?;
+    fs::rename(&tmp, path).map_err(|_| "Failed to save mod state.".to_string())
+}
+
+/// Ids come from the webview and become folder names under the install
+/// root, so they follow the server's app id rules.
+pub fn check_app_id(id: &str) -> Result<(), String> {
+    if id.trim().is_empty() || id.contains(['/', '\\']) || id.contains("..") {
+        return Err("Invalid app id.".to_string());
+    }
+    Ok(())
+}
+
+/// Installed mods whose config names `base_id` as their base app.
+fn installed_mods(install_dir: &Path, base_id: &str) -> BTreeMap<String, launch::AppConfig> {
+    let mut found = BTreeMap::new();
+    let Ok(entries) = fs::read_dir(install_dir) else {
+        return found;
+    };
+    for entry in entries.flatten() {
+        let Some(id) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        if id == base_id || !entry.path().join("content").is_dir() {
+            continue;
+        }
+        if let Ok(config) = launch::load_app_config(&entry.path(), &id) {
+            if config.base_app.as_deref().map(str::trim) == Some(base_id) {
+                found.insert(id, config);
+            }
+        }
+    }
+    found
+}
+
+/// Drops mods that are no longer installed and appends new ones, disabled,
+/// at the end of the load order.
+fn sync_mod_list(state: &mut ModState, installed: &BTreeMap<String, launch::AppConfig>) {
+    state.mods.retain(|entry| installed.contains_key(&entry.id));
+    for id in installed.keys() {
+        if !state.mods.iter().any(|entry| &entry.id == id) {
+            state.mods.push(ModEntry {
+                id: id.clone(),
+                enabled: false,
+            });
+        }
+    }
+}
+
+/// Regular files under `dir`, as `/`-separated paths relative to it.
+fn list_files(dir: &Path) -> io::Result<Vec<String>> {
+    let mut files = Vec::new();
+    let mut pending = vec![PathBuf::new()];
+    while let Some(rel) = pending.pop() {
+        for entry in fs::read_dir(dir.join(&rel))? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            let child = rel.join(entry.file_name());
+            if file_type.is_dir() {
+                pending.push(child);
+            } else if file_type.is_file() {
+                files.push(
+                    child
+                        .components()
+                        .map(|part| part.as_os_str().to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("/"),
+                );
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn copy_file(from: &Path, to: &Path) -> io::Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::copy(from, to).map(|_| ())
+}
+
+/// Puts every file the overlay touched back the way the base had it. Entries
+/// that fail stay recorded so the next attempt picks them up.
+fn restore(base_dir: &Path, state: &mut ModState) -> Result<(), String> {
+    let content_dir = base_dir.join("content");
+    let backup_dir = base_dir.join(BACKUP_DIR);
+    let mut failed = BTreeMap::new();
+    for (rel, file) in std::mem::take(&mut state.applied) {
+        let target = content_dir.join(&rel);
+        let result = if file.had_original {
+            copy_file(&backup_dir.join(&rel), &target)
+        } else {
+            match fs::remove_file(&target) {
+                Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+                _ => Ok(()),
+            }
+        };
+        if result.is_err() {
+            failed.insert(rel, file);
+        }
+    }
+    if !failed.is_empty() {
+        state.applied = failed;
+        return Err("Failed to restore the app's original files.".to_string());
+    }
+    // Children were recorded after their parents, so this empties them first.
+    for rel in state.created_dirs.drain(..).rev() {
+        let _ = fs::remove_dir(content_dir.join(rel));
+    }
+    if backup_dir.exists() {
+        fs::remove_dir_all(&backup_dir).map_err(|_| "Failed to clear mod backups.".to_string())?;
+    }
+    Ok(())
+}
+
+/// Creates the parent folders of `rel` under `content_dir`, recording the
+/// ones that didn't exist yet.
+fn create_parents(content_dir: &Path, rel: &str, created: &mut Vec<String>) -> io::Result<()> {
+    let mut parts: Vec<&str> = rel.split('/').collect();
+    parts.pop();
+    let mut current = String::new();
+    for part in parts {
+        if !current.is_empty() {
+            current.push('/');
+        }
+        current.push_str(part);
+        let dir = content_dir.join(&current);
+        if !dir.is_dir() {
+            fs::create_dir(&dir)?;
+            created.push(current.clone());
+        }
+    }
+    Ok(())
+}
+
+fn overlay(install_dir: &Path, base_dir: &Path, state: &mut ModState) -> Result<(), String> {
+    let content_dir = base_dir.join("content");
+    let backup_dir = base_dir.join(BACKUP_DIR);
+    let enabled: Vec<String> = state
+        .mods
+        .iter()
+        .filter(|entry| entry.enabled)
+        .map(|entry| entry.id.clone())
+        .collect();
+    for id in enabled {
+        let mod_content = install_dir.join(&id).join("content");
+        let files = list_files(&mod_content).map_err(|_| format!("Failed to read mod {}.", id))?;
+        for rel in files {
+            let target = content_dir.join(&rel);
+            if let Some(file) = state.applied.get_mut(&rel) {
+                // An earlier mod already saved the original.
+                file.owner = id.clone();
+            } else {
+                let had_original = target.is_file();
+                if had_original {
+                    copy_file(&target, &backup_dir.join(&rel))
+                        .map_err(|_| "Failed to back up the app's original files.".to_string())?;
+                } else {
+                    create_parents(&content_dir, &rel, &mut state.created_dirs)
+                        .map_err(|_| format!("Failed to apply mod {}.", id))?;
+                }
+                state.applied.insert(
+                    rel.clone(),
+                    AppliedFile {
+                        owner: id.clone(),
+                        had_original,
+                    },
+                );
+            }
+            fs::copy(mod_content.join(&rel), &target)
+                .map_err(|_| format!("Failed to apply mod {}.", id))?;
+        }
+    }
+    Ok(())
+}
+
+/// Restores the base and applies the enabled mods again. The state is saved
+/// even when something fails, so a later attempt knows what is on disk.
+fn reapply(install_dir: &Path, base_dir: &Path, state: &mut ModState) -> Result<(), String> {
+    let result = restore(base_dir, state).and_then(|_| overlay(install_dir, base_dir, state));
+    write_state(base_dir, state)?;
+    result
+}
+
+fn overview(
+    install_dir: &Path,
+    state: &ModState,
+    installed: &BTreeMap<String, launch::AppConfig>,
+) -> ModOverview {
+    let mut owners: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut mods = Vec::new();
+    for entry in &state.mods {
+        let files = list_files(&install_dir.join(&entry.id).join("content")).unwrap_or_default();
+        let config = installed.get(&entry.id);
+        mods.push(ModInfo {
+            id: entry.id.clone(),
+            name: config
+                .map(|config| config.name.trim().to_string())
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| entry.id.clone()),
+            version: config
+                .map(|config| config.version.clone())
+                .unwrap_or_default(),
+            enabled: entry.enabled,
+            files: files.len(),
+        });
+        if entry.enabled {
+            for rel in files {
+                owners.entry(rel).or_default().push(entry.id.clone());
+            }
+        }
+    }
+    let conflicts = owners
+        .into_iter()
+        .filter(|(_, mods)| mods.len() > 1)
+        .map(|(path, mods)| ModConflict { path, mods })
+        .collect();
+    ModOverview { mods, conflicts }
+}
+
+/// Lists the mods installed for `base_id` in load order, with conflicts.
+pub fn list(install_dir: &Path, base_id: &str) -> Result<ModOverview, String> {
+    check_app_id(base_id)?;
+    let installed = installed_mods(install_dir, base_id);
+    let mut state = read_state(&install_dir.join(base_id));
+    sync_mod_list(&mut state, &installed);
+    Ok(overview(install_dir, &state, &installed))
+}
+
+/// Replaces the load order and enabled set with `order`, then applies it.
+/// Installed mods missing from `order` keep their place at the end, off.
+pub fn set(install_dir: &Path, base_id: &str, order: &[ModEntry]) -> Result<ModOverview, String> {
+    check_app_id(base_id)?;
+    let base_dir = install_dir.join(base_id);
+    if !base_dir.join("content").is_dir() {
+        return Err("App is not installed.".to_string());
+    }
+    let installed = installed_mods(install_dir, base_id);
+    let mut state = read_state(&base_dir);
+    let mut seen = HashSet::new();
+    state.mods = order
+        .iter()
+        .filter(|entry| seen.insert(entry.id.clone()))
+        .cloned()
+        .collect();
+    sync_mod_list(&mut state, &installed);
+    reapply(install_dir, &base_dir, &mut state)?;
+    Ok(overview(install_dir, &state, &installed))
+}
+
+/// Takes a mod's files out of the base and deletes the mod. Only an
+/// installed mod of `base_id` can be deleted this way.
+pub fn remove(install_dir: &Path, base_id: &str, mod_id: &str) -> Result<ModOverview, String> {
+    check_app_id(base_id)?;
+    check_app_id(mod_id)?;
+    if mod_id == base_id {
+        return Err("An app is not a mod of itself.".to_string());
+    }
+    if !installed_mods(install_dir, base_id).contains_key(mod_id) {
+        return Err("Mod is not installed for this app.".to_string());
+    }
+    let base_dir = install_dir.join(base_id);
+    let mut state = read_state(&base_dir);
+    state.mods.retain(|entry| entry.id != mod_id);
+    reapply(install_dir, &base_dir, &mut state)?;
+    let mod_dir = install_dir.join(mod_id);
+    if mod_dir.exists() {
+        fs::remove_dir_all(&mod_dir).map_err(|_| "Failed to remove mod.".to_string())?;
+    }
+    let installed = installed_mods(install_dir, base_id);
+    sync_mod_list(&mut state, &installed);
+    Ok(overview(install_dir, &state, &installed))
+}
+
+/// Called before a new archive is unpacked into `app_dir`, so the base files
+/// are pristine and the backups don't go stale.
+pub fn restore_before_install(app_dir: &Path) -> Result<(), String> {
+    let mut state = read_state(app_dir);
+    if state.applied.is_empty() && state.created_dirs.is_empty() {
+        return Ok(());
+    }
+    let result = restore(app_dir, &mut state);
+    write_state(app_dir, &state)?;
+    result
+}
+
+/// Brings the overlay up to date once `id` finished installing: a base gets
+/// its enabled mods back, and an updated mod that is enabled is applied again.
+pub fn after_install(install_dir: &Path, id: &str) -> Result<(), String> {
+    let base_id = match launch::load_app_config(&install_dir.join(id), id)
+        .ok()
+        .and_then(|config| config.base_app)
+    {
+        Some(base) if !base.trim().is_empty() && base.trim() != id => base.trim().to_string(),
+        _ => id.to_string(),
+    };
+    let base_dir = install_dir.join(&base_id);
+    if !base_dir.join("content").is_dir() {
+        return Ok(());
+    }
+    let mut state = read_state(&base_dir);
+    if !state.mods.iter().any(|entry| entry.enabled) {
+        return Ok(());
+    }
+    let installed = installed_mods(install_dir, &base_id);
+    sync_mod_list(&mut state, &installed);
+    reapply(install_dir, &base_dir, &mut state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// A fresh install folder with a base app `game`, a mod `extra` for it
+    /// and an unrelated app `other`.
+    fn install_dir() -> PathBuf {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "gaggle-mods-{}-{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        for (id, config) in [
+            ("game", "name = \"Game\"\n"),
+            ("extra", "name = \"Extra\"\nbase_app = \"game\"\n"),
+            ("other", "name = \"Other\"\n"),
+        ] {
+            let app_dir = dir.join(id);
+            fs::create_dir_all(app_dir.join("content")).unwrap();
+            fs::write(app_dir.join(format!("{}.toml", id)), config).unwrap();
+        }
+        dir
+    }
+
+    /// Adds another mod of `game` named `id`.
+    fn add_mod(dir: &Path, id: &str) {
+        let app_dir = dir.join(id);
+        fs::create_dir_all(app_dir.join("content")).unwrap();
+        fs::write(
+            app_dir.join(format!("{}.toml", id)),
+            format!("name = \"{}\"\nbase_app = \"game\"\n", id),
+        )
+        .unwrap();
+    }
+
+    fn write_file(path: &Path, bytes: &[u8]) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, bytes).unwrap();
+    }
+
+    /// Every file under `dir` with its bytes.
+    fn snapshot(dir: &Path) -> BTreeMap<String, Vec<u8>> {
+        list_files(dir)
+            .unwrap()
+            .into_iter()
+            .map(|rel| {
+                let bytes = fs::read(dir.join(&rel)).unwrap();
+                (rel, bytes)
+            })
+            .collect()
+    }
+
+    fn enabled(ids: &[&str]) -> Vec<ModEntry> {
+        ids.iter()
+            .map(|id| ModEntry {
+                id: id.to_string(),
+                enabled: true,
+            })
+            .collect()
+    }
+
+    fn assert_untouched(dir: &Path) {
+        for id in ["game", "extra", "other"] {
+            assert!(dir.join(id).join("content").is_dir(), "{} was removed", id);
+        }
+    }
+
+    #[test]
+    fn remove_rejects_an_empty_mod_id() {
+        let dir = install_dir();
+        assert!(remove(&dir, "game", "").is_err());
+        assert!(remove(&dir, "game", " ").is_err());
+        assert_untouched(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn remove_rejects_path_separators_and_parent_dirs() {
+        let dir = install_dir();
+        for mod_id in ["../game", "extra/..", "game/content", "..\\other", ".."] {
+            assert!(
+                remove(&dir, "game", mod_id).is_err(),
+                "{mod_id} was accepted"
+            );
+        }
+        for base_id in ["", "../game", "a/b", "a\\b"] {
+            assert!(
+                remove(&dir, base_id, "extra").is_err(),
+                "{base_id} was accepted"
+            );
+        }
+        assert_untouched(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn remove_rejects_the_base_app_itself() {
+        let dir = install_dir();
+        assert!(remove(&dir, "game", "game").is_err());
+        assert_untouched(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn remove_rejects_an_app_that_is_not_a_mod_of_the_base() {
+        let dir = install_dir();
+        assert!(remove(&dir, "game", "other").is_err());
+        assert!(remove(&dir, "other", "extra").is_err());
+        assert!(remove(&dir, "game", "missing").is_err());
+        assert_untouched(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn remove_deletes_an_installed_mod() {
+        let dir = install_dir();
+        let overview = remove(&dir, "game", "extra").unwrap();
+        assert!(overview.mods.is_empty());
+        assert!(!dir.join("extra").exists());
+        assert!(dir.join("game").join("content").is_dir());
+        assert!(dir.join("other").join("content").is_dir());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn disabling_a_mod_restores_the_base_byte_for_byte() {
+        let dir = install_dir();
+        let content = dir.join("game").join("content");
+        write_file(&content.join("data.txt"), b"base data\n");
+        write_file(
+            &content.join("bin").join("game.bin"),
+            &[0, 159, 146, 150, 255],
+        );
+        let original = snapshot(&content);
+        let extra = dir.join("extra").join("content");
+        write_file(&extra.join("data.txt"), b"modded data\n");
+        write_file(&extra.join("bin").join("game.bin"), &[1, 2, 3]);
+        write_file(
+            &extra.join("levels").join("new").join("map.txt"),
+            b"new map",
+        );
+
+        set(&dir, "game", &enabled(&["extra"])).unwrap();
+        assert_eq!(
+            fs::read(content.join("data.txt")).unwrap(),
+            b"modded data\n"
+        );
+        assert_eq!(
+            fs::read(content.join("bin").join("game.bin")).unwrap(),
+            [1, 2, 3]
+        );
+        assert!(content.join("levels").join("new").join("map.txt").is_file());
+
+        let off = [ModEntry {
+            id: "extra".to_string(),
+            enabled: false,
+        }];
+        let overview = set(&dir, "game", &off).unwrap();
+        assert!(!overview.mods[0].enabled);
+        assert_eq!(snapshot(&content), original);
+        assert!(!content.join("levels").exists());
+        assert!(!dir.join("game").join(BACKUP_DIR).exists());
+        assert_eq!(snapshot(&extra).len(), 3);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn removing_conflicting_mods_restores_the_base_byte_for_byte() {
+        let dir = install_dir();
+        add_mod(&dir, "patch");
+        let content = dir.join("game").join("content");
+        write_file(&content.join("data.txt"), b"base data\n");
+        write_file(&content.join("keep.txt"), b"keep");
+        let original = snapshot(&content);
+        let extra = dir.join("extra").join("content");
+        let patch = dir.join("patch").join("content");
+        write_file(&extra.join("data.txt"), b"extra data\n");
+        write_file(&patch.join("data.txt"), b"patch data\n");
+        write_file(&extra.join("shared").join("added.txt"), b"from extra");
+        write_file(&patch.join("shared").join("added.txt"), b"from patch");
+
+        let overview = set(&dir, "game", &enabled(&["extra", "patch"])).unwrap();
+        let paths: Vec<&str> = overview.conflicts.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(paths, ["data.txt", "shared/added.txt"]);
+        assert_eq!(overview.conflicts[0].mods, ["extra", "patch"]);
+        assert_eq!(fs::read(content.join("data.txt")).unwrap(), b"patch data\n");
+        assert_eq!(
+            fs::read(content.join("shared").join("added.txt")).unwrap(),
+            b"from patch"
+        );
+
+        // The later mod's files go, and the earlier one's come back.
+        remove(&dir, "game", "patch").unwrap();
+        assert!(!dir.join("patch").exists());
+        assert_eq!(fs::read(content.join("data.txt")).unwrap(), b"extra data\n");
+        assert_eq!(
+            fs::read(content.join("shared").join("added.txt")).unwrap(),
+            b"from extra"
+        );
+
+        remove(&dir, "game", "extra").unwrap();
+        assert_eq!(snapshot(&content), original);
+        assert!(!content.join("shared").exists());
+        assert!(!dir.join("game").join(BACKUP_DIR).exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn list_and_set_reject_invalid_base_ids() {
+        let dir = install_dir();
+        assert!(list(&dir, "").is_err());
+        assert!(list(&dir, "../game").is_err());
+        assert!(set(&dir, "", &[]).is_err());
+        assert!(set(&dir, "game/..", &[]).is_err());
+        assert_eq!(list(&dir, "game").unwrap().mods.len(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,265 @@
+//! Local journal of finished play sessions.
+//!
+//! The run watcher appends every session here as soon as it ends, whether or
+//! not the Library is open. Sessions are uploaded to `/apps/:id/playtime`
+//! with their `session_id` and only marked as synced once the server accepted
+//! them; the server ignores a session id it has already counted and still
+//! answers with success, so a retry after a lost response can't add the time
+//! twice. A session the server refuses stays queued.
+//!
+//! Each session remembers the server its app was installed from and is only
+//! uploaded there, so playtime never lands on another server that happens to
+//! have an app with the same id.
+//!
+//! The Library hands over the server address and token through
+//! `sync_playtime`; from then on a background task retries every
+//! `SYNC_INTERVAL` until the journal is empty.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex as StdMutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tokio::sync::Mutex;
+
+const JOURNAL_FILE: &str = "playtime_journal.json";
+const SYNC_INTERVAL: Duration = Duration::from_secs(60);
+/// Synced sessions kept around for reference; older ones are dropped.
+const MAX_SYNCED: usize = 100;
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+    pub session_id: String,
+    pub app_id: String,
+    /// Server the app came from, as its `server_key`. Empty for sessions
+    /// journaled before it was recorded, which go to whichever server syncs first.
+    #[serde(default)]
+    pub server: String,
+    pub started_at: u64,
+    pub ended_at: u64,
+    pub duration_seconds: u64,
+    #[serde(default)]
+    pub synced: bool,
+}
+
+#[derive(Clone)]
+pub struct SyncTarget {
+    pub server_ip: String,
+    pub server_port: String,
+    pub token: String,
+}
+
+impl SyncTarget {
+    /// The server as apps record where they came from.
+    pub fn server_key(&self) -> String {
+        crate::http_client::server_key(&self.server_ip, &self.server_port)
+    }
+}
+
+#[derive(Default)]
+pub struct PlaytimeSync {
+    target: StdMutex<Option<SyncTarget>>,
+    /// Held for the whole upload so two syncs never send the same session.
+    running: Mutex<()>,
+}
+
+/// Guards read-modify-write of the journal file between the run watchers
+/// and the sync.
+static JOURNAL_LOCK: StdMutex<()> = StdMutex::new(());
+
+fn journal_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|_| "Failed to locate app data folder.".to_string())?;
+    Ok(dir.join(JOURNAL_FILE))
+}
+
+fn read_journal(path: &Path) -> Vec<JournalEntry> {
+    fs::read(path)
+        .ok()
+        .and_then(|raw| serde_json::from_slice(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn write_journal(path: &Path, entries: &[JournalEntry]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|_| "Failed to create app data folder.".to_string())?;
+    }
+    let raw = serde_json::to_vec_pretty(entries)
+        .map_err(|_| "Failed to encode playtime journal.".to_string())?;
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, raw).map_err(|_| "Failed to save playtime journal.".to_string())?;
+    fs::rename(&tmp, path).map_err(|_| "Failed to save playtime journal.".to_string())
+}
+
+fn update_journal(
+    app: &AppHandle,
+    change: impl FnOnce(&mut Vec<JournalEntry>),
+) -> Result<(), String> {
+    let path = journal_path(app)?;
+    let _guard = JOURNAL_LOCK.lock().unwrap();
+    let mut entries = read_journal(&path);
+    change(&mut entries);
+    write_journal(&path, &entries)
+}
+
+/// Appends a finished session of an app installed from `server`. Sessions
+/// shorter than a second aren't playtime and are skipped.
+pub fn record(
+    app: &AppHandle,
+    app_id: &str,
+    server: &str,
+    duration: Duration,
+) -> Result<(), String> {
+    let duration_seconds = duration.as_secs();
+    if duration_seconds == 0 {
+        return Ok(());
+    }
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let ended_at = now.as_secs();
+    let entry = JournalEntry {
+        session_id: format!("{:x}-{:x}", now.as_nanos(), std::process::id()),
+        app_id: app_id.to_string(),
+        server: server.to_string(),
+        started_at: ended_at.saturating_sub(duration_seconds),
+        ended_at,
+        duration_seconds,
+        synced: false,
+    };
+    update_journal(app, |entries| entries.push(entry))
+}
+
+pub fn set_target(app: &AppHandle, target: SyncTarget) {
+    let sync = app.state::<PlaytimeSync>();
+    *sync.target.lock().unwrap() = Some(target);
+}
+
+/// The server and token last handed over; cloud saves use them too.
+pub fn target(app: &AppHandle) -> Option<SyncTarget> {
+    app.state::<PlaytimeSync>().target.lock().unwrap().clone()
+}
+
+/// What the server made of an uploaded session.
+enum UploadOutcome {
+    /// Counted now or already counted before.
+    Counted,
+    /// Refused; the session stays queued for a later attempt.
+    Refused,
+}
+
+async fn upload(
+    client: &reqwest::Client,
+    target: &SyncTarget,
+    entry: &JournalEntry,
+) -> Result<UploadOutcome, String> {
+    let url = crate::net::build_http_url(
+        &target.server_ip,
+        &target.server_port,
+        &format!("/apps/{}/playtime", entry.app_id),
+    );
+    let body = serde_json::json!({
+        "seconds": entry.duration_seconds,
+        "ended_at": entry.ended_at,
+        "session_id": entry.session_id,
+    });
+    let resp = client
+        .post(url)
+        .bearer_auth(&target.token)
+        .header("Content-Type", "application/json")
+        .body(body.to_string())
+        .send()
+        .await
+        .map_err(|_| "Server not reachable.".to_string())?;
+    let status = resp.status();
+    if status.is_success() {
+        Ok(UploadOutcome::Counted)
+    } else if status.is_client_error() {
+        Ok(UploadOutcome::Refused)
+    } else {
+        Err(format!("Playtime upload failed (HTTP {}).", status))
+    }
+}
+
+/// Uploads every session not yet synced to the server it belongs to and
+/// returns how many went through. A refused session is skipped; any other
+/// failure stops the sync and the rest wait for the next attempt.
+pub async fn sync(app: &AppHandle) -> Result<usize, String> {
+    let state = app.state::<PlaytimeSync>();
+    let _running = state.running.lock().await;
+    let Some(target) = target(app) else {
+        return Ok(0);
+    };
+    let server = target.server_key();
+    let pending: Vec<JournalEntry> = {
+        let path = journal_path(app)?;
+        let _guard = JOURNAL_LOCK.lock().unwrap();
+        read_journal(&path)
+            .into_iter()
+            .filter(|entry| !entry.synced && (entry.server.is_empty() || entry.server == server))
+            .collect()
+    };
+    if pending.is_empty() {
+        return Ok(0);
+    }
+
+    let client = crate::http_client::client(app, &target.server_ip, &target.server_port)?;
+    let mut done = Vec::new();
+    let mut failure = None;
+    for entry in &pending {
+        match upload(&client, &target, entry).await {
+            Ok(UploadOutcome::Counted) => done.push(entry.session_id.clone()),
+            Ok(UploadOutcome::Refused) => {}
+            Err(err) => {
+                failure = Some(err);
+                break;
+            }
+        }
+    }
+
+    let synced = done.len();
+    if synced > 0 {
+        update_journal(app, |entries| {
+            for entry in entries.iter_mut() {
+                if done.contains(&entry.session_id) {
+                    entry.synced = true;
+                }
+            }
+            let extra = entries
+                .iter()
+                .filter(|entry| entry.synced)
+                .count()
+                .saturating_sub(MAX_SYNCED);
+            let mut dropped = 0;
+            entries.retain(|entry| {
+                if entry.synced && dropped < extra {
+                    dropped += 1;
+                    return false;
+                }
+                true
+            });
+        })?;
+    }
+    match failure {
+        Some(err) => Err(err),
+        None => Ok(synced),
+    }
+}
+
+/// Retries pending uploads in the background, so sessions recorded while
+/// the server was down reach it without the Library doing anything.
+pub fn spawn_background_sync(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(SYNC_INTERVAL).await;
+            let _ = sync(&app).await;
+        }
+    });
+}
@@ -0,0 +1,349 @@
+//! Follows a launched app through its process tree.
+//!
+//! Plenty of games start a small launcher that spawns the real game and
+//! exits. Watching only the direct child would end the session right there,
+//! so on Linux the tracker keeps every process it has seen belong to the run
+//! and treats the app as running while any of them is alive. A process
+//! belongs to the run when it:
+//!
+//! - descends from the spawned child or another tracked process,
+//! - is in the child's process group (survives the launcher exiting early),
+//! - runs an executable from the app's `content/` folder, or
+//! - matches the app's `track_process` name and runs in the run's session
+//!   (the client's own session for a run it didn't spawn).
+//!
+//! The last two also let a run started through the OS default handler be
+//! picked up after the fact. A process matched only by name, and anything
+//! it starts, keeps the session going but is never signalled by Stop, since
+//! a common name can belong to something else entirely. Other platforms
+//! only see the direct child.
+
+use std::path::{Path, PathBuf};
+#[cfg(windows)]
+use std::process::Command;
+#[cfg(target_os = "linux")]
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    fs,
+};
+
+pub struct ProcessTracker {
+    root: Option<u32>,
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    content_dir: PathBuf,
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    track_process: Option<String>,
+    /// Tracked pid -> start time, so a recycled pid isn't mistaken for ours.
+    #[cfg(target_os = "linux")]
+    known: HashMap<u32, u64>,
+    /// Tracked pids that only matched `track_process`, or descend from one.
+    #[cfg(target_os = "linux")]
+    name_only: HashSet<u32>,
+    /// Session name matches must be in; set on the first poll.
+    #[cfg(target_os = "linux")]
+    session: Option<u32>,
+}
+
+#[cfg(target_os = "linux")]
+struct ProcInfo {
+    ppid: u32,
+    pgrp: u32,
+    session: u32,
+    start_time: u64,
+    comm: String,
+}
+
+impl ProcessTracker {
+    pub fn new(root: Option<u32>, content_dir: &Path, track_process: Option<String>) -> Self {
+        Self {
+            root,
+            content_dir: content_dir.to_path_buf(),
+            track_process: track_process
+                .map(|name| name.trim().to_string())
+                .filter(|name| !name.is_empty()),
+            #[cfg(target_os = "linux")]
+            known: HashMap::new(),
+            #[cfg(target_os = "linux")]
+            name_only: HashSet::new(),
+            #[cfg(target_os = "linux")]
+            session: None,
+        }
+    }
+
+    /// Whether processes beyond the direct child can be followed here.
+    pub fn supported() -> bool {
+        cfg!(target_os = "linux")
+    }
+
+    /// Pids currently tracked, including the direct child while it runs.
+    #[cfg(target_os = "linux")]
+    pub fn pids(&self) -> Vec<u32> {
+        self.known.keys().copied().collect()
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn pids(&self) -> Vec<u32> {
+        self.root.into_iter().collect()
+    }
+
+    /// Tracked pids that Stop may signal: everything but name-only matches.
+    #[cfg(target_os = "linux")]
+    pub fn signal_pids(&self) -> Vec<u32> {
+        self.known
+            .keys()
+            .filter(|pid| !self.name_only.contains(pid))
+            .copied()
+            .collect()
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn signal_pids(&self) -> Vec<u32> {
+        self.pids()
+    }
+
+    /// Refreshes the tracked set and reports whether any tracked process is
+    /// still alive. Always `false` where trees can't be followed.
+    #[cfg(target_os = "linux")]
+    pub fn poll(&mut self) -> bool {
+        let snapshot = snapshot();
+        if self.session.is_none() {
+            let owner = self.root.unwrap_or_else(std::process::id);
+            self.session = snapshot.get(&owner).map(|info| info.session);
+        }
+
+        self.known.retain(|pid, start| {
+            snapshot
+                .get(pid)
+                .map(|info| info.start_time == *start)
+                .unwrap_or(false)
+        });
+        let known = &self.known;
+        self.name_only.retain(|pid| known.contains_key(pid));
+
+        // Name matches wait until the run's own tree is in, so a child of
+        // the run with the tracked name still counts as the run's.
+        let mut by_name = Vec::new();
+        for (pid, info) in &snapshot {
+            if self.known.contains_key(pid) {
+                continue;
+            }
+            let in_group = self.root.is_some_and(|root| info.pgrp == root);
+            let matched = if Some(*pid) == self.root || in_group {
+                Some(false)
+            } else {
+                self.matches(*pid, info)
+            };
+            match matched {
+                Some(false) => {
+                    self.known.insert(*pid, info.start_time);
+                }
+                Some(true) => by_name.push((*pid, info.start_time)),
+                None => {}
+            }
+        }
+        self.add_descendants(&snapshot);
+
+        for (pid, start_time) in by_name {
+            if let Entry::Vacant(entry) = self.known.entry(pid) {
+                entry.insert(start_time);
+                self.name_only.insert(pid);
+            }
+        }
+        self.add_descendants(&snapshot);
+
+        !self.known.is_empty()
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn poll(&mut self) -> bool {
+        false
+    }
+
+    /// Pulls in descendants of tracked processes until nothing new turns up.
+    /// Children of a name-only match count as name-only too.
+    #[cfg(target_os = "linux")]
+    fn add_descendants(&mut self, snapshot: &HashMap<u32, ProcInfo>) {
+        loop {
+            let mut added = false;
+            for (pid, info) in snapshot {
+                if !self.known.contains_key(pid) && self.known.contains_key(&info.ppid) {
+                    self.known.insert(*pid, info.start_time);
+                    if self.name_only.contains(&info.ppid) {
+                        self.name_only.insert(*pid);
+                    }
+                    added = true;
+                }
+            }
+            if !added {
+                break;
+            }
+        }
+    }
+
+    /// Whether a process outside the run's tree belongs to it: `Some(false)`
+    /// for an executable from `content/`, `Some(true)` for a `track_process`
+    /// match in the run's session.
+    #[cfg(target_os = "linux")]
+    fn matches(&self, pid: u32, info: &ProcInfo) -> Option<bool> {
+        let exe = fs::read_link(format!("/proc/{}/exe", pid)).ok();
+        if exe
+            .as_ref()
+            .is_some_and(|exe| exe.starts_with(&self.content_dir))
+        {
+            return Some(false);
+        }
+        let name = self.track_process.as_ref()?;
+        if self.session != Some(info.session) {
+            return None;
+        }
+        let exe_name = exe
+            .as_ref()
+            .and_then(|path| path.file_name())
+            .map(|name| name.to_string_lossy().to_string());
+        // The kernel cuts comm to 15 bytes.
+        let comm_matches =
+            info.comm == *name || (info.comm.len() == 15 && name.starts_with(&info.comm));
+        (comm_matches || exe_name.as_deref() == Some(name.as_str())).then_some(true)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn snapshot() -> HashMap<u32, ProcInfo> {
+    let mut processes = HashMap::new();
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return processes;
+    };
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        if let Some(info) = read_stat(pid) {
+            processes.insert(pid, info);
+        }
+    }
+    processes
+}
+
+/// Parses `/proc/<pid>/stat`. The command name is wrapped in parentheses and
+/// may itself contain spaces or parentheses, so fields are split after the
+/// last `)`. Zombies count as gone.
+#[cfg(target_os = "linux")]
+fn read_stat(pid: u32) -> Option<ProcInfo> {
+    let raw = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    let open = raw.find('(')?;
+    let close = raw.rfind(')')?;
+    let comm = raw.get(open + 1..close)?.to_string();
+    let fields: Vec<&str> = raw.get(close + 1..)?.split_whitespace().collect();
+    // fields[0] is the state (stat field 3), so stat field N is fields[N - 3].
+    if matches!(fields.first(), Some(&"Z") | Some(&"X")) {
+        return None;
+    }
+    Some(ProcInfo {
+        ppid: fields.get(1)?.parse().ok()?,
+        pgrp: fields.get(2)?.parse().ok()?,
+        session: fields.get(3)?.parse().ok()?,
+        start_time: fields.get(19)?.parse().ok()?,
+        comm,
+    })
+}
+
+/// Politely asks a run to exit: SIGTERM to the launched process group and
+/// every tracked pid on Unix, `taskkill` without `/F` on Windows. Returns
+/// whether anything received the request.
+pub fn terminate(group: Option<u32>, pids: &[u32]) -> bool {
+    signal_all(group, pids, false)
+}
+
+/// Kills the launched process group and every tracked pid.
+pub fn kill(group: Option<u32>, pids: &[u32]) -> bool {
+    signal_all(group, pids, true)
+}
+
+#[cfg(unix)]
+fn signal_all(group: Option<u32>, pids: &[u32], force: bool) -> bool {
+    let signal = if force { libc::SIGKILL } else { libc::SIGTERM };
+    let mut sent = false;
+    // SAFETY: plain kill(2) calls; a negative pid addresses a process group.
+    if let Some(group) = group {
+        sent |= unsafe { libc::kill(-(group as libc::pid_t), signal) } == 0;
+    }
+    for pid in pids {
+        sent |= unsafe { libc::kill(*pid as libc::pid_t, signal) } == 0;
+    }
+    sent
+}
+
+#[cfg(windows)]
+fn signal_all(group: Option<u32>, pids: &[u32], force: bool) -> bool {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    let mut sent = false;
+    for pid in group.into_iter().chain(pids.iter().copied()) {
+        let mut command = Command::new("taskkill");
+        if force {
+            command.arg("/F");
+        }
+        command
+            .args(["/T", "/PID", &pid.to_string()])
+            .creation_flags(CREATE_NO_WINDOW);
+        sent |= command
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false);
+    }
+    sent
+}
+
+#[cfg(not(any(unix, windows)))]
+fn signal_all(_group: Option<u32>, _pids: &[u32], _force: bool) -> bool {
+    false
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn name_matches_are_tracked_but_not_signalled() {
+        let me = std::process::id();
+        let comm = read_stat(me).unwrap().comm;
+        let mut tracker = ProcessTracker::new(None, Path::new("/nonexistent"), Some(comm));
+        assert!(tracker.poll());
+        assert!(tracker.pids().contains(&me));
+        assert!(!tracker.signal_pids().contains(&me));
+    }
+
+    #[test]
+    fn children_of_the_run_are_signalled_despite_their_name() {
+        let mut child = std::process::Command::new("sleep")
+            .arg("5")
+            .spawn()
+            .unwrap();
+        let me = std::process::id();
+        let mut tracker = ProcessTracker::new(
+            Some(me),
+            Path::new("/nonexistent"),
+            Some("sleep".to_string()),
+        );
+        tracker.poll();
+        let signalled = tracker.signal_pids().contains(&child.id());
+        child.kill().unwrap();
+        child.wait().unwrap();
+        assert!(signalled);
+    }
+
+    #[test]
+    fn name_matches_stay_in_the_session() {
+        let me = std::process::id();
+        let comm = read_stat(me).unwrap().comm;
+        let mut tracker = ProcessTracker::new(None, Path::new("/nonexistent"), Some(comm));
+        tracker.session = Some(u32::MAX);
+        tracker.poll();
+        assert!(!tracker.pids().contains(&me));
+    }
+}
@@ -0,0 +1,317 @@
+//! Per-session logs of tracked runs, kept in `<app_dir>/logs`.
+//!
+//! Every run gets its own `session-<timestamp>.log` with a header describing
+//! the command, each stdout/stderr line prefixed with a timestamp, and a
+//! footer with the exit status and peak resource usage. Only the newest
+//! `MAX_SESSIONS` files are kept.
+//! Lifecycle hooks write `hook-<timestamp>-<stage>.log` files next to them.
+
+use std::{
+    fs::{self, File},
+    io::{BufRead, BufReader, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    thread::JoinHandle,
+    time::{Duration, Instant, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+
+use crate::{launch::LaunchPlan, run_stats::PeakStats};
+
+const LOGS_DIR: &str = "logs";
+const MAX_SESSIONS: usize = 10;
+/// Output beyond this is dropped so a chatty app can't fill the disk.
+const MAX_LOG_BYTES: u64 = 8 * 1024 * 1024;
+/// How much of a log `read_log` returns, from the end.
+const MAX_READ_BYTES: u64 = 1024 * 1024;
+
+struct LogFile {
+    file: File,
+    written: u64,
+    truncated: bool,
+}
+
+#[derive(Clone)]
+pub struct SessionLog {
+    inner: Arc<Mutex<LogFile>>,
+    name: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntry {
+    pub name: String,
+    pub size: u64,
+    pub modified: u64,
+}
+
+pub fn logs_dir(app_dir: &Path) -> PathBuf {
+    app_dir.join(LOGS_DIR)
+}
+
+fn timestamp() -> String {
+    chrono::Local::now().format("%H:%M:%S%.3f").to_string()
+}
+
+impl SessionLog {
+    /// Creates the log file for a new run and prunes old sessions.
+    pub fn create(app_dir: &Path, id: &str, plan: &LaunchPlan) -> Result<Self, String> {
+        let dir = logs_dir(app_dir);
+        fs::create_dir_all(&dir).map_err(|_| "Failed to create logs folder.".to_string())?;
+        let now = chrono::Local::now();
+        let mut path = dir.join(format!("session-{}.log", now.format("%Y%m%d-%H%M%S")));
+        let mut suffix = 1;
+        while path.exists() {
+            path = dir.join(format!(
+                "session-{}-{}.log",
+                now.format("%Y%m%d-%H%M%S"),
+                suffix
+            ));
+            suffix += 1;
+        }
+        let file = File::create(&path).map_err(|_| "Failed to create log file.".to_string())?;
+        prune(&dir);
+
+        let log = Self {
+            inner: Arc::new(Mutex::new(LogFile {
+                file,
+                written: 0,
+                truncated: false,
+            })),
+            name: path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+        };
+        let mut command = vec![plan.program.to_string_lossy().to_string()];
+        command.extend(plan.args.iter().cloned());
+        log.write_raw(&format!(
+            "Gaggle session log\nApp: {}\nProfile: {}\nCommand: {}\nWorking dir: {}\nStarted: {}\n\n",
+            id,
+            plan.profile.as_deref().unwrap_or("default"),
+            command.join(" "),
+            plan.working_dir.display(),
+            now.format("%Y-%m-%d %H:%M:%S %:z"),
+        ));
+        Ok(log)
+    }
+
+    /// File name of the log, as accepted by `read_log`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Writes the header, footer and other session lines, which go in even
+    /// after the output hit the size limit.
+    fn write_raw(&self, text: &str) {
+        let _ = self.inner.lock().unwrap().file.write_all(text.as_bytes());
+    }
+
+    /// Writes the child's output, up to `MAX_LOG_BYTES` of it.
+    fn write_output(&self, text: &str) {
+        let mut log = self.inner.lock().unwrap();
+        if log.truncated {
+            return;
+        }
+        if log.written + text.len() as u64 > MAX_LOG_BYTES {
+            log.truncated = true;
+            let _ = log
+                .file
+                .write_all(b"\n[log truncated: size limit reached]\n");
+            return;
+        }
+        if log.file.write_all(text.as_bytes()).is_ok() {
+            log.written += text.len() as u64;
+        }
+    }
+
+    /// Copies `stream` into the log line by line on a background thread.
+    pub fn capture<R: Read + Send + 'static>(
+        &self,
+        stream: R,
+        label: &'static str,
+    ) -> JoinHandle<()> {
+        let log = self.clone();
+        std::thread::spawn(move || {
+            let mut reader = BufReader::new(stream);
+            let mut line = Vec::new();
+            loop {
+                line.clear();
+                match reader.read_until(b'\n', &mut line) {
+                    Ok(0) | Err(_) => break,
+                    Ok(_) => {
+                        let text = String::from_utf8_lossy(&line);
+                        log.write_output(&format!(
+                            "[{}] [{}] {}\n",
+                            timestamp(),
+                            label,
+                            text.trim_end_matches(['\r', '\n'])
+                        ));
+                    }
+                }
+            }
+        })
+    }
+
+    /// Writes the footer once the process has exited. Waits briefly for the
+    /// capture threads so trailing output lands before it; a grandchild that
+    /// keeps the pipes open doesn't hold the footer back for long.
+    pub fn finish(&self, readers: Vec<JoinHandle<()>>, exit_code: Option<i32>, duration: Duration) {
+        let deadline = Instant::now() + Duration::from_secs(2);
+        for reader in readers {
+            while !reader.is_finished() && Instant::now() < deadline {
+                std::thread::sleep(Duration::from_millis(25));
+            }
+            if reader.is_finished() {
+                let _ = reader.join();
+            }
+        }
+        let status = match exit_code {
+            Some(code) => format!("exited with code {}", code),
+            None => "terminated by a signal or stopped".to_string(),
+        };
+        self.write_raw(&format!(
+            "\n[{}] Process {} after {}s\n",
+            timestamp(),
+            status,
+            duration.as_secs()
+        ));
+        let _ = self.inner.lock().unwrap().file.flush();
+    }
+
+    /// Appends the session's peak resource usage after the footer.
+    pub fn record_peak(&self, peak: &PeakStats) {
+        self.write_raw(&format!(
+            "[{}] Peak usage: {}\n",
+            timestamp(),
+            peak.describe()
+        ));
+        let _ = self.inner.lock().unwrap().file.flush();
+    }
+
+    /// Records why the process never started.
+    pub fn finish_failed(&self, err: &std::io::Error) {
+        self.write_raw(&format!("[{}] Failed to start: {}\n", timestamp(), err));
+        let _ = self.inner.lock().unwrap().file.flush();
+    }
+}
+
+fn log_files(dir: &Path, prefix: &str) -> Vec<(PathBuf, fs::Metadata)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<(PathBuf, fs::Metadata)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let name = path.file_name()?.to_str()?;
+            if !name.starts_with(prefix) || !name.ends_with(".log") {
+                return None;
+            }
+            let meta = entry.metadata().ok()?;
+            meta.is_file().then_some((path, meta))
+        })
+        .collect();
+    // Names sort chronologically; newest first.
+    files.sort_by(|a, b| b.0.cmp(&a.0));
+    files
+}
+
+fn prune(dir: &Path) {
+    for (path, _) in log_files(dir, "session-").into_iter().skip(MAX_SESSIONS) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+pub fn prune_hook_logs(dir: &Path) {
+    for (path, _) in log_files(dir, "hook-").into_iter().skip(MAX_SESSIONS) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// Session and hook logs, each newest first, sessions before hooks.
+pub fn list_logs(app_dir: &Path) -> Vec<LogEntry> {
+    let dir = logs_dir(app_dir);
+    log_files(&dir, "session-")
+        .into_iter()
+        .chain(log_files(&dir, "hook-"))
+        .map(|(path, meta)| LogEntry {
+            name: path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            size: meta.len(),
+            modified: meta
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+        })
+        .collect()
+}
+
+/// Reads a session log by file name. Large logs are cut to their last
+/// `MAX_READ_BYTES`, which is where a crash shows up.
+pub fn read_log(app_dir: &Path, name: &str) -> Result<String, String> {
+    if name.contains(['/', '\\']) || name.contains("..") || !name.ends_with(".log") {
+        return Err("Invalid log name.".to_string());
+    }
+    let path = logs_dir(app_dir).join(name);
+    let mut file = File::open(&path).map_err(|_| "Log not found.".to_string())?;
+    let len = file
+        .metadata()
+        .map(|meta| meta.len())
+        .map_err(|_| "Failed to read log.".to_string())?;
+    let mut prefix = String::new();
+    if len > MAX_READ_BYTES {
+        file.seek(SeekFrom::Start(len - MAX_READ_BYTES))
+            .map_err(|_| "Failed to read log.".to_string())?;
+        prefix = format!("[showing the last {} KB]\n", MAX_READ_BYTES / 1024);
+    }
+    let mut raw = Vec::new();
+    file.read_to_end(&mut raw)
+        .map_err(|_| "Failed to read log.".to_string())?;
+    Ok(prefix + &String::from_utf8_lossy(&raw))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn footer_is_written_after_the_output_limit() {
+        let app_dir = std::env::temp_dir().join(format!("gaggle-run-logs-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&app_dir);
+        let plan = LaunchPlan {
+            program: PathBuf::from("game"),
+            args: Vec::new(),
+            env: Vec::new(),
+            working_dir: app_dir.clone(),
+            profile: None,
+            track_process: None,
+        };
+        let log = SessionLog::create(&app_dir, "game", &plan).unwrap();
+
+        let line = "x".repeat(1024) + "\n";
+        for _ in 0..(MAX_LOG_BYTES / 1024 + 1) {
+            log.write_output(&line);
+        }
+        log.write_output("after the limit\n");
+        log.finish(Vec::new(), Some(3), Duration::from_secs(5));
+        log.finish_failed(&std::io::Error::other("boom"));
+
+        let raw = fs::read_to_string(logs_dir(&app_dir).join(log.name())).unwrap();
+        assert!(raw.starts_with("Gaggle session log\n"));
+        assert_eq!(
+            raw.matches("[log truncated: size limit reached]").count(),
+            1
+        );
+        assert!(!raw.contains("after the limit"));
+        assert!(raw.contains("Process exited with code 3 after 5s"));
+        assert!(raw.contains("Failed to start: boom"));
+        assert!(raw.len() as u64 <= MAX_LOG_BYTES + 1024);
+        fs::remove_dir_all(&app_dir).unwrap();
+    }
+}
@@ -0,0 +1,166 @@
+//! CPU, memory and thread usage of a tracked run.
+//!
+//! The watcher samples every process the tracker follows and emits the sums
+//! as `app_run_stats` every `SAMPLE_INTERVAL`. CPU is the share of one core
+//! used since the previous sample, so a busy multi-threaded game can go past
+//! 100%. Only Linux has the numbers (from `/proc`); elsewhere nothing is
+//! sampled.
+
+use std::time::Duration;
+#[cfg(target_os = "linux")]
+use std::{collections::HashMap, fs, time::Instant};
+
+use serde::Serialize;
+
+pub const SAMPLE_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RunStats {
+    pub id: String,
+    pub cpu_percent: f64,
+    pub memory_bytes: u64,
+    pub threads: u64,
+    pub processes: usize,
+}
+
+/// Highest values seen during a session.
+#[derive(Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PeakStats {
+    pub cpu_percent: f64,
+    pub memory_bytes: u64,
+    pub threads: u64,
+}
+
+impl PeakStats {
+    pub fn describe(&self) -> String {
+        format!(
+            "CPU {:.0}%, memory {:.1} MB, {} threads",
+            self.cpu_percent,
+            self.memory_bytes as f64 / (1024.0 * 1024.0),
+            self.threads
+        )
+    }
+}
+
+#[derive(Default)]
+pub struct StatsSampler {
+    /// CPU ticks per pid at the previous sample, keyed with the start time
+    /// so a recycled pid starts over.
+    #[cfg(target_os = "linux")]
+    last: HashMap<u32, (u64, u64)>,
+    #[cfg(target_os = "linux")]
+    last_at: Option<Instant>,
+    peak: Option<PeakStats>,
+}
+
+#[cfg(target_os = "linux")]
+struct ProcUsage {
+    start_time: u64,
+    cpu_ticks: u64,
+    threads: u64,
+    rss_pages: u64,
+}
+
+impl StatsSampler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Peaks so far, or `None` if nothing was ever sampled.
+    pub fn peak(&self) -> Option<&PeakStats> {
+        self.peak.as_ref()
+    }
+
+    /// Sums usage over `pids`. The first sample has no CPU figure yet, so it
+    /// only primes the counters and returns `None`.
+    #[cfg(target_os = "linux")]
+    pub fn sample(&mut self, id: &str, pids: &[u32]) -> Option<RunStats> {
+        let now = Instant::now();
+        let mut current = HashMap::new();
+        let mut delta_ticks = 0;
+        let mut threads = 0;
+        let mut rss_pages = 0;
+        for pid in pids {
+            let Some(usage) = read_usage(*pid) else {
+                continue;
+            };
+            if let Some((start_time, ticks)) = self.last.get(pid) {
+                if *start_time == usage.start_time {
+                    delta_ticks += usage.cpu_ticks.saturating_sub(*ticks);
+                }
+            }
+            threads += usage.threads;
+            rss_pages += usage.rss_pages;
+            current.insert(*pid, (usage.start_time, usage.cpu_ticks));
+        }
+        let processes = current.len();
+        self.last = current;
+        let elapsed = now.saturating_duration_since(self.last_at.replace(now)?);
+        if processes == 0 || elapsed.is_zero() {
+            return None;
+        }
+
+        let stats = RunStats {
+            id: id.to_string(),
+            cpu_percent: delta_ticks as f64 / clock_ticks() / elapsed.as_secs_f64() * 100.0,
+            memory_bytes: rss_pages * page_size(),
+            threads,
+            processes,
+        };
+        let peak = self.peak.get_or_insert_with(PeakStats::default);
+        peak.cpu_percent = peak.cpu_percent.max(stats.cpu_percent);
+        peak.memory_bytes = peak.memory_bytes.max(stats.memory_bytes);
+        peak.threads = peak.threads.max(stats.threads);
+        Some(stats)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn sample(&mut self, _id: &str, _pids: &[u32]) -> Option<RunStats> {
+        None
+    }
+}
+
+/// Reads CPU time, thread count and resident pages from `/proc/<pid>/stat`,
+/// splitting after the last `)` like `proc_tree` does.
+#[cfg(target_os = "linux")]
+fn read_usage(pid: u32) -> Option<ProcUsage> {
+    let raw = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    let close = raw.rfind(')')?;
+    let fields: Vec<&str> = raw.get(close + 1..)?.split_whitespace().collect();
+    // Stat field N is fields[N - 3].
+    let field = |n: usize| {
+        fields
+            .get(n - 3)
+            .and_then(|value| value.parse::<u64>().ok())
+    };
+    Some(ProcUsage {
+        cpu_ticks: field(14)? + field(15)?,
+        threads: field(20)?,
+        start_time: field(22)?,
+        rss_pages: field(24)?,
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn clock_ticks() -> f64 {
+    // SAFETY: sysconf only reads a system constant.
+    let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+    if ticks > 0 {
+        ticks as f64
+    } else {
+        100.0
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn page_size() -> u64 {
+    // SAFETY: sysconf only reads a system constant.
+    let size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    if size > 0 {
+        size as u64
+    } else {
+        4096
+    }
+}
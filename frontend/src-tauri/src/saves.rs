@@ -0,0 +1,563 @@
+//! Cloud saves for the locations an app declares under `[[saves]]`.
+//!
+//! A location is a file or folder relative to the app's `content/`, the home
+//! folder, or the user data/config folder (the XDG ones on Linux). After a
+//! tracked session the client hashes every location and, if anything changed
+//! since the last sync, uploads a `.tar.gz` of them to `/apps/:id/save`.
+//! Archive entries are prefixed with the location's index in the config, so a
+//! restore puts each file back where it came from.
+//!
+//! `<app>/cloud_save.json` remembers the hash both sides last agreed on. When
+//! the local save and the cloud save have both moved away from it, neither is
+//! picked silently: the server refuses the upload and the Library asks.
+//!
+//! Saves only ever sync with the server the app was installed from (its
+//! `source.json`); while another server is connected they are left alone.
+
+use std::{
+    fs,
+    io::{Cursor, Read},
+    path::{Component, Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{launch, playtime_journal};
+
+const STATE_FILE: &str = "cloud_save.json";
+
+#[derive(Deserialize, Clone)]
+pub struct SaveLocation {
+    #[serde(default)]
+    pub root: SaveRoot,
+    pub path: String,
+}
+
+#[derive(Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SaveRoot {
+    #[default]
+    Content,
+    Home,
+    Data,
+    Config,
+}
+
+/// Metadata of the cloud save, as the server returns it.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudSave {
+    pub hash: String,
+    pub size: i64,
+    #[serde(alias = "uploaded_at")]
+    pub uploaded_at: i64,
+    #[serde(default)]
+    pub device: String,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct SyncState {
+    /// Server the hash was agreed with; a hash from another server means
+    /// nothing here.
+    #[serde(default)]
+    server: Option<String>,
+    #[serde(default)]
+    synced_hash: Option<String>,
+}
+
+/// How the local save relates to the cloud one.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudSaveStatus {
+    /// `none`, `in_sync`, `uploaded`, `cloud_newer` or `conflict`.
+    pub state: String,
+    pub cloud: Option<CloudSave>,
+}
+
+impl CloudSaveStatus {
+    fn new(state: &str, cloud: Option<CloudSave>) -> Self {
+        Self {
+            state: state.to_string(),
+            cloud,
+        }
+    }
+}
+
+enum UploadOutcome {
+    Uploaded,
+    Unchanged,
+    Conflict(CloudSave),
+}
+
+fn root_dir(app: &AppHandle, app_dir: &Path, root: SaveRoot) -> Result<PathBuf, String> {
+    let dir = match root {
+        SaveRoot::Content => return Ok(app_dir.join("content")),
+        SaveRoot::Home => app.path().home_dir(),
+        SaveRoot::Data => app.path().data_dir(),
+        SaveRoot::Config => app.path().config_dir(),
+    };
+    dir.map_err(|_| "Failed to locate the save folder.".to_string())
+}
+
+/// Every folder a save location can be relative to. None of them may ever
+/// be replaced as a whole.
+pub fn roots(app: &AppHandle, app_dir: &Path) -> Vec<PathBuf> {
+    [
+        SaveRoot::Content,
+        SaveRoot::Home,
+        SaveRoot::Data,
+        SaveRoot::Config,
+    ]
+    .into_iter()
+    .filter_map(|root| root_dir(app, app_dir, root).ok())
+    .collect()
+}
+
+/// Resolves a save path under `root`. A path that names the root itself
+/// (`.` or `./`) is refused, since a restore replaces the whole location.
+pub fn resolve_location(root: &Path, rel: &str) -> Result<PathBuf, String> {
+    let path = launch::resolve_content_path(root, rel, "save")?;
+    if path == root {
+        return Err("Save path must name a file or folder inside its root.".to_string());
+    }
+    Ok(path)
+}
+
+/// Absolute paths of the app's save locations, in config order.
+pub fn locations(app: &AppHandle, app_dir: &Path, id: &str) -> Result<Vec<PathBuf>, String> {
+    let Ok(config) = launch::load_app_config(app_dir, id) else {
+        return Ok(Vec::new());
+    };
+    config
+        .saves
+        .iter()
+        .map(|location| {
+            let root = root_dir(app, app_dir, location.root)?;
+            resolve_location(&root, &location.path)
+        })
+        .collect()
+}
+
+/// Every file in `paths` with its archive name, sorted by name.
+pub fn collect_files(paths: &[PathBuf]) -> Vec<(String, PathBuf)> {
+    let mut files = Vec::new();
+    for (index, path) in paths.iter().enumerate() {
+        if path.is_file() {
+            files.push((index.to_string(), path.clone()));
+            continue;
+        }
+        let mut pending = vec![PathBuf::new()];
+        while let Some(rel) = pending.pop() {
+            let Ok(entries) = fs::read_dir(path.join(&rel)) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let Ok(file_type) = entry.file_type() else {
+                    continue;
+                };
+                let child = rel.join(entry.file_name());
+                if file_type.is_dir() {
+                    pending.push(child);
+                } else if file_type.is_file() {
+                    let name = child
+                        .components()
+                        .map(|part| part.as_os_str().to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("/");
+                    files.push((format!("{}/{}", index, name), path.join(&child)));
+                }
+            }
+        }
+    }
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    files
+}
+
+/// Hash of the names and contents of `files`, so timestamps alone don't count
+/// as a change. `None` when there is no save at all.
+pub fn content_hash(files: &[(String, PathBuf)]) -> Result<Option<String>, String> {
+    if files.is_empty() {
+        return Ok(None);
+    }
+    let mut hasher = Sha256::new();
+    for (name, path) in files {
+        let data = fs::read(path).map_err(|_| "Failed to read save files.".to_string())?;
+        hasher.update(name.as_bytes());
+        hasher.update([0]);
+        hasher.update((data.len() as u64).to_le_bytes());
+        hasher.update(&data);
+    }
+    Ok(Some(format!("{:x}", hasher.finalize())))
+}
+
+pub fn build_archive(files: &[(String, PathBuf)]) -> Result<Vec<u8>, String> {
+    let encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+    for (name, path) in files {
+        builder
+            .append_path_with_name(path, name)
+            .map_err(|_| "Failed to pack save files.".to_string())?;
+    }
+    builder
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .map_err(|_| "Failed to pack save files.".to_string())
+}
+
+/// Replaces the save locations with the contents of `archive`. The archive
+/// is read in full first, so a broken download leaves the local save alone.
+/// A location that is one of `roots`, or contains one, is never replaced.
+pub fn unpack_archive(archive: &[u8], paths: &[PathBuf], roots: &[PathBuf]) -> Result<(), String> {
+    let failed = || "Failed to restore save files.".to_string();
+    if paths
+        .iter()
+        .any(|path| roots.iter().any(|root| root.starts_with(path)))
+    {
+        return Err("Save location is not safe to restore.".to_string());
+    }
+    let mut files = Vec::new();
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(Cursor::new(archive)));
+    for entry in archive.entries().map_err(|_| failed())? {
+        let mut entry = entry.map_err(|_| failed())?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let name = entry.path().map_err(|_| failed())?.into_owned();
+        let mut parts = name.components();
+        let index = match parts.next() {
+            Some(Component::Normal(part)) => part.to_string_lossy().parse::<usize>().ok(),
+            _ => None,
+        };
+        // Entries for locations that are gone from the config are skipped.
+        let Some(base) = index.and_then(|index| paths.get(index)) else {
+            continue;
+        };
+        let rest = parts.as_path();
+        let target = if rest.as_os_str().is_empty() {
+            base.clone()
+        } else {
+            launch::resolve_content_path(base, &rest.to_string_lossy(), "save")?
+        };
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data).map_err(|_| failed())?;
+        files.push((target, data));
+    }
+
+    for path in paths {
+        let result = if path.is_dir() {
+            fs::remove_dir_all(path)
+        } else if path.exists() {
+            fs::remove_file(path)
+        } else {
+            Ok(())
+        };
+        result.map_err(|_| failed())?;
+    }
+    for (target, data) in files {
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|_| failed())?;
+        }
+        fs::write(&target, data).map_err(|_| failed())?;
+    }
+    Ok(())
+}
+
+fn read_state(app_dir: &Path, server: &str) -> SyncState {
+    fs::read(app_dir.join(STATE_FILE))
+        .ok()
+        .and_then(|raw| serde_json::from_slice::<SyncState>(&raw).ok())
+        .filter(|state| state.server.as_deref().is_none_or(|known| known == server))
+        .unwrap_or_default()
+}
+
+fn write_state(app_dir: &Path, server: &str, synced_hash: Option<String>) -> Result<(), String> {
+    let path = app_dir.join(STATE_FILE);
+    let state = SyncState {
+        server: Some(server.to_string()),
+        synced_hash,
+    };
+    let raw = serde_json::to_vec_pretty(&state)
+        .map_err(|_| "Failed to encode save sync state.".to_string())?;
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, raw).map_err(|_| "Failed to save save sync state.".to_string())?;
+    fs::rename(&tmp, path).map_err(|_| "Failed to save save sync state.".to_string())
+}
+
+/// The connected server, as long as the app came from it. Apps installed
+/// before their source was recorded sync with whichever server is connected.
+fn target(app: &AppHandle, app_dir: &Path) -> Result<playtime_journal::SyncTarget, String> {
+    let target =
+        playtime_journal::target(app).ok_or_else(|| "Not connected to a server.".to_string())?;
+    match crate::app_source(app_dir) {
+        Some(server) if server != target.server_key() => {
+            Err("This app's cloud save is on the server it was installed from.".to_string())
+        }
+        _ => Ok(target),
+    }
+}
+
+/// Shown next to the cloud save so players can tell their PCs apart.
+fn device_name() -> String {
+    std::env::var("COMPUTERNAME")
+        .ok()
+        .or_else(|| fs::read_to_string("/etc/hostname").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| std::env::consts::OS.to_string())
+}
+
+async fn fetch_cloud(
+    client: &reqwest::Client,
+    target: &playtime_journal::SyncTarget,
+    id: &str,
+) -> Result<Option<CloudSave>, String> {
+    let url = crate::net::build_http_url(
+        &target.server_ip,
+        &target.server_port,
+        &format!("/apps/{}/save", id),
+    );
+    let resp = client
+        .get(url)
+        .bearer_auth(&target.token)
+        .send()
+        .await
+        .map_err(|_| "Server not reachable.".to_string())?;
+    if resp.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !resp.status().is_success() {
+        return Err(format!(
+            "Failed to check the cloud save (HTTP {}).",
+            resp.status()
+        ));
+    }
+    let body = resp
+        .bytes()
+        .await
+        .map_err(|_| "Failed to read the cloud save.".to_string())?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|_| "Cloud save info is invalid.".to_string())
+}
+
+async fn upload(
+    app: &AppHandle,
+    app_dir: &Path,
+    id: &str,
+    force: bool,
+) -> Result<UploadOutcome, String> {
+    let target = target(app, app_dir)?;
+    let files = collect_files(&locations(app, app_dir, id)?);
+    let Some(hash) = content_hash(&files)? else {
+        return Ok(UploadOutcome::Unchanged);
+    };
+    let state = read_state(app_dir, &target.server_key());
+    if state.synced_hash.as_deref() == Some(hash.as_str()) {
+        return Ok(UploadOutcome::Unchanged);
+    }
+    let archive = build_archive(&files)?;
+
+    let mut url = crate::net::build_http_url(
+        &target.server_ip,
+        &target.server_port,
+        &format!("/apps/{}/save", id),
+    );
+    if force {
+        url.push_str("?force=1");
+    }
+    let resp = crate::http_client::client(app, &target.server_ip, &target.server_port)?
+        .put(url)
+        .bearer_auth(&target.token)
+        .header("Content-Type", "application/gzip")
+        .header("X-Save-Hash", &hash)
+        .header("X-Save-Base", state.synced_hash.unwrap_or_default())
+        .header("X-Save-Device", device_name())
+        .body(archive)
+        .send()
+        .await
+        .map_err(|_| "Server not reachable.".to_string())?;
+    let status = resp.status();
+    if status == reqwest::StatusCode::CONFLICT {
+        #[derive(Deserialize)]
+        struct Conflict {
+            save: CloudSave,
+        }
+        let body = resp.bytes().await.unwrap_or_default();
+        return serde_json::from_slice::<Conflict>(&body)
+            .map(|conflict| UploadOutcome::Conflict(conflict.save))
+            .map_err(|_| "Cloud save changed on another PC.".to_string());
+    }
+    if !status.is_success() {
+        return Err(format!("Cloud save upload failed (HTTP {}).", status));
+    }
+    write_state(app_dir, &target.server_key(), Some(hash))?;
+    Ok(UploadOutcome::Uploaded)
+}
+
+/// Compares the local save with the cloud one before a launch. A local save
+/// that changed on its own is uploaded on the way, so only a newer cloud save
+/// or a conflict is left for the player to decide.
+pub async fn check(app: &AppHandle, app_dir: &Path, id: &str) -> Result<CloudSaveStatus, String> {
+    let paths = locations(app, app_dir, id)?;
+    if paths.is_empty() {
+        return Ok(CloudSaveStatus::new("none", None));
+    }
+    let target = target(app, app_dir)?;
+    let client = crate::http_client::client(app, &target.server_ip, &target.server_port)?;
+    let Some(cloud) = fetch_cloud(&client, &target, id).await? else {
+        // Nothing to compare against, so whatever is here becomes the save.
+        write_state(app_dir, &target.server_key(), None)?;
+        upload(app, app_dir, id, false).await?;
+        return Ok(CloudSaveStatus::new("none", None));
+    };
+    let local = content_hash(&collect_files(&paths))?;
+    let state = read_state(app_dir, &target.server_key());
+    if local.as_deref() == Some(cloud.hash.as_str()) {
+        if state.synced_hash.as_deref() != Some(cloud.hash.as_str()) {
+            write_state(app_dir, &target.server_key(), Some(cloud.hash.clone()))?;
+        }
+        return Ok(CloudSaveStatus::new("in_sync", Some(cloud)));
+    }
+    let cloud_changed = state.synced_hash.as_deref() != Some(cloud.hash.as_str());
+    let local_changed = local.is_some() && local != state.synced_hash;
+    let status = match (local_changed, cloud_changed) {
+        (true, false) => {
+            upload(app, app_dir, id, false).await?;
+            "uploaded"
+        }
+        (false, _) => "cloud_newer",
+        (true, true) => "conflict",
+    };
+    Ok(CloudSaveStatus::new(status, Some(cloud)))
+}
+
+/// Replaces the local save with the cloud one.
+pub async fn restore(app: &AppHandle, app_dir: &Path, id: &str) -> Result<(), String> {
+    let paths = locations(app, app_dir, id)?;
+    if paths.is_empty() {
+        return Err("This app has no save locations.".to_string());
+    }
+    let target = target(app, app_dir)?;
+    let client = crate::http_client::client(app, &target.server_ip, &target.server_port)?;
+    let cloud = fetch_cloud(&client, &target, id)
+        .await?
+        .ok_or_else(|| "There is no cloud save yet.".to_string())?;
+    let url = crate::net::build_http_url(
+        &target.server_ip,
+        &target.server_port,
+        &format!("/apps/{}/save/archive", id),
+    );
+    let resp = client
+        .get(url)
+        .bearer_auth(&target.token)
+        .send()
+        .await
+        .map_err(|_| "Server not reachable.".to_string())?;
+    if !resp.status().is_success() {
+        return Err(format!(
+            "Failed to download the cloud save (HTTP {}).",
+            resp.status()
+        ));
+    }
+    let archive = resp
+        .bytes()
+        .await
+        .map_err(|_| "Failed to download the cloud save.".to_string())?;
+    // Keeps what was here, in case the cloud save turns out to be the wrong one.
+    crate::snapshots::take(app, app_dir, id, "restore")?;
+    unpack_archive(&archive, &paths, &roots(app, app_dir))?;
+    write_state(app_dir, &target.server_key(), Some(cloud.hash))
+}
+
+/// Uploads the local save, replacing the cloud one even if it changed.
+pub async fn upload_forced(app: &AppHandle, app_dir: &Path, id: &str) -> Result<(), String> {
+    upload(app, app_dir, id, true).await.map(|_| ())
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct SaveConflictEvent {
+    id: String,
+    cloud: CloudSave,
+}
+
+/// Uploads the save once a session is over. A conflict is handed to the
+/// Library as `app_save_conflict`; other failures wait for the next check.
+pub fn sync_after_session(app: &AppHandle, id: &str, app_dir: &Path) {
+    let app = app.clone();
+    let id = id.to_string();
+    let app_dir = app_dir.to_path_buf();
+    tauri::async_runtime::spawn(async move {
+        if let Ok(UploadOutcome::Conflict(cloud)) = upload(&app, &app_dir, &id, false).await {
+            let _ = app.emit("app_save_conflict", SaveConflictEvent { id, cloud });
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn temp_dir() -> PathBuf {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "gaggle-saves-{}-{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn save_paths_must_not_name_the_root() {
+        let root = Path::new("home");
+        for rel in [".", "./", " ./ ", "./.", ""] {
+            assert!(resolve_location(root, rel).is_err(), "{rel:?} was accepted");
+        }
+        assert_eq!(
+            resolve_location(root, "./saves").unwrap(),
+            root.join("saves")
+        );
+    }
+
+    #[test]
+    fn restore_round_trips_a_location() {
+        let dir = temp_dir();
+        let root = dir.join("home");
+        let save = root.join("saves");
+        fs::create_dir_all(save.join("slot")).unwrap();
+        fs::write(save.join("slot").join("1.sav"), "first").unwrap();
+        let archive = build_archive(&collect_files(std::slice::from_ref(&save))).unwrap();
+
+        fs::write(save.join("slot").join("1.sav"), "changed").unwrap();
+        fs::write(save.join("extra.sav"), "extra").unwrap();
+        unpack_archive(&archive, std::slice::from_ref(&save), &[root]).unwrap();
+
+        assert_eq!(fs::read(save.join("slot").join("1.sav")).unwrap(), b"first");
+        assert!(!save.join("extra.sav").exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn restore_never_replaces_a_root() {
+        let dir = temp_dir();
+        let root = dir.join("home");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("keep.txt"), "keep").unwrap();
+        let archive = build_archive(&[]).unwrap();
+
+        let roots = [root.clone()];
+        for path in [root.clone(), dir.clone()] {
+            assert!(unpack_archive(&archive, &[path], &roots).is_err());
+        }
+        assert_eq!(fs::read(root.join("keep.txt")).unwrap(), b"keep");
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
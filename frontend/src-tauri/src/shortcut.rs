@@ -0,0 +1,222 @@
+//! Desktop shortcuts that start an installed app with `--run`.
+//!
+//! - Linux: a `.desktop` entry in the applications menu and on the desktop.
+//! - Windows: a `.lnk` on the desktop, written through PowerShell.
+//! - macOS: an executable `.command` script on the desktop.
+
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Manager};
+
+/// The command a shortcut runs, without the program itself.
+fn launch_args(id: &str, profile: Option<&str>) -> Vec<String> {
+    let mut args = vec!["--run".to_string(), id.to_string()];
+    if let Some(profile) = profile {
+        args.push("--profile".to_string());
+        args.push(profile.to_string());
+    }
+    args
+}
+
+fn shortcut_title(name: &str, profile: Option<&str>) -> String {
+    match profile {
+        Some(profile) => format!("{} ({})", name, profile),
+        None => name.to_string(),
+    }
+}
+
+/// Keeps a file name portable; the title itself stays as is inside the file.
+fn file_stem(text: &str) -> String {
+    let stem: String = text
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, ' ' | '-' | '_' | '(' | ')') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let stem = stem.trim().to_string();
+    if stem.is_empty() {
+        "Gaggle app".to_string()
+    } else {
+        stem
+    }
+}
+
+/// The launcher binary. Inside an AppImage the running executable lives in a
+/// temporary mount, so the image itself is used instead.
+fn launcher_path() -> Result<PathBuf, String> {
+    if let Some(image) = std::env::var_os("APPIMAGE").filter(|path| !path.is_empty()) {
+        return Ok(PathBuf::from(image));
+    }
+    std::env::current_exe().map_err(|_| "Failed to locate the Gaggle executable.".to_string())
+}
+
+fn desktop_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .desktop_dir()
+        .map_err(|_| "Failed to locate the desktop folder.".to_string())
+}
+
+/// Creates a shortcut for app `id` and returns where it was written.
+pub fn create(
+    app: &AppHandle,
+    id: &str,
+    name: &str,
+    profile: Option<&str>,
+) -> Result<PathBuf, String> {
+    let launcher = launcher_path()?;
+    let title = shortcut_title(
+        if name.trim().is_empty() {
+            id
+        } else {
+            name.trim()
+        },
+        profile,
+    );
+    create_for_platform(app, &launcher, id, &title, profile)
+}
+
+#[cfg(target_os = "linux")]
+fn create_for_platform(
+    app: &AppHandle,
+    launcher: &Path,
+    id: &str,
+    title: &str,
+    profile: Option<&str>,
+) -> Result<PathBuf, String> {
+    let mut exec = vec![desktop_quote(&launcher.to_string_lossy())];
+    exec.extend(
+        launch_args(id, profile)
+            .iter()
+            .map(|arg| desktop_quote(arg)),
+    );
+    let entry = format!(
+        "[Desktop Entry]\nType=Application\nName={}\nComment=Start {} with Gaggle\nExec={}\nTerminal=false\nCategories=Game;\n",
+        title.replace('\n', " "),
+        title.replace('\n', " "),
+        exec.join(" ")
+    );
+    let stem = match profile {
+        Some(profile) => format!("{}-{}", id, profile),
+        None => id.to_string(),
+    };
+    let file_name = format!("gaggle-{}.desktop", file_stem(&stem).replace(' ', "_"));
+
+    let menu_dir = app
+        .path()
+        .data_dir()
+        .map_err(|_| "Failed to locate the applications folder.".to_string())?
+        .join("applications");
+    let mut written = None;
+    for dir in [
+        Some(menu_dir),
+        desktop_dir(app).ok().filter(|dir| dir.is_dir()),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        let path = dir.join(&file_name);
+        if write_executable(&path, &entry).is_ok() {
+            written = Some(path);
+        }
+    }
+    written.ok_or_else(|| "Failed to create shortcut.".to_string())
+}
+
+/// Quotes one `Exec=` argument as the desktop entry spec asks for.
+#[cfg(target_os = "linux")]
+fn desktop_quote(arg: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in arg.chars() {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    // `%` starts a field code, even inside quotes.
+    quoted.replace('%', "%%")
+}
+
+#[cfg(target_os = "macos")]
+fn create_for_platform(
+    app: &AppHandle,
+    launcher: &Path,
+    id: &str,
+    title: &str,
+    profile: Option<&str>,
+) -> Result<PathBuf, String> {
+    let mut command = vec![shell_quote(&launcher.to_string_lossy())];
+    command.extend(launch_args(id, profile).iter().map(|arg| shell_quote(arg)));
+    let script = format!("#!/bin/sh\nexec {} >/dev/null 2>&1\n", command.join(" "));
+    let path = desktop_dir(app)?.join(format!("{}.command", file_stem(title)));
+    write_executable(&path, &script).map_err(|_| "Failed to create shortcut.".to_string())?;
+    Ok(path)
+}
+
+#[cfg(target_os = "macos")]
+fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+#[cfg(windows)]
+fn create_for_platform(
+    app: &AppHandle,
+    launcher: &Path,
+    id: &str,
+    title: &str,
+    profile: Option<&str>,
+) -> Result<PathBuf, String> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    let path = desktop_dir(app)?.join(format!("{}.lnk", file_stem(title)));
+    let arguments = launch_args(id, profile)
+        .iter()
+        .map(|arg| format!("\"{}\"", arg.replace('"', "\\\"")))
+        .collect::<Vec<_>>()
+        .join(" ");
+    // PowerShell single-quoted strings only need `'` doubled.
+    let ps = |text: &str| format!("'{}'", text.replace('\'', "''"));
+    let script = format!(
+        "$s = (New-Object -ComObject WScript.Shell).CreateShortcut({}); $s.TargetPath = {}; $s.Arguments = {}; $s.IconLocation = {}; $s.Save()",
+        ps(&path.to_string_lossy()),
+        ps(&launcher.to_string_lossy()),
+        ps(&arguments),
+        ps(&launcher.to_string_lossy()),
+    );
+    let status = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .creation_flags(CREATE_NO_WINDOW)
+        .status()
+        .map_err(|_| "Failed to create shortcut.".to_string())?;
+    if !status.success() {
+        return Err("Failed to create shortcut.".to_string());
+    }
+    Ok(path)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn create_for_platform(
+    _app: &AppHandle,
+    _launcher: &Path,
+    _id: &str,
+    _title: &str,
+    _profile: Option<&str>,
+) -> Result<PathBuf, String> {
+    Err("Shortcuts are not supported on this platform.".to_string())
+}
+
+#[cfg(unix)]
+fn write_executable(path: &Path, contents: &str) -> std::io::Result<()> {
+    use std::{fs, os::unix::fs::PermissionsExt};
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)?;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+}
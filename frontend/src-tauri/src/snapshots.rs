@@ -0,0 +1,153 @@
+//! Local snapshots of an app's saves, taken before anything that could wipe
+//! them: an install that unpacks over an existing `content/` (update, other
+//! version, repair) and an uninstall.
+//!
+//! A snapshot covers the app's `[[saves]]` locations plus the extra paths
+//! under `content/` the player listed in the app's settings. It is a save
+//! archive from `saves` with a `.json` next to it, kept in
+//! `save_snapshots/<id>/` in the app data folder so it outlives the app
+//! folder. The newest `MAX_SNAPSHOTS` per app are kept.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::{launch_settings, saves};
+
+const SNAPSHOT_DIR: &str = "save_snapshots";
+const MAX_SNAPSHOTS: usize = 10;
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotInfo {
+    pub name: String,
+    pub created_at: u64,
+    /// What the snapshot was taken before: `update`, `uninstall` or `restore`.
+    pub reason: String,
+    pub size: u64,
+    pub files: usize,
+    #[serde(default)]
+    pub hash: String,
+}
+
+fn snapshot_dir(app: &AppHandle, id: &str) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|_| "Failed to locate app data folder.".to_string())?;
+    Ok(dir.join(SNAPSHOT_DIR).join(id))
+}
+
+/// Declared save locations first, then the player's extra paths, so archive
+/// indexes stay put as long as neither list changes.
+fn locations(app: &AppHandle, app_dir: &Path, id: &str) -> Result<Vec<PathBuf>, String> {
+    let mut paths = saves::locations(app, app_dir, id)?;
+    let settings = launch_settings::load(app);
+    if let Some(app_settings) = settings.apps.get(id) {
+        for rel in &app_settings.save_paths {
+            let path = saves::resolve_location(&app_dir.join("content"), rel)?;
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+    }
+    Ok(paths)
+}
+
+/// Snapshots, newest first.
+pub fn list(app: &AppHandle, id: &str) -> Vec<SnapshotInfo> {
+    let Ok(dir) = snapshot_dir(app, id) else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+    let mut snapshots: Vec<SnapshotInfo> = entries
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|entry| fs::read(entry.path()).ok())
+        .filter_map(|raw| serde_json::from_slice(&raw).ok())
+        .filter(|info: &SnapshotInfo| dir.join(format!("{}.tar.gz", info.name)).is_file())
+        .collect();
+    snapshots.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(b.name.cmp(&a.name)));
+    snapshots
+}
+
+fn remove_snapshot(dir: &Path, name: &str) {
+    let _ = fs::remove_file(dir.join(format!("{}.tar.gz", name)));
+    let _ = fs::remove_file(dir.join(format!("{}.json", name)));
+}
+
+/// Snapshots the app's saves. Returns `None` when there is nothing to save
+/// or the newest snapshot already holds exactly these files.
+pub fn take(
+    app: &AppHandle,
+    app_dir: &Path,
+    id: &str,
+    reason: &str,
+) -> Result<Option<SnapshotInfo>, String> {
+    let files = saves::collect_files(&locations(app, app_dir, id)?);
+    let Some(hash) = saves::content_hash(&files)? else {
+        return Ok(None);
+    };
+    let existing = list(app, id);
+    if existing.first().is_some_and(|newest| newest.hash == hash) {
+        return Ok(None);
+    }
+
+    let dir = snapshot_dir(app, id)?;
+    fs::create_dir_all(&dir).map_err(|_| "Failed to create snapshot folder.".to_string())?;
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let mut name = format!("{}-{}", stamp, reason);
+    let mut suffix = 1;
+    while dir.join(format!("{}.json", name)).exists() {
+        suffix += 1;
+        name = format!("{}-{}-{}", stamp, reason, suffix);
+    }
+
+    let archive = saves::build_archive(&files)?;
+    let info = SnapshotInfo {
+        name: name.clone(),
+        created_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|now| now.as_secs())
+            .unwrap_or_default(),
+        reason: reason.to_string(),
+        size: archive.len() as u64,
+        files: files.len(),
+        hash,
+    };
+    let failed = || "Failed to save snapshot.".to_string();
+    let tmp = dir.join(format!("{}.tar.gz.tmp", name));
+    fs::write(&tmp, archive).map_err(|_| failed())?;
+    fs::rename(&tmp, dir.join(format!("{}.tar.gz", name))).map_err(|_| failed())?;
+    let raw = serde_json::to_vec_pretty(&info).map_err(|_| failed())?;
+    fs::write(dir.join(format!("{}.json", name)), raw).map_err(|_| failed())?;
+
+    for old in existing.iter().skip(MAX_SNAPSHOTS - 1) {
+        remove_snapshot(&dir, &old.name);
+    }
+    Ok(Some(info))
+}
+
+/// Puts a snapshot back. What is there now is snapshotted first, so a
+/// restore can be undone.
+pub fn restore(app: &AppHandle, app_dir: &Path, id: &str, name: &str) -> Result<(), String> {
+    let info = list(app, id)
+        .into_iter()
+        .find(|info| info.name == name)
+        .ok_or_else(|| "Snapshot not found.".to_string())?;
+    let archive = fs::read(snapshot_dir(app, id)?.join(format!("{}.tar.gz", info.name)))
+        .map_err(|_| "Failed to read snapshot.".to_string())?;
+    take(app, app_dir, id, "restore")?;
+    saves::unpack_archive(
+        &archive,
+        &locations(app, app_dir, id)?,
+        &saves::roots(app, app_dir),
+    )
+}
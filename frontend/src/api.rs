@@ -16,6 +16,10 @@ pub async fn get_json<T: serde::de::DeserializeOwned>(
     if !response.ok() {
         return Err(format!("Request failed (HTTP {}).", response.status()));
     }
+    read_json(response).await
+}
+
+pub async fn read_json<T: serde::de::DeserializeOwned>(response: Response) -> Result<T, String> {
     let json = JsFuture::from(
         response
             .json()
@@ -69,9 +73,26 @@ pub async fn send_request(
     let win = web_sys::window().ok_or_else(|| "No window available.".to_string())?;
     let resp_value = JsFuture::from(win.fetch_with_request(&request))
         .await
-        .map_err(|_| format!("Cannot reach backend at {url}."))?;
+        .map_err(|_| unreachable_error(url))?;
     let resp: Response = resp_value
         .dyn_into()
         .map_err(|_| "Invalid response type.".to_string())?;
+    // The backend never answers with these itself; they come from a proxy
+    // in front of it that can't reach it.
+    if matches!(resp.status(), 502..=504) {
+        return Err(unreachable_error(url));
+    }
     Ok(resp)
 }
+
+const UNREACHABLE_PREFIX: &str = "Cannot reach backend";
+
+fn unreachable_error(url: &str) -> String {
+    format!("{UNREACHABLE_PREFIX} at {url}.")
+}
+
+/// Whether a request failed because the backend couldn't be reached at all,
+/// which the client treats as being offline rather than as an error.
+pub fn is_unreachable(message: &str) -> bool {
+    message.starts_with(UNREACHABLE_PREFIX)
+}
@@ -6,18 +6,22 @@ use wasm_bindgen_futures::spawn_local;
 use web_sys::{console, HtmlInputElement};
 use yew::prelude::*;
 
-use crate::api::send_request;
+use crate::api::{is_unreachable, send_request};
 use crate::auth::{
-    check_session, clear_query_param, fetch_me, get_local_storage_item, get_query_param,
-    handle_login, handle_logout, remove_local_storage_item, set_local_storage_item,
-    LOGIN_SUCCESS_KEY, SERVER_IP_KEY, SERVER_PORT_KEY, SESSION_TOKEN_KEY,
+    check_session, clear_query_param, complete_login, expire_session, fetch_login_options,
+    fetch_me, get_local_storage_item, get_query_param, handle_logout, login, start_session,
+    stored_server, switch_server, LoginForm, LoginKind, LoginOptions, LoginOutcome, LoginProvider,
+    SERVER_IP_KEY, SESSION_TOKEN_KEY,
 };
 use crate::components::{Button, Card};
 use crate::confirm::ConfirmProvider;
-use crate::net::build_http_url;
+use crate::net::{build_http_url, ServerAddress, DEFAULT_PORT};
+use crate::profiles::{
+    cache_theme, cached_theme, forget_profile, load_profiles, save_active_profile, ServerProfile,
+};
 use crate::screens::dashboard::Dashboard;
 use crate::screens::error::ErrorScreen;
-use crate::toast::{use_toast, ToastProvider, ToastVariant};
+use crate::toast::{use_toast, ToastContext, ToastProvider, ToastVariant};
 
 #[wasm_bindgen]
 extern "C" {
@@ -32,6 +36,9 @@ pub struct AppState {
     pub server_port: Option<String>,
     pub session_token: Option<String>,
     pub auth_error: Option<String>,
+    /// The server couldn't be reached; installed apps still work from the
+    /// cached library.
+    pub offline: bool,
     pub user: Option<User>,
 }
 
@@ -64,23 +71,16 @@ pub fn app() -> Html {
         server_port: None,
         session_token: None,
         auth_error: None,
+        offline: false,
         user: None,
     });
 
     {
         let app_state = app_state.clone();
         use_effect_with((), move |_| {
-            let server_ip = get_local_storage_item(SERVER_IP_KEY);
-            let server_port =
-                get_local_storage_item(SERVER_PORT_KEY).or_else(|| Some("2121".to_string()));
-            let token_from_query = get_query_param("token");
-            if let Some(token) = token_from_query.as_deref() {
-                set_local_storage_item(SESSION_TOKEN_KEY, token);
-                set_local_storage_item(LOGIN_SUCCESS_KEY, "1");
-            }
-            let token = token_from_query
-                .clone()
-                .or_else(|| get_local_storage_item(SESSION_TOKEN_KEY));
+            let (server_ip, server_port) = stored_server();
+            let token = get_local_storage_item(SESSION_TOKEN_KEY);
+            save_active_profile();
 
             if token.is_some() || server_ip.is_some() {
                 app_state.set(AppState {
@@ -89,11 +89,9 @@ pub fn app() -> Html {
                     server_port,
                     session_token: token,
                     auth_error: None,
+                    offline: false,
                     user: None,
                 });
-                if token_from_query.is_some() {
-                    clear_query_param("token");
-                }
             }
             || ()
         });
@@ -103,10 +101,17 @@ pub fn app() -> Html {
         let server_ip = app_state.server_ip.clone();
         let server_port = app_state.server_port.clone();
         use_effect_with((server_ip, server_port), move |(server_ip, server_port)| {
+            // Each server has its own theme; show the cached one right away
+            // so switching servers doesn't flash the previous server's colors.
+            match server_ip.as_deref().and_then(cached_theme) {
+                Some(theme) => apply_theme(&theme),
+                None => reset_theme(),
+            }
             if let (Some(server_ip), Some(server_port)) = (server_ip.clone(), server_port.clone()) {
                 spawn_local(async move {
                     if let Ok(theme) = fetch_theme(&server_ip, &server_port).await {
                         apply_theme(&theme);
+                        cache_theme(&server_ip, &theme);
                     }
                 });
             }
@@ -175,6 +180,18 @@ pub fn apply_theme(theme: &Theme) {
     }
 }
 
+/// Drops a server theme, going back to the stylesheet's colors.
+pub fn reset_theme() {
+    if let Some(doc) = web_sys::window().and_then(|win| win.document()) {
+        if let Some(root) = doc.document_element() {
+            let _ = root.remove_attribute("style");
+        }
+        if let Some(body) = doc.body() {
+            let _ = body.remove_attribute("style");
+        }
+    }
+}
+
 #[function_component(AppRouter)]
 fn app_router() -> Html {
     let app_state = use_context::<AppStateHandle>()
@@ -183,10 +200,24 @@ fn app_router() -> Html {
 
     {
         let toast = toast.clone();
+        let app_state = app_state.clone();
         use_effect_with((), move |_| {
-            if get_local_storage_item(LOGIN_SUCCESS_KEY).is_some() {
-                remove_local_storage_item(LOGIN_SUCCESS_KEY);
-                toast.toast("Logged in successfully.", ToastVariant::Success, Some(2500));
+            // Back from the login page: trade the one-time code for a session.
+            if let Some(code) = get_query_param("code") {
+                clear_query_param("code");
+                spawn_local(async move {
+                    match complete_login(&code).await {
+                        Ok(token) => {
+                            start_session(app_state, token);
+                            toast.toast(
+                                "Logged in successfully.",
+                                ToastVariant::Success,
+                                Some(2500),
+                            );
+                        }
+                        Err(message) => toast.toast(&message, ToastVariant::Error, Some(4000)),
+                    }
+                });
             }
             || ()
         });
@@ -194,6 +225,7 @@ fn app_router() -> Html {
 
     {
         let app_state = app_state.clone();
+        let toast = toast.clone();
         let cancelled = use_mut_ref(|| false);
         use_effect_with(
             (
@@ -202,6 +234,7 @@ fn app_router() -> Html {
                 app_state.server_port.clone(),
                 app_state.session_token.clone(),
                 app_state.auth_error.clone(),
+                app_state.offline,
             ),
             move |_| {
                 *cancelled.borrow_mut() = false;
@@ -221,6 +254,7 @@ fn app_router() -> Html {
                 let server_port = app_state.server_port.clone().unwrap();
                 let token = app_state.session_token.clone().unwrap();
                 let app_state_interval = app_state.clone();
+                let toast = toast.clone();
                 let cancelled_task = cancelled.clone();
                 let cancelled_cleanup = cancelled.clone();
 
@@ -228,7 +262,12 @@ fn app_router() -> Html {
                     if *cancelled_task.borrow() {
                         return;
                     }
-                    if app_state_interval.user.is_none() {
+                    let result = check_session(&server_ip, &server_port, &token).await;
+                    if !apply_session_check(&app_state_interval, &toast, &token, result) {
+                        return;
+                    }
+
+                    if app_state_interval.user.is_none() && !app_state_interval.offline {
                         match fetch_me(&server_ip, &server_port, &token).await {
                             Ok(user) => {
                                 let mut next = (*app_state_interval).clone();
@@ -237,30 +276,24 @@ fn app_router() -> Html {
                             }
                             Err(message) => {
                                 let mut next = (*app_state_interval).clone();
-                                next.auth_error = Some(message);
+                                if is_unreachable(&message) {
+                                    next.offline = true;
+                                } else {
+                                    next.auth_error = Some(message);
+                                }
                                 app_state_interval.set(next);
                                 return;
                             }
                         }
                     }
 
-                    if let Err(message) = check_session(&server_ip, &server_port, &token).await {
-                        let mut next = (*app_state_interval).clone();
-                        next.auth_error = Some(message);
-                        app_state_interval.set(next);
-                        return;
-                    }
-
                     let mut interval = IntervalStream::new(30_000);
                     while interval.next().await.is_some() {
                         if *cancelled_task.borrow() {
                             break;
                         }
-                        if let Err(message) = check_session(&server_ip, &server_port, &token).await
-                        {
-                            let mut next = (*app_state_interval).clone();
-                            next.auth_error = Some(message);
-                            app_state_interval.set(next);
+                        let result = check_session(&server_ip, &server_port, &token).await;
+                        if !apply_session_check(&app_state_interval, &toast, &token, result) {
                             break;
                         }
                     }
@@ -280,6 +313,7 @@ fn app_router() -> Html {
         };
         let on_retry = {
             let app_state = app_state.clone();
+            let toast = toast.clone();
             Callback::from(move |_| {
                 let app_state = app_state.clone();
                 let server_ip = app_state.server_ip.clone();
@@ -288,18 +322,31 @@ fn app_router() -> Html {
                 if let (Some(server_ip), Some(server_port), Some(token)) =
                     (server_ip, server_port, token)
                 {
+                    let toast = toast.clone();
                     spawn_local(async move {
                         if let Ok(user) = fetch_me(&server_ip, &server_port, &token).await {
                             let mut next = (*app_state).clone();
                             next.user = Some(user);
                             next.auth_error = None;
                             app_state.set(next);
-                        } else if let Err(message) =
-                            check_session(&server_ip, &server_port, &token).await
-                        {
-                            let mut next = (*app_state).clone();
-                            next.auth_error = Some(message);
-                            app_state.set(next);
+                            return;
+                        }
+                        match check_session(&server_ip, &server_port, &token).await {
+                            Ok(Some(current)) => {
+                                let mut next = (*app_state).clone();
+                                next.session_token = Some(current);
+                                next.auth_error = None;
+                                app_state.set(next);
+                            }
+                            Ok(None) => {
+                                toast.toast(SESSION_EXPIRED, ToastVariant::Warning, Some(4000));
+                                expire_session(app_state);
+                            }
+                            Err(message) => {
+                                let mut next = (*app_state).clone();
+                                next.auth_error = Some(message);
+                                app_state.set(next);
+                            }
                         }
                     });
                 }
@@ -309,17 +356,86 @@ fn app_router() -> Html {
     }
 
     if app_state.logged_in {
-        html! { <Dashboard /> }
+        // Keyed by server so switching remounts every screen with the new
+        // server's settings.
+        html! { <Dashboard key={app_state.server_ip.clone().unwrap_or_default()} /> }
     } else {
         html! { <LoginScreen /> }
     }
 }
 
+const SESSION_EXPIRED: &str = "Your session expired. Please log in again.";
+
+/// Checks the session right away, for when the server rejected it between
+/// the regular checks (a WebSocket closed with `WS_CLOSE_UNAUTHORIZED`).
+/// Renews it, or sends the user to the login screen if that fails.
+pub fn recheck_session(app_state: AppStateHandle, toast: ToastContext) {
+    let (Some(server_ip), Some(server_port), Some(token)) = (
+        app_state.server_ip.clone(),
+        app_state.server_port.clone(),
+        app_state.session_token.clone(),
+    ) else {
+        return;
+    };
+    spawn_local(async move {
+        let result = check_session(&server_ip, &server_port, &token).await;
+        apply_session_check(&app_state, &toast, &token, result);
+    });
+}
+
+/// Applies the result of `check_session` to the app state. Returns false
+/// when polling should stop: the session was renewed or the client went
+/// offline or back online (each of which restarts it), the session ended,
+/// or the backend failed.
+fn apply_session_check(
+    app_state: &AppStateHandle,
+    toast: &ToastContext,
+    token: &str,
+    result: Result<Option<String>, String>,
+) -> bool {
+    match result {
+        Ok(Some(current)) if current == token && !app_state.offline => true,
+        Ok(Some(current)) => {
+            let mut next = (**app_state).clone();
+            next.session_token = Some(current);
+            next.offline = false;
+            app_state.set(next);
+            false
+        }
+        // Offline, polling carries on until the server answers again.
+        Err(message) if is_unreachable(&message) => {
+            if app_state.offline {
+                return true;
+            }
+            let mut next = (**app_state).clone();
+            next.offline = true;
+            app_state.set(next);
+            false
+        }
+        Ok(None) => {
+            toast.toast(SESSION_EXPIRED, ToastVariant::Warning, Some(4000));
+            expire_session(app_state.clone());
+            false
+        }
+        Err(message) => {
+            let mut next = (**app_state).clone();
+            next.auth_error = Some(message);
+            app_state.set(next);
+            false
+        }
+    }
+}
+
 #[function_component(LoginScreen)]
 fn login_screen() -> Html {
+    let app_state = use_context::<AppStateHandle>()
+        .expect("AppState context not found. Ensure LoginScreen is under <ContextProvider>.");
     let server_ip = use_state(|| get_local_storage_item(SERVER_IP_KEY).unwrap_or_default());
+    let profiles = use_state(load_profiles);
     let password = use_state(String::new);
-    let password_required = use_state(|| false);
+    let username = use_state(String::new);
+    let account_password = use_state(String::new);
+    let options = use_state(LoginOptions::default);
     let toast = use_toast();
     let feature_req_id = use_mut_ref(|| 0u64);
     let on_ip_input = {
@@ -333,48 +449,33 @@ fn login_screen() -> Html {
 
     {
         let server_ip = server_ip.clone();
-        let password_required = password_required.clone();
+        let options = options.clone();
         let feature_req_id = feature_req_id.clone();
         use_effect_with(server_ip.clone(), move |server_ip| {
             let server_ip = server_ip.clone();
-            if server_ip.trim().is_empty() {
-                password_required.set(false);
-                return ();
-            }
+            let Ok(server) = ServerAddress::parse(&server_ip, DEFAULT_PORT) else {
+                options.set(LoginOptions::default());
+                return;
+            };
             *feature_req_id.borrow_mut() += 1;
             let req_id = *feature_req_id.borrow();
             spawn_local(async move {
-                let features_url = build_http_url(&server_ip, "2121", "features");
-                let mut requires_password = false;
-                if let Ok(resp) = send_request("GET", &features_url, None, None).await {
-                    if resp.ok() {
-                        if let Ok(json) = resp.json() {
-                            if let Ok(value) = wasm_bindgen_futures::JsFuture::from(json).await {
-                                if let Ok(features) =
-                                    serde_wasm_bindgen::from_value::<serde_json::Value>(value)
-                                {
-                                    requires_password = features
-                                        .get("login_password_required")
-                                        .and_then(|v| v.as_bool())
-                                        .unwrap_or(false);
-                                }
-                            }
-                        }
-                    }
-                }
+                let next = fetch_login_options(&server).await.unwrap_or_default();
                 if *feature_req_id.borrow() == req_id {
-                    password_required.set(requires_password);
+                    options.set(next);
                 }
             });
-            ()
         });
     }
-    let on_login_click = {
+    let on_login = {
+        let app_state = app_state.clone();
         let server_ip = server_ip.clone();
         let password = password.clone();
-        let password_required = password_required.clone();
+        let username = username.clone();
+        let account_password = account_password.clone();
+        let options = options.clone();
         let toast = toast.clone();
-        Callback::from(move |_| {
+        Callback::from(move |(provider, create_account): (LoginProvider, bool)| {
             let ip = server_ip.as_str().trim().to_string();
             if ip.is_empty() {
                 toast.toast(
@@ -384,56 +485,47 @@ fn login_screen() -> Html {
                 );
                 return;
             }
-            let password_value = password.as_str().trim().to_string();
-
-            let toast = toast.clone();
-            let password_required = password_required.clone();
-            spawn_local(async move {
-                let features_url = build_http_url(&ip, "2121", "features");
-                let mut requires_password = false;
-                if let Ok(resp) = send_request("GET", &features_url, None, None).await {
-                    if resp.ok() {
-                        if let Ok(json) = resp.json() {
-                            if let Ok(value) = wasm_bindgen_futures::JsFuture::from(json).await {
-                                if let Ok(features) =
-                                    serde_wasm_bindgen::from_value::<serde_json::Value>(value)
-                                {
-                                    requires_password = features
-                                        .get("login_password_required")
-                                        .and_then(|v| v.as_bool())
-                                        .unwrap_or(false);
-                                }
-                            }
-                        }
-                    }
-                }
-                password_required.set(requires_password);
-                if requires_password && password_value.is_empty() {
-                    toast.toast(
-                        "This server requires a login password.",
-                        ToastVariant::Warning,
-                        Some(3000),
-                    );
+            let server = match ServerAddress::parse(&ip, DEFAULT_PORT) {
+                Ok(server) => server,
+                Err(message) => {
+                    toast.toast(&message, ToastVariant::Warning, Some(3000));
                     return;
                 }
+            };
+            let form = LoginForm {
+                access_password: (*password).clone(),
+                username: (*username).clone(),
+                password: (*account_password).clone(),
+                create_account,
+            };
 
-                let ping_url = build_http_url(&ip, "2121", "ping");
-                match send_request("GET", &ping_url, None, None).await {
-                    Ok(resp) if resp.ok() => handle_login(&ip, Some(&password_value)),
-                    Ok(resp) => {
+            let app_state = app_state.clone();
+            let options = options.clone();
+            let toast = toast.clone();
+            spawn_local(async move {
+                // The access password gates new accounts, which any
+                // redirect login can create.
+                if let Ok(latest) = fetch_login_options(&server).await {
+                    let needs_password = latest.login_password_required
+                        && (provider.kind == LoginKind::Redirect || create_account);
+                    options.set(latest);
+                    if needs_password && form.access_password.trim().is_empty() {
                         toast.toast(
-                            &format!("Backend error (HTTP {}).", resp.status()),
-                            ToastVariant::Error,
+                            "This server requires a login password.",
+                            ToastVariant::Warning,
                             Some(3000),
                         );
+                        return;
                     }
-                    Err(_) => {
-                        toast.toast(
-                            "Backend could not be reached.",
-                            ToastVariant::Error,
-                            Some(3000),
-                        );
+                }
+
+                match login(&server, &provider, &form).await {
+                    Ok(LoginOutcome::LoggedIn(token)) => {
+                        start_session(app_state, token);
+                        toast.toast("Logged in successfully.", ToastVariant::Success, Some(2500));
                     }
+                    Ok(LoginOutcome::Redirected) => {}
+                    Err(message) => toast.toast(&message, ToastVariant::Error, Some(3000)),
                 }
             });
         })
@@ -445,6 +537,44 @@ fn login_screen() -> Html {
             password.set(input.value());
         })
     };
+    let on_username_input = {
+        let username = username.clone();
+        Callback::from(move |event: InputEvent| {
+            let input: HtmlInputElement = event.target_unchecked_into();
+            username.set(input.value());
+        })
+    };
+    let on_account_password_input = {
+        let account_password = account_password.clone();
+        Callback::from(move |event: InputEvent| {
+            let input: HtmlInputElement = event.target_unchecked_into();
+            account_password.set(input.value());
+        })
+    };
+
+    let on_pick_profile = {
+        let app_state = app_state.clone();
+        let server_ip = server_ip.clone();
+        Callback::from(move |profile: ServerProfile| {
+            if profile.session_token.is_some() {
+                switch_server(app_state.clone(), Some(&profile.server_ip));
+            } else {
+                server_ip.set(profile.server_ip);
+            }
+        })
+    };
+
+    let on_forget_profile = {
+        let profiles = profiles.clone();
+        let server_ip = server_ip.clone();
+        Callback::from(move |forgotten: String| {
+            forget_profile(&forgotten);
+            if *server_ip == forgotten {
+                server_ip.set(String::new());
+            }
+            profiles.set(load_profiles());
+        })
+    };
 
     html! {
         <main class="min-h-screen text-secondary flex items-center justify-center">
@@ -467,7 +597,7 @@ fn login_screen() -> Html {
                         oninput={on_ip_input}
                     />
                 </div>
-                if *password_required {
+                if options.login_password_required {
                     <div class="mt-4 text-xs uppercase tracking-wide text-accent/80">
                         { "Access Password" }
                     </div>
@@ -481,13 +611,104 @@ fn login_screen() -> Html {
                         />
                     </div>
                 }
-                <Button
-                    class={Some("mt-6 relative z-10".to_string())}
-                    onclick={on_login_click}
-                >
-                    <DiscordIcon />
-                    { "Log in with Discord" }
-                </Button>
+                if options.login_providers.is_empty() {
+                    <p class="mt-6 text-sm text-accent">
+                        { "This server has no login methods set up." }
+                    </p>
+                }
+                { for options.login_providers.iter().map(|provider| {
+                    let on_provider = |create_account: bool| {
+                        let on_login = on_login.clone();
+                        let provider = provider.clone();
+                        Callback::from(move |_| on_login.emit((provider.clone(), create_account)))
+                    };
+                    match provider.kind {
+                        LoginKind::Redirect => html! {
+                            <Button
+                                class={Some("mt-6 relative z-10".to_string())}
+                                onclick={on_provider(false)}
+                            >
+                                if provider.id == "discord" {
+                                    <DiscordIcon />
+                                }
+                                { format!("Log in with {}", provider.name) }
+                            </Button>
+                        },
+                        LoginKind::Password => html! {
+                            <div class="mt-6 rounded-xl border border-ink/50 bg-ink/40 p-4">
+                                <div class="text-xs uppercase tracking-wide text-accent/80">
+                                    { provider.name.clone() }
+                                </div>
+                                <input
+                                    class="mt-2 w-full rounded border border-ink/50 bg-ink/50 px-3 py-2 text-secondary placeholder:text-secondary/60 outline outline-1 outline-accent/50 focus:outline-none focus:ring-2 focus:ring-primary/40"
+                                    type="text"
+                                    placeholder="Username"
+                                    value={(*username).clone()}
+                                    oninput={on_username_input.clone()}
+                                />
+                                <input
+                                    class="mt-2 w-full rounded border border-ink/50 bg-ink/50 px-3 py-2 text-secondary placeholder:text-secondary/60 outline outline-1 outline-accent/50 focus:outline-none focus:ring-2 focus:ring-primary/40"
+                                    type="password"
+                                    placeholder="Password"
+                                    value={(*account_password).clone()}
+                                    oninput={on_account_password_input.clone()}
+                                />
+                                <div class="mt-3 flex gap-2">
+                                    <Button onclick={on_provider(false)}>
+                                        { "Log in" }
+                                    </Button>
+                                    <Button
+                                        class={Some("border border-ink/50 bg-ink/40 text-secondary hover:bg-ink/50".to_string())}
+                                        onclick={on_provider(true)}
+                                    >
+                                        { "Create account" }
+                                    </Button>
+                                </div>
+                            </div>
+                        },
+                    }
+                }) }
+                if !profiles.is_empty() {
+                    <div class="mt-8 text-xs uppercase tracking-wide text-accent/80">
+                        { "Saved servers" }
+                    </div>
+                    <div class="mt-2 flex flex-col gap-2">
+                        { for profiles.iter().map(|profile| {
+                            let on_pick = {
+                                let on_pick_profile = on_pick_profile.clone();
+                                let profile = profile.clone();
+                                Callback::from(move |_| on_pick_profile.emit(profile.clone()))
+                            };
+                            let on_forget = {
+                                let on_forget_profile = on_forget_profile.clone();
+                                let server_ip = profile.server_ip.clone();
+                                Callback::from(move |_| on_forget_profile.emit(server_ip.clone()))
+                            };
+                            html! {
+                                <div key={profile.server_ip.clone()} class="flex items-center justify-between gap-3 rounded-xl border border-ink/50 bg-ink/40 px-3 py-2">
+                                    <div class="min-w-0">
+                                        <p class="truncate text-sm font-semibold">{ profile.name.clone() }</p>
+                                        <p class="truncate text-xs text-secondary/60">{ profile.server_ip.clone() }</p>
+                                    </div>
+                                    <div class="flex shrink-0 gap-2">
+                                        <Button
+                                            class={Some("border border-primary/60 bg-primary/30 text-secondary hover:bg-primary/40".to_string())}
+                                            onclick={on_pick}
+                                        >
+                                            { if profile.session_token.is_some() { "Open" } else { "Use" } }
+                                        </Button>
+                                        <Button
+                                            class={Some("border border-ink/50 bg-ink/40 text-secondary hover:bg-ink/50".to_string())}
+                                            onclick={on_forget}
+                                        >
+                                            { "Forget" }
+                                        </Button>
+                                    </div>
+                                </div>
+                            }
+                        }) }
+                    </div>
+                }
             </Card>
         </main>
     }
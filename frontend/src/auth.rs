@@ -1,72 +1,292 @@
-use wasm_bindgen::prelude::JsValue;
-use web_sys::{window, UrlSearchParams};
-use yew::UseStateHandle;
+use std::cell::RefCell;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use futures_util::future::{FutureExt, LocalBoxFuture, Shared};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use wasm_bindgen::prelude::{Closure, JsValue};
+use wasm_bindgen::JsCast;
+use web_sys::{window, CloseEvent, UrlSearchParams, WebSocket};
+use yew::{Callback, UseStateHandle};
 
 use crate::app::{AppState, User};
 use wasm_bindgen_futures::spawn_local;
 
-use crate::api::{get_json, send_json, send_request};
-use crate::net::build_http_url;
+use crate::api::{get_json, read_json, send_json, send_request};
+use crate::net::{build_http_url, build_ws_url, ServerAddress, DEFAULT_PORT};
+use crate::profiles::{activate_for_login, activate_profile, save_active_profile};
 
 pub const SERVER_IP_KEY: &str = "gaggle_server_ip";
 pub const SERVER_PORT_KEY: &str = "gaggle_server_port";
 pub const SESSION_TOKEN_KEY: &str = "gaggle_session_token";
-pub const LOGIN_SUCCESS_KEY: &str = "gaggle_login_success";
+pub const REFRESH_TOKEN_KEY: &str = "gaggle_refresh_token";
+pub const LOGIN_VERIFIER_KEY: &str = "gaggle_login_verifier";
 pub const INSTALL_DIR_KEY: &str = "gaggle_install_dir";
+pub const PINNED_VERSIONS_KEY: &str = "gaggle_pinned_versions";
 
-pub fn handle_login(server_input: &str, password: Option<&str>) {
-    let (server_base, port_hint) = match normalize_server_input(server_input) {
-        Ok(result) => result,
-        Err(_) => return,
-    };
+/// Close code the server uses when it rejects a WebSocket's session.
+pub const WS_CLOSE_UNAUTHORIZED: u16 = 4401;
+
+/// How long before it expires a session is renewed.
+const RENEW_BEFORE_SECONDS: f64 = 10.0 * 60.0;
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LoginKind {
+    /// Logs in on the provider's own page and comes back with a code.
+    Redirect,
+    /// Logs in with a username and password form.
+    Password,
+}
+
+/// A way to log into a server, as advertised by its `/features`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct LoginProvider {
+    pub id: String,
+    pub name: String,
+    pub kind: LoginKind,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct LoginOptions {
+    #[serde(default)]
+    pub login_password_required: bool,
+    /// Servers from before login providers only offer Discord.
+    #[serde(default = "discord_only")]
+    pub login_providers: Vec<LoginProvider>,
+}
 
-    set_local_storage_item(SERVER_IP_KEY, &server_base);
-    set_local_storage_item(SERVER_PORT_KEY, &port_hint);
-
-    let redirect = current_app_url();
-    let redirect = js_sys::encode_uri_component(&redirect)
-        .as_string()
-        .unwrap_or_default();
-    let mut login_url = build_http_url(
-        &server_base,
-        &port_hint,
-        &format!("auth/discord/login?redirect={redirect}"),
-    );
-    if let Some(password) = password {
-        let password = password.trim();
-        if !password.is_empty() {
-            let encoded = js_sys::encode_uri_component(password)
-                .as_string()
-                .unwrap_or_default();
-            login_url = format!("{login_url}&password={encoded}");
+impl Default for LoginOptions {
+    fn default() -> Self {
+        LoginOptions {
+            login_password_required: false,
+            login_providers: discord_only(),
         }
     }
+}
+
+fn discord_only() -> Vec<LoginProvider> {
+    vec![LoginProvider {
+        id: "discord".to_string(),
+        name: "Discord".to_string(),
+        kind: LoginKind::Redirect,
+    }]
+}
+
+/// What was entered on the login screen. Providers use the parts they need.
+#[derive(Debug, Clone, Default)]
+pub struct LoginForm {
+    /// The server's access password, if it has one.
+    pub access_password: String,
+    pub username: String,
+    pub password: String,
+    /// Create the account instead of logging into an existing one.
+    pub create_account: bool,
+}
+
+pub enum LoginOutcome {
+    /// The app is leaving for the provider's login page and finishes the
+    /// login in `complete_login` when it comes back.
+    Redirected,
+    /// Logged in; holds the session token.
+    LoggedIn(String),
+}
+
+pub async fn fetch_login_options(server: &ServerAddress) -> Result<LoginOptions, String> {
+    get_json(&server.http_url("features"), None).await
+}
+
+/// Logs into `server` with `provider`, whichever kind it is.
+pub async fn login(
+    server: &ServerAddress,
+    provider: &LoginProvider,
+    form: &LoginForm,
+) -> Result<LoginOutcome, String> {
+    match provider.kind {
+        LoginKind::Redirect => start_redirect_login(server, provider, form).await,
+        LoginKind::Password => password_login(server, provider, form).await,
+    }
+}
+
+#[derive(Deserialize)]
+struct LoginStart {
+    url: String,
+}
+
+/// The access password and a PKCE-style challenge are sent in the request
+/// body; the verifier stays here until `complete_login` trades the code
+/// from the redirect for a session, so nothing secret ends up in a URL.
+async fn start_redirect_login(
+    server: &ServerAddress,
+    provider: &LoginProvider,
+    form: &LoginForm,
+) -> Result<LoginOutcome, String> {
+    let verifier = new_code_verifier()?;
+    let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+    let body = serde_json::json!({
+        "redirect": current_app_url(),
+        "code_challenge": challenge,
+        "code_challenge_method": "S256",
+        "password": form.access_password.trim(),
+    });
+    let url = server.http_url(&format!("auth/{}/login", provider.id));
+    let resp = send_json("POST", &url, None, Some(body)).await?;
+    if resp.status() == 401 {
+        return Err("Wrong server access password.".to_string());
+    }
+    if !resp.ok() {
+        return Err(format!("Backend error (HTTP {}).", resp.status()));
+    }
+    let start: LoginStart = read_json(resp).await?;
+
+    activate_for_login(server);
+    set_local_storage_item(LOGIN_VERIFIER_KEY, &verifier);
 
     if let Some(win) = window() {
-        if win.location().set_href(&login_url).is_ok() {
-            return;
+        if win.location().set_href(&start.url).is_ok() {
+            return Ok(LoginOutcome::Redirected);
+        }
+        let _ = win.open_with_url_and_target(&start.url, "_blank");
+    }
+    Ok(LoginOutcome::Redirected)
+}
+
+async fn password_login(
+    server: &ServerAddress,
+    provider: &LoginProvider,
+    form: &LoginForm,
+) -> Result<LoginOutcome, String> {
+    let username = form.username.trim();
+    if username.is_empty() || form.password.is_empty() {
+        return Err("Enter your username and password.".to_string());
+    }
+    let action = if form.create_account {
+        "register"
+    } else {
+        "login"
+    };
+    let body = serde_json::json!({
+        "username": username,
+        "password": form.password,
+        "access_password": form.access_password.trim(),
+    });
+    let url = server.http_url(&format!("auth/{}/{}", provider.id, action));
+    let resp = send_json("POST", &url, None, Some(body)).await?;
+    match resp.status() {
+        401 if form.create_account => return Err("Wrong server access password.".to_string()),
+        401 => return Err("Wrong username or password.".to_string()),
+        409 => return Err("That username is taken.".to_string()),
+        400 if form.create_account => {
+            return Err(
+                "Usernames are 3 to 32 letters, digits, dots, dashes or underscores, \
+                 and passwords at least 8 characters."
+                    .to_string(),
+            )
         }
-        let _ = win.open_with_url_and_target(&login_url, "_blank");
+        _ if !resp.ok() => return Err(format!("Backend error (HTTP {}).", resp.status())),
+        _ => {}
+    }
+    let session: IssuedSession = read_json(resp).await?;
+
+    activate_for_login(server);
+    set_local_storage_item(SESSION_TOKEN_KEY, &session.token);
+    set_local_storage_item(REFRESH_TOKEN_KEY, &session.refresh_token);
+    save_active_profile();
+    Ok(LoginOutcome::LoggedIn(session.token))
+}
+
+/// Shows the dashboard for the active server with a freshly issued session.
+pub fn start_session(app_state: UseStateHandle<AppState>, token: String) {
+    let (server_ip, server_port) = stored_server();
+    app_state.set(AppState {
+        logged_in: true,
+        server_ip,
+        server_port,
+        session_token: Some(token),
+        auth_error: None,
+        offline: false,
+        user: None,
+    });
+}
+
+/// Finishes a login when the server redirects back with a one-time `code`,
+/// and stores the session. Returns the new session token.
+pub async fn complete_login(code: &str) -> Result<String, String> {
+    let verifier = get_local_storage_item(LOGIN_VERIFIER_KEY)
+        .ok_or_else(|| "This login wasn't started here. Please log in again.".to_string())?;
+    remove_local_storage_item(LOGIN_VERIFIER_KEY);
+    let (Some(server_ip), Some(server_port)) = stored_server() else {
+        return Err("No server selected. Please log in again.".to_string());
+    };
+    let url = build_http_url(&server_ip, &server_port, "auth/token");
+    let body = serde_json::json!({ "code": code, "code_verifier": verifier });
+    let resp = send_json("POST", &url, None, Some(body)).await?;
+    if resp.status() == 401 {
+        return Err("Login expired. Please log in again.".to_string());
+    }
+    if !resp.ok() {
+        return Err(format!("Backend error (HTTP {}).", resp.status()));
     }
+    let session: IssuedSession = read_json(resp).await?;
+    set_local_storage_item(SESSION_TOKEN_KEY, &session.token);
+    set_local_storage_item(REFRESH_TOKEN_KEY, &session.refresh_token);
+    save_active_profile();
+    Ok(session.token)
+}
+
+/// 32 random bytes, base64url encoded: a 43 character PKCE verifier.
+fn new_code_verifier() -> Result<String, String> {
+    let crypto = window()
+        .and_then(|win| win.crypto().ok())
+        .ok_or_else(|| "Secure random numbers are not available.".to_string())?;
+    let mut bytes = [0u8; 32];
+    crypto
+        .get_random_values_with_u8_array(&mut bytes)
+        .map_err(|_| "Failed to start login.".to_string())?;
+    Ok(URL_SAFE_NO_PAD.encode(bytes))
 }
 
 pub fn handle_logout(app_state: UseStateHandle<AppState>) {
     let server_ip = app_state.server_ip.clone();
     let server_port = app_state.server_port.clone();
     let token = app_state.session_token.clone();
+    let refresh_token = get_local_storage_item(REFRESH_TOKEN_KEY);
     if let (Some(server_ip), Some(server_port), Some(token)) = (server_ip, server_port, token) {
         spawn_local(async move {
             let url = build_http_url(&server_ip, &server_port, "auth/logout");
-            let _ = send_json("POST", &url, Some(&token), None).await;
+            let body = refresh_token.map(|token| serde_json::json!({ "refresh_token": token }));
+            let _ = send_json("POST", &url, Some(&token), body).await;
         });
     }
     remove_local_storage_item(SESSION_TOKEN_KEY);
+    remove_local_storage_item(REFRESH_TOKEN_KEY);
+    save_active_profile();
+    let (server_ip, server_port) = stored_server();
     app_state.set(AppState {
         logged_in: false,
-        server_ip: get_local_storage_item(SERVER_IP_KEY),
-        server_port: get_local_storage_item(SERVER_PORT_KEY).or_else(|| Some("2121".to_string())),
+        server_ip,
+        server_port,
         session_token: None,
         auth_error: None,
+        offline: false,
+        user: None,
+    });
+}
+
+/// Switches to a saved server, or to the login screen with `None`. The
+/// server being left keeps its session.
+pub fn switch_server(app_state: UseStateHandle<AppState>, server_ip: Option<&str>) {
+    activate_profile(server_ip);
+    let (server_ip, server_port) = stored_server();
+    let token = get_local_storage_item(SESSION_TOKEN_KEY);
+    app_state.set(AppState {
+        logged_in: token.is_some(),
+        server_ip,
+        server_port,
+        session_token: token,
+        auth_error: None,
+        offline: false,
         user: None,
     });
 }
@@ -133,34 +353,189 @@ pub fn remove_local_storage_item(key: &str) {
     }
 }
 
-fn normalize_server_input(input: &str) -> Result<(String, String), String> {
-    let trimmed = input.trim();
-    if trimmed.is_empty() {
-        return Err("missing server address".to_string());
+/// The saved server as `(base URL, port)`. Logins save the canonical URL;
+/// an address saved as typed by an older version is rewritten once, keeping
+/// its old meaning that a host without a port is reached over HTTPS.
+pub fn stored_server() -> (Option<String>, Option<String>) {
+    let port = get_local_storage_item(SERVER_PORT_KEY).or_else(|| Some(DEFAULT_PORT.to_string()));
+    let Some(ip) = get_local_storage_item(SERVER_IP_KEY) else {
+        return (None, port);
+    };
+    if ip.contains("://") {
+        return (Some(ip), port);
     }
-    if trimmed.starts_with("http://") || trimmed.starts_with("https://") {
-        let base = trimmed.trim_end_matches('/').to_string();
-        let port_hint = if trimmed.starts_with("https://") {
-            "443".to_string()
-        } else {
-            "80".to_string()
-        };
-        return Ok((base, port_hint));
+    match ServerAddress::parse(&ip, "") {
+        Ok(server) => {
+            let (ip, port) = (server.to_string(), server.port().to_string());
+            set_local_storage_item(SERVER_IP_KEY, &ip);
+            set_local_storage_item(SERVER_PORT_KEY, &port);
+            (Some(ip), Some(port))
+        }
+        Err(_) => (Some(ip), port),
     }
+}
 
-    Ok((trimmed.to_string(), "2121".to_string()))
+/// Sends the user back to the login screen of the current server after the
+/// session could not be renewed. Downloads are left running.
+pub fn expire_session(app_state: UseStateHandle<AppState>) {
+    remove_local_storage_item(SESSION_TOKEN_KEY);
+    remove_local_storage_item(REFRESH_TOKEN_KEY);
+    save_active_profile();
+    let (server_ip, server_port) = stored_server();
+    app_state.set(AppState {
+        logged_in: false,
+        server_ip,
+        server_port,
+        session_token: None,
+        auth_error: None,
+        offline: false,
+        user: None,
+    });
+}
+
+#[derive(Deserialize)]
+struct SessionInfo {
+    #[serde(default)]
+    expires_at: f64,
+}
+
+#[derive(Deserialize)]
+struct IssuedSession {
+    token: String,
+    refresh_token: String,
+}
+
+/// Checks the session and renews it when it has expired or is about to.
+/// Returns the token to use from now on, or `None` when the session is gone
+/// and the user has to log in again.
+pub async fn check_session(
+    server_ip: &str,
+    server_port: &str,
+    token: &str,
+) -> Result<Option<String>, String> {
+    let url = build_http_url(server_ip, server_port, "auth/session");
+    let resp = send_request("GET", &url, Some(token), None).await?;
+    if resp.status() == 401 {
+        return renew_session(server_ip, server_port).await;
+    }
+    // Servers without session renewal only tell whether the token works.
+    if resp.status() == 404 {
+        return check_session_legacy(server_ip, server_port, token).await;
+    }
+    if !resp.ok() {
+        return Err(format!("Backend error (HTTP {}).", resp.status()));
+    }
+    let info: SessionInfo = read_json(resp).await?;
+    let now = js_sys::Date::now() / 1000.0;
+    if info.expires_at > 0.0 && info.expires_at - now < RENEW_BEFORE_SECONDS {
+        // The session still works, so a failed renewal can wait for the
+        // next check.
+        if let Ok(Some(token)) = renew_session(server_ip, server_port).await {
+            return Ok(Some(token));
+        }
+    }
+    Ok(Some(token.to_string()))
 }
 
-pub async fn check_session(server_ip: &str, server_port: &str, token: &str) -> Result<(), String> {
+async fn check_session_legacy(
+    server_ip: &str,
+    server_port: &str,
+    token: &str,
+) -> Result<Option<String>, String> {
     let url = build_http_url(server_ip, server_port, "users/me");
     let resp = send_request("GET", &url, Some(token), None).await?;
     if resp.status() == 401 {
-        return Err("Session expired. Please log in again.".to_string());
+        return Ok(None);
     }
     if !resp.ok() {
         return Err(format!("Backend error (HTTP {}).", resp.status()));
     }
-    Ok(())
+    Ok(Some(token.to_string()))
+}
+
+type Renewal = Shared<LocalBoxFuture<'static, Result<Option<String>, String>>>;
+
+thread_local! {
+    /// The renewal in flight. Refresh tokens only work once, so callers that
+    /// need a renewal at the same time wait for this one instead of sending
+    /// the same token again and losing the session to the 401.
+    static RENEWAL: RefCell<Option<Renewal>> = const { RefCell::new(None) };
+}
+
+/// Renews the session, joining a renewal that is already under way.
+async fn renew_session(server_ip: &str, server_port: &str) -> Result<Option<String>, String> {
+    let renewal = RENEWAL.with(|slot| {
+        slot.borrow_mut()
+            .get_or_insert_with(|| {
+                let server_ip = server_ip.to_string();
+                let server_port = server_port.to_string();
+                async move {
+                    let result = request_renewal(&server_ip, &server_port).await;
+                    RENEWAL.with(|slot| slot.borrow_mut().take());
+                    result
+                }
+                .boxed_local()
+                .shared()
+            })
+            .clone()
+    });
+    renewal.await
+}
+
+/// Trades the stored refresh token for a new session and stores both new
+/// tokens. `None` when the server no longer accepts the refresh token.
+async fn request_renewal(server_ip: &str, server_port: &str) -> Result<Option<String>, String> {
+    let Some(refresh_token) = get_local_storage_item(REFRESH_TOKEN_KEY) else {
+        return Ok(None);
+    };
+    let url = build_http_url(server_ip, server_port, "auth/refresh");
+    let body = serde_json::json!({ "refresh_token": refresh_token });
+    let resp = send_json("POST", &url, None, Some(body)).await?;
+    if resp.status() == 400 || resp.status() == 401 {
+        return Ok(None);
+    }
+    if !resp.ok() {
+        return Err(format!("Backend error (HTTP {}).", resp.status()));
+    }
+    let renewed: IssuedSession = read_json(resp).await?;
+    set_local_storage_item(SESSION_TOKEN_KEY, &renewed.token);
+    set_local_storage_item(REFRESH_TOKEN_KEY, &renewed.refresh_token);
+    save_active_profile();
+    Ok(Some(renewed.token))
+}
+
+/// Opens a WebSocket to `path` and authenticates it by sending `token` as
+/// the first frame, which keeps the token out of the URL. `on_unauthorized`
+/// runs if the server closes the socket because it rejected the session.
+pub fn open_socket(
+    server_ip: &str,
+    server_port: &str,
+    path: &str,
+    token: &str,
+    on_unauthorized: Callback<()>,
+) -> Result<WebSocket, String> {
+    let ws = WebSocket::new(&build_ws_url(server_ip, server_port, path))
+        .map_err(|_| "Failed to open socket.".to_string())?;
+
+    let auth = serde_json::json!({ "type": "auth", "token": token }).to_string();
+    let onopen = {
+        let ws = ws.clone();
+        Closure::<dyn FnMut(web_sys::Event)>::wrap(Box::new(move |_event| {
+            let _ = ws.send_with_str(&auth);
+        }))
+    };
+    ws.set_onopen(Some(onopen.as_ref().unchecked_ref()));
+    onopen.forget();
+
+    let onclose = Closure::<dyn FnMut(CloseEvent)>::wrap(Box::new(move |event: CloseEvent| {
+        if event.code() == WS_CLOSE_UNAUTHORIZED {
+            on_unauthorized.emit(());
+        }
+    }));
+    ws.set_onclose(Some(onclose.as_ref().unchecked_ref()));
+    onclose.forget();
+
+    Ok(ws)
 }
 
 pub async fn fetch_me(server_ip: &str, server_port: &str, token: &str) -> Result<User, String> {
@@ -118,6 +118,9 @@ pub struct DownloadSpeedGraphProps {
     #[prop_or(72)]
     pub height: u32,
     pub paused: bool,
+    /// Seconds of history shown across the graph.
+    #[prop_or(10.0)]
+    pub window_seconds: f64,
 }
 
 #[function_component(DownloadSpeedGraph)]
@@ -136,12 +139,13 @@ pub fn download_speed_graph(props: &DownloadSpeedGraphProps) -> Html {
         let interp_point = interp_point.clone();
         let last_real = last_real.clone();
         let points = props.points.clone();
+        let window_seconds = props.window_seconds;
 
         use_effect_with(points, move |points| {
             let mut h = history.borrow_mut();
 
             if let Some(&(t, v)) = points.last() {
-                let cutoff = t - 10.0;
+                let cutoff = t - window_seconds;
 
                 if h.last() != Some(&(t, v)) {
                     h.push((t, v));
@@ -224,7 +228,7 @@ pub fn download_speed_graph(props: &DownloadSpeedGraphProps) -> Html {
     let max_speed = nice_max(raw_max * 1.1); // 10% headroom
 
     let width = 100.0;
-    let window = 10.0;
+    let window = props.window_seconds;
 
     let first_t = points.first().unwrap().0;
     let now = last_real
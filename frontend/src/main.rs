@@ -1,13 +1,14 @@
-mod app;
 mod api;
+mod app;
 mod auth;
 mod components;
 mod confirm;
-mod net;
+mod profiles;
 mod screens;
 mod toast;
 
 use app::App;
+use gaggle_shared::net;
 
 fn main() {
     console_error_panic_hook::set_once();
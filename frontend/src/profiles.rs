@@ -0,0 +1,189 @@
+//! Saved server profiles. The active server keeps living in the plain
+//! localStorage keys the screens read (`SERVER_IP_KEY`, `SESSION_TOKEN_KEY`,
+//! `INSTALL_DIR_KEY`, ...); a profile is a copy of those values, written back
+//! when another profile is activated.
+
+use serde::{Deserialize, Serialize};
+
+use crate::app::Theme;
+use crate::auth::{
+    get_local_storage_item, remove_local_storage_item, set_local_storage_item, INSTALL_DIR_KEY,
+    REFRESH_TOKEN_KEY, SERVER_IP_KEY, SERVER_PORT_KEY, SESSION_TOKEN_KEY,
+};
+use crate::net::{server_key, ServerAddress};
+
+pub const PROFILES_KEY: &str = "gaggle_server_profiles";
+const LIBRARY_CACHE_PREFIX: &str = "gaggle_library_cache:";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ServerProfile {
+    pub name: String,
+    /// Canonical base URL, which also identifies the profile.
+    pub server_ip: String,
+    pub server_port: String,
+    #[serde(default)]
+    pub session_token: Option<String>,
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    #[serde(default)]
+    pub install_dir: Option<String>,
+    #[serde(default)]
+    pub theme: Option<Theme>,
+}
+
+pub fn load_profiles() -> Vec<ServerProfile> {
+    get_local_storage_item(PROFILES_KEY)
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn store_profiles(profiles: &[ServerProfile]) {
+    if let Ok(raw) = serde_json::to_string(profiles) {
+        set_local_storage_item(PROFILES_KEY, &raw);
+    }
+}
+
+/// Copies the live keys into the active server's profile, creating it for a
+/// server that was logged into before profiles existed.
+pub fn save_active_profile() {
+    let Some(server_ip) = get_local_storage_item(SERVER_IP_KEY) else {
+        return;
+    };
+    let mut profiles = load_profiles();
+    let index = match profiles.iter().position(|p| p.server_ip == server_ip) {
+        Some(index) => index,
+        None => {
+            profiles.push(ServerProfile {
+                name: default_name(&server_ip),
+                server_ip: server_ip.clone(),
+                server_port: String::new(),
+                session_token: None,
+                refresh_token: None,
+                install_dir: None,
+                theme: None,
+            });
+            profiles.len() - 1
+        }
+    };
+    let profile = &mut profiles[index];
+    profile.server_port = get_local_storage_item(SERVER_PORT_KEY).unwrap_or_default();
+    profile.session_token = get_local_storage_item(SESSION_TOKEN_KEY);
+    profile.refresh_token = get_local_storage_item(REFRESH_TOKEN_KEY);
+    profile.install_dir = get_local_storage_item(INSTALL_DIR_KEY);
+    store_profiles(&profiles);
+}
+
+/// Saves the active profile and loads `server_ip`'s values into the live
+/// keys. With `None`, no server is active afterwards.
+pub fn activate_profile(server_ip: Option<&str>) -> Option<ServerProfile> {
+    save_active_profile();
+    let profile = server_ip.and_then(|server_ip| {
+        load_profiles()
+            .into_iter()
+            .find(|p| p.server_ip == server_ip)
+    });
+    let set_or_remove = |key: &str, value: Option<&String>| match value {
+        Some(value) => set_local_storage_item(key, value),
+        None => remove_local_storage_item(key),
+    };
+    set_or_remove(SERVER_IP_KEY, profile.as_ref().map(|p| &p.server_ip));
+    set_or_remove(SERVER_PORT_KEY, profile.as_ref().map(|p| &p.server_port));
+    set_or_remove(
+        SESSION_TOKEN_KEY,
+        profile.as_ref().and_then(|p| p.session_token.as_ref()),
+    );
+    set_or_remove(
+        REFRESH_TOKEN_KEY,
+        profile.as_ref().and_then(|p| p.refresh_token.as_ref()),
+    );
+    set_or_remove(
+        INSTALL_DIR_KEY,
+        profile.as_ref().and_then(|p| p.install_dir.as_ref()),
+    );
+    profile
+}
+
+/// Makes `server` the active server at login, reusing its profile (and
+/// install folder) if it has one.
+pub fn activate_for_login(server: &ServerAddress) {
+    let server_ip = server.to_string();
+    if activate_profile(Some(&server_ip)).is_none() {
+        set_local_storage_item(SERVER_IP_KEY, &server_ip);
+        remove_local_storage_item(SESSION_TOKEN_KEY);
+        remove_local_storage_item(REFRESH_TOKEN_KEY);
+        remove_local_storage_item(INSTALL_DIR_KEY);
+    }
+    set_local_storage_item(SERVER_PORT_KEY, &server.port().to_string());
+    save_active_profile();
+}
+
+/// Drops a saved server. Forgetting the active one also clears the live
+/// keys, so it isn't saved again on the next switch.
+pub fn forget_profile(server_ip: &str) {
+    let mut profiles = load_profiles();
+    profiles.retain(|p| p.server_ip != server_ip);
+    store_profiles(&profiles);
+    remove_local_storage_item(&library_cache_key(server_ip));
+    if get_local_storage_item(SERVER_IP_KEY).as_deref() == Some(server_ip) {
+        for key in [
+            SERVER_IP_KEY,
+            SERVER_PORT_KEY,
+            SESSION_TOKEN_KEY,
+            REFRESH_TOKEN_KEY,
+            INSTALL_DIR_KEY,
+        ] {
+            remove_local_storage_item(key);
+        }
+    }
+}
+
+pub fn rename_profile(server_ip: &str, name: &str) {
+    let name = name.trim();
+    if name.is_empty() {
+        return;
+    }
+    let mut profiles = load_profiles();
+    if let Some(profile) = profiles.iter_mut().find(|p| p.server_ip == server_ip) {
+        profile.name = name.to_string();
+        store_profiles(&profiles);
+    }
+}
+
+pub fn cached_theme(server_ip: &str) -> Option<Theme> {
+    load_profiles()
+        .into_iter()
+        .find(|p| p.server_ip == server_ip)
+        .and_then(|p| p.theme)
+}
+
+pub fn cache_theme(server_ip: &str, theme: &Theme) {
+    let mut profiles = load_profiles();
+    if let Some(profile) = profiles.iter_mut().find(|p| p.server_ip == server_ip) {
+        if profile.theme.as_ref() != Some(theme) {
+            profile.theme = Some(theme.clone());
+            store_profiles(&profiles);
+        }
+    }
+}
+
+/// Key of the app list and playtime the Library last loaded from a server,
+/// kept apart from the profile since it can be large.
+pub fn library_cache_key(server_ip: &str) -> String {
+    format!("{LIBRARY_CACHE_PREFIX}{server_ip}")
+}
+
+/// Display name for an app's source server (its `server_key`): the profile
+/// name if there is one, else the host.
+pub fn server_label(source: &str) -> String {
+    load_profiles()
+        .into_iter()
+        .find(|p| server_key(&p.server_ip, &p.server_port) == source)
+        .map(|p| p.name)
+        .unwrap_or_else(|| default_name(source))
+}
+
+fn default_name(server_ip: &str) -> String {
+    ServerAddress::parse(server_ip, "")
+        .map(|server| server.host().to_string())
+        .unwrap_or_else(|_| server_ip.to_string())
+}
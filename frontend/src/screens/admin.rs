@@ -1,4 +1,4 @@
-﻿use wasm_bindgen_futures::spawn_local;
+use wasm_bindgen_futures::spawn_local;
 use web_sys::{HtmlInputElement, HtmlTextAreaElement};
 use yew::prelude::*;
 
@@ -6,7 +6,7 @@ use crate::api::{get_json, send_json};
 use crate::app::AppState;
 use crate::components::Button;
 use crate::confirm::{use_confirm, ConfirmRequest};
-use crate::net::build_http_url;
+use crate::net::{build_http_url, DEFAULT_PORT};
 use crate::toast::{use_toast, ToastVariant};
 use js_sys::{Function, Reflect};
 use wasm_bindgen::prelude::JsValue;
@@ -16,6 +16,9 @@ use wasm_bindgen::prelude::*;
 extern "C" {
     #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"])]
     async fn invoke(cmd: &str, args: JsValue) -> JsValue;
+
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"], js_name = invoke, catch)]
+    async fn invoke_safe(cmd: &str, args: JsValue) -> Result<JsValue, JsValue>;
 }
 
 #[derive(Clone, PartialEq, serde::Deserialize)]
@@ -44,6 +47,42 @@ struct UploadStageEvent {
     stage: String,
 }
 
+#[derive(Clone, PartialEq, serde::Deserialize)]
+struct AppVersion {
+    id: String,
+    #[serde(default)]
+    version: String,
+    uploaded_at: i64,
+    size: i64,
+    #[serde(default)]
+    uploader_name: String,
+    current: bool,
+}
+
+#[derive(Clone, PartialEq, serde::Deserialize)]
+struct CrashReport {
+    id: String,
+    #[serde(default)]
+    username: String,
+    #[serde(default)]
+    app_version: String,
+    #[serde(default)]
+    profile: Option<String>,
+    #[serde(default)]
+    exit_code: Option<i32>,
+    #[serde(default)]
+    signal: Option<i32>,
+    #[serde(default)]
+    duration_seconds: i64,
+    #[serde(default)]
+    os: String,
+    #[serde(default)]
+    client_version: String,
+    #[serde(default)]
+    output: String,
+    created_at: i64,
+}
+
 #[function_component(AdminScreen)]
 pub fn admin_screen() -> Html {
     let app_state = use_context::<UseStateHandle<AppState>>()
@@ -52,7 +91,7 @@ pub fn admin_screen() -> Html {
     let server_port = app_state
         .server_port
         .clone()
-        .unwrap_or_else(|| "2121".to_string());
+        .unwrap_or_else(|| DEFAULT_PORT.to_string());
     let token = app_state.session_token.clone().unwrap_or_default();
 
     let sessions = use_state(|| None::<i64>);
@@ -78,6 +117,7 @@ pub fn admin_screen() -> Html {
     let upload_progress = use_state(|| 0.0);
     let upload_current_id = use_state(String::new);
     let upload_stage = use_state(|| "idle".to_string());
+    let cancelling_upload = use_state(|| false);
     let manage_apps = use_state(Vec::<AppInfo>::new);
     let manage_loading = use_state(|| false);
     let manage_error = use_state(|| None::<String>);
@@ -85,6 +125,16 @@ pub fn admin_screen() -> Html {
     let manage_config = use_state(String::new);
     let manage_app_id = use_state(String::new);
     let manage_saving = use_state(|| false);
+    let versions_open = use_state(|| false);
+    let versions_app_id = use_state(String::new);
+    let versions = use_state(Vec::<AppVersion>::new);
+    let versions_loading = use_state(|| false);
+    let versions_busy = use_state(|| false);
+    let crashes_open = use_state(|| false);
+    let crashes_app_id = use_state(String::new);
+    let crashes = use_state(Vec::<CrashReport>::new);
+    let crashes_loading = use_state(|| false);
+    let crash_expanded = use_state(|| None::<String>);
 
     {
         let sessions = sessions.clone();
@@ -645,30 +695,56 @@ pub fn admin_screen() -> Html {
                     }
                 }))
                 .unwrap_or(JsValue::NULL);
-                let result = invoke("upload_app", payload).await;
-                if result.is_null() || result.is_undefined() {
-                    toast.toast("App uploaded.", ToastVariant::Success, Some(2500));
-                    upload_progress.set(100.0);
-                    upload_stage.set("done".to_string());
-                } else if let Ok(msg) = serde_wasm_bindgen::from_value::<String>(result.clone()) {
-                    if msg.is_empty() {
+                match invoke_safe("upload_app", payload).await {
+                    Ok(_) => {
                         toast.toast("App uploaded.", ToastVariant::Success, Some(2500));
                         upload_progress.set(100.0);
                         upload_stage.set("done".to_string());
-                    } else {
-                        toast.toast(msg, ToastVariant::Error, Some(3000));
-                        upload_stage.set("error".to_string());
                     }
-                } else {
-                    toast.toast("App uploaded.", ToastVariant::Success, Some(2500));
-                    upload_progress.set(100.0);
-                    upload_stage.set("done".to_string());
+                    Err(err) => {
+                        let msg = err
+                            .as_string()
+                            .unwrap_or_else(|| "Upload failed.".to_string());
+                        if msg == "Upload cancelled." {
+                            toast.toast(msg, ToastVariant::Info, Some(2500));
+                            upload_stage.set("cancelled".to_string());
+                        } else {
+                            toast.toast(msg, ToastVariant::Error, Some(3000));
+                            upload_stage.set("error".to_string());
+                        }
+                    }
                 }
                 uploading.set(false);
             });
         })
     };
 
+    let on_cancel_upload = {
+        let upload_current_id = upload_current_id.clone();
+        let cancelling_upload = cancelling_upload.clone();
+        let toast = toast.clone();
+        Callback::from(move |_| {
+            let id = (*upload_current_id).clone();
+            if id.is_empty() {
+                return;
+            }
+            cancelling_upload.set(true);
+            let cancelling_upload = cancelling_upload.clone();
+            let toast = toast.clone();
+            spawn_local(async move {
+                let payload = serde_wasm_bindgen::to_value(&serde_json::json!({ "id": id }))
+                    .unwrap_or(JsValue::NULL);
+                if let Err(err) = invoke_safe("cancel_upload", payload).await {
+                    let msg = err
+                        .as_string()
+                        .unwrap_or_else(|| "Failed to cancel upload.".to_string());
+                    toast.toast(msg, ToastVariant::Warning, Some(3500));
+                }
+                cancelling_upload.set(false);
+            });
+        })
+    };
+
     let on_open_manage_config = {
         let server_ip = server_ip.clone();
         let server_port = server_port.clone();
@@ -767,6 +843,265 @@ pub fn admin_screen() -> Html {
         })
     };
 
+    let on_open_versions = {
+        let server_ip = server_ip.clone();
+        let server_port = server_port.clone();
+        let token = token.clone();
+        let versions_open = versions_open.clone();
+        let versions_app_id = versions_app_id.clone();
+        let versions = versions.clone();
+        let versions_loading = versions_loading.clone();
+        let toast = toast.clone();
+        Callback::from(move |app_id: String| {
+            if server_ip.is_empty() || server_port.is_empty() || token.is_empty() {
+                toast.toast(
+                    "Missing server address or token.",
+                    ToastVariant::Warning,
+                    Some(2500),
+                );
+                return;
+            }
+            versions_open.set(true);
+            versions_app_id.set(app_id.clone());
+            versions.set(Vec::new());
+            versions_loading.set(true);
+            let server_ip = server_ip.clone();
+            let server_port = server_port.clone();
+            let token = token.clone();
+            let versions = versions.clone();
+            let versions_loading = versions_loading.clone();
+            let toast = toast.clone();
+            spawn_local(async move {
+                match fetch_app_versions(&server_ip, &server_port, &token, &app_id).await {
+                    Ok(list) => versions.set(list),
+                    Err(msg) => toast.toast(msg, ToastVariant::Error, Some(3000)),
+                }
+                versions_loading.set(false);
+            });
+        })
+    };
+
+    let on_promote_version = {
+        let server_ip = server_ip.clone();
+        let server_port = server_port.clone();
+        let token = token.clone();
+        let versions_app_id = versions_app_id.clone();
+        let versions = versions.clone();
+        let versions_busy = versions_busy.clone();
+        let toast = toast.clone();
+        Callback::from(move |vid: String| {
+            let app_id = versions_app_id.as_str().to_string();
+            versions_busy.set(true);
+            let server_ip = server_ip.clone();
+            let server_port = server_port.clone();
+            let token = token.clone();
+            let versions = versions.clone();
+            let versions_busy = versions_busy.clone();
+            let toast = toast.clone();
+            spawn_local(async move {
+                let url = build_http_url(
+                    &server_ip,
+                    &server_port,
+                    &format!("admin/apps/{}/versions/{}/promote", app_id, vid),
+                );
+                match send_json("POST", &url, Some(&token), None).await {
+                    Ok(resp) if resp.ok() => {
+                        toast.toast("Version is now current.", ToastVariant::Success, Some(2500));
+                        if let Ok(list) =
+                            fetch_app_versions(&server_ip, &server_port, &token, &app_id).await
+                        {
+                            versions.set(list);
+                        }
+                    }
+                    Ok(resp) => {
+                        toast.toast(
+                            format!("Promote failed (HTTP {}).", resp.status()),
+                            ToastVariant::Error,
+                            Some(3000),
+                        );
+                    }
+                    Err(msg) => toast.toast(msg, ToastVariant::Error, Some(3000)),
+                }
+                versions_busy.set(false);
+            });
+        })
+    };
+
+    let on_delete_version = {
+        let server_ip = server_ip.clone();
+        let server_port = server_port.clone();
+        let token = token.clone();
+        let confirm = confirm.clone();
+        let versions_app_id = versions_app_id.clone();
+        let versions = versions.clone();
+        let toast = toast.clone();
+        Callback::from(move |vid: String| {
+            let app_id = versions_app_id.as_str().to_string();
+            let server_ip = server_ip.clone();
+            let server_port = server_port.clone();
+            let token = token.clone();
+            let versions = versions.clone();
+            let toast = toast.clone();
+            confirm.confirm(ConfirmRequest {
+                title: "Delete version?".to_string(),
+                message: format!(
+                    "This will permanently delete version \"{vid}\" of \"{app_id}\" from the backend."
+                ),
+                confirm_label: "Delete".to_string(),
+                cancel_label: "Cancel".to_string(),
+                on_confirm: Callback::from(move |_| {
+                    let server_ip = server_ip.clone();
+                    let server_port = server_port.clone();
+                    let token = token.clone();
+                    let versions = versions.clone();
+                    let toast = toast.clone();
+                    let app_id = app_id.clone();
+                    let vid = vid.clone();
+                    spawn_local(async move {
+                        let url = build_http_url(
+                            &server_ip,
+                            &server_port,
+                            &format!("admin/apps/{}/versions/{}", app_id, vid),
+                        );
+                        match send_json("DELETE", &url, Some(&token), None).await {
+                            Ok(resp) if resp.ok() => {
+                                toast.toast("Version deleted.", ToastVariant::Success, Some(2500));
+                                let mut next = (*versions).clone();
+                                next.retain(|version| version.id != vid);
+                                versions.set(next);
+                            }
+                            Ok(resp) => {
+                                toast.toast(
+                                    format!("Delete failed (HTTP {}).", resp.status()),
+                                    ToastVariant::Error,
+                                    Some(3000),
+                                );
+                            }
+                            Err(msg) => toast.toast(msg, ToastVariant::Error, Some(3000)),
+                        }
+                    });
+                }),
+            });
+        })
+    };
+
+    let on_close_versions = {
+        let versions_open = versions_open.clone();
+        Callback::from(move |_| versions_open.set(false))
+    };
+
+    let on_open_crashes = {
+        let server_ip = server_ip.clone();
+        let server_port = server_port.clone();
+        let token = token.clone();
+        let crashes_open = crashes_open.clone();
+        let crashes_app_id = crashes_app_id.clone();
+        let crashes = crashes.clone();
+        let crashes_loading = crashes_loading.clone();
+        let crash_expanded = crash_expanded.clone();
+        let toast = toast.clone();
+        Callback::from(move |app_id: String| {
+            if server_ip.is_empty() || server_port.is_empty() || token.is_empty() {
+                toast.toast(
+                    "Missing server address or token.",
+                    ToastVariant::Warning,
+                    Some(2500),
+                );
+                return;
+            }
+            crashes_open.set(true);
+            crashes_app_id.set(app_id.clone());
+            crashes.set(Vec::new());
+            crash_expanded.set(None);
+            crashes_loading.set(true);
+            let server_ip = server_ip.clone();
+            let server_port = server_port.clone();
+            let token = token.clone();
+            let crashes = crashes.clone();
+            let crashes_loading = crashes_loading.clone();
+            let toast = toast.clone();
+            spawn_local(async move {
+                match fetch_crash_reports(&server_ip, &server_port, &token, &app_id).await {
+                    Ok(list) => crashes.set(list),
+                    Err(msg) => toast.toast(msg, ToastVariant::Error, Some(3000)),
+                }
+                crashes_loading.set(false);
+            });
+        })
+    };
+
+    let on_clear_crashes = {
+        let server_ip = server_ip.clone();
+        let server_port = server_port.clone();
+        let token = token.clone();
+        let crashes_app_id = crashes_app_id.clone();
+        let crashes = crashes.clone();
+        let confirm = confirm.clone();
+        let toast = toast.clone();
+        Callback::from(move |_| {
+            let app_id = crashes_app_id.as_str().to_string();
+            let server_ip = server_ip.clone();
+            let server_port = server_port.clone();
+            let token = token.clone();
+            let crashes = crashes.clone();
+            let toast = toast.clone();
+            confirm.confirm(ConfirmRequest {
+                title: "Clear crash reports?".to_string(),
+                message: format!(
+                    "This will permanently delete all crash reports for \"{app_id}\"."
+                ),
+                confirm_label: "Clear reports".to_string(),
+                cancel_label: "Cancel".to_string(),
+                on_confirm: Callback::from(move |_| {
+                    let server_ip = server_ip.clone();
+                    let server_port = server_port.clone();
+                    let token = token.clone();
+                    let app_id = app_id.clone();
+                    let crashes = crashes.clone();
+                    let toast = toast.clone();
+                    spawn_local(async move {
+                        let url = build_http_url(
+                            &server_ip,
+                            &server_port,
+                            &format!("admin/apps/{}/crash-reports", app_id),
+                        );
+                        match send_json("DELETE", &url, Some(&token), None).await {
+                            Ok(resp) if resp.ok() => {
+                                toast.toast(
+                                    "Crash reports cleared.",
+                                    ToastVariant::Success,
+                                    Some(2500),
+                                );
+                                crashes.set(Vec::new());
+                            }
+                            _ => toast.toast(
+                                "Failed to clear crash reports.",
+                                ToastVariant::Error,
+                                Some(3000),
+                            ),
+                        }
+                    });
+                }),
+            });
+        })
+    };
+
+    let on_toggle_crash = {
+        let crash_expanded = crash_expanded.clone();
+        Callback::from(move |id: String| {
+            if crash_expanded.as_deref() == Some(id.as_str()) {
+                crash_expanded.set(None);
+            } else {
+                crash_expanded.set(Some(id));
+            }
+        })
+    };
+
+    let on_close_crashes = {
+        let crashes_open = crashes_open.clone();
+        Callback::from(move |_| crashes_open.set(false))
+    };
+
     let on_delete_app = {
         let server_ip = server_ip.clone();
         let server_port = server_port.clone();
@@ -1019,6 +1354,13 @@ pub fn admin_screen() -> Html {
                             { if *uploading { "Uploading..." } else { "Upload App" } }
                         </Button>
                         if *uploading {
+                            <Button
+                                class={Some("ml-3 border border-rose-400/60 bg-rose-500/20 text-rose-100 hover:bg-rose-500/30".to_string())}
+                                onclick={on_cancel_upload}
+                                disabled={*cancelling_upload || upload_stage.as_str() == "cancelled"}
+                            >
+                                { if *cancelling_upload { "Cancelling..." } else { "Cancel" } }
+                            </Button>
                             <span class="ml-3 text-sm text-secondary/70">
                                 { format!("{}% - {}", upload_progress.round() as i64, upload_stage.as_str()) }
                             </span>
@@ -1028,7 +1370,7 @@ pub fn admin_screen() -> Html {
             } else {
                 <div class="mt-8 rounded-2xl border border-ink/50 bg-inkLight p-6">
                     <p class="text-xs uppercase tracking-wide text-accent/80">{ "Manage Apps" }</p>
-                    <p class="mt-2 text-sm text-secondary/70">{ "Edit metadata or delete apps from the backend." }</p>
+                    <p class="mt-2 text-sm text-secondary/70">{ "Edit metadata, roll back to an earlier upload or delete apps from the backend." }</p>
                     if *manage_loading {
                         <p class="mt-4 text-sm text-secondary/70">{ "Loading apps..." }</p>
                     } else if let Some(err) = (*manage_error).clone() {
@@ -1040,7 +1382,11 @@ pub fn admin_screen() -> Html {
                             { for manage_apps.iter().cloned().map(|app| {
                                 let on_open_manage_config = on_open_manage_config.clone();
                                 let on_delete_app = on_delete_app.clone();
+                                let on_open_versions = on_open_versions.clone();
+                                let on_open_crashes = on_open_crashes.clone();
                                 let app_id = app.id.clone();
+                                let versions_id = app.id.clone();
+                                let crashes_id = app.id.clone();
                                 html! {
                                     <div class="flex flex-wrap items-center justify-between gap-3 rounded-xl border border-ink/50 bg-ink/40 px-4 py-3">
                                         <div>
@@ -1054,6 +1400,18 @@ pub fn admin_screen() -> Html {
                                             >
                                                 { "Edit config" }
                                             </Button>
+                                            <Button
+                                                class={Some("border border-ink/50 bg-ink/40 text-secondary hover:bg-ink/50".to_string())}
+                                                onclick={Callback::from(move |_| on_open_versions.emit(versions_id.clone()))}
+                                            >
+                                                { "Versions" }
+                                            </Button>
+                                            <Button
+                                                class={Some("border border-ink/50 bg-ink/40 text-secondary hover:bg-ink/50".to_string())}
+                                                onclick={Callback::from(move |_| on_open_crashes.emit(crashes_id.clone()))}
+                                            >
+                                                { "Crashes" }
+                                            </Button>
                                             <Button
                                                 class={Some("border border-rose-400/60 bg-rose-500/20 text-rose-100 hover:bg-rose-500/30".to_string())}
                                                 onclick={Callback::from(move |_| on_delete_app.emit(app.id.clone()))}
@@ -1169,6 +1527,165 @@ pub fn admin_screen() -> Html {
                     </div>
                 </div>
             }
+            if *versions_open {
+                <div class="fixed inset-0 z-50 flex items-center justify-center">
+                    <div class="absolute inset-0 bg-ink" onclick={on_close_versions.clone()} />
+                    <div class="relative w-[min(94vw,52rem)] max-h-[90vh] overflow-hidden rounded-2xl border border-ink/50 bg-inkLight shadow-2xl">
+                        <div class="flex items-center justify-between border-b border-ink/40 px-6 py-4">
+                            <div>
+                                <h2 class="text-lg font-semibold text-secondary">{ "App versions" }</h2>
+                                <p class="text-xs text-accent">{ versions_app_id.as_str() }</p>
+                            </div>
+                            <Button
+                                class={Some("border border-ink/50 bg-ink/40 text-secondary hover:bg-ink/50".to_string())}
+                                onclick={on_close_versions.clone()}
+                            >
+                                { "Close" }
+                            </Button>
+                        </div>
+                        <div class="max-h-[calc(90vh-5rem)] overflow-y-auto px-6 py-5 scrollbar-thin">
+                            if *versions_loading {
+                                <p class="text-sm text-secondary/70">{ "Loading versions..." }</p>
+                            } else if versions.is_empty() {
+                                <p class="text-sm text-secondary/70">{ "No uploads stored for this app." }</p>
+                            } else {
+                                <div class="flex flex-col gap-3">
+                                    { for versions.iter().map(|version| {
+                                        let on_promote_version = on_promote_version.clone();
+                                        let on_delete_version = on_delete_version.clone();
+                                        let promote_id = version.id.clone();
+                                        let delete_id = version.id.clone();
+                                        let uploader = if version.uploader_name.is_empty() {
+                                            "unknown".to_string()
+                                        } else {
+                                            version.uploader_name.clone()
+                                        };
+                                        html! {
+                                            <div key={version.id.clone()} class="flex flex-wrap items-center justify-between gap-3 rounded-xl border border-ink/50 bg-ink/40 px-4 py-3">
+                                                <div>
+                                                    <p class="text-sm font-semibold">
+                                                        { format!("Version {}", if version.version.is_empty() { "-" } else { &version.version }) }
+                                                        if version.current {
+                                                            <span class="ml-2 rounded-full border border-primary/60 bg-primary/30 px-2 py-0.5 text-xs">{ "Current" }</span>
+                                                        }
+                                                    </p>
+                                                    <p class="text-xs text-secondary/70">
+                                                        { format!("{} - {} - by {}", format_timestamp(version.uploaded_at), format_size(version.size), uploader) }
+                                                    </p>
+                                                </div>
+                                                if !version.current {
+                                                    <div class="flex items-center gap-2">
+                                                        <Button
+                                                            class={Some("border border-accent/50 bg-accent/20 text-secondary hover:bg-accent/30".to_string())}
+                                                            onclick={Callback::from(move |_| on_promote_version.emit(promote_id.clone()))}
+                                                            disabled={*versions_busy}
+                                                        >
+                                                            { "Make current" }
+                                                        </Button>
+                                                        <Button
+                                                            class={Some("border border-rose-400/60 bg-rose-500/20 text-rose-100 hover:bg-rose-500/30".to_string())}
+                                                            onclick={Callback::from(move |_| on_delete_version.emit(delete_id.clone()))}
+                                                            disabled={*versions_busy}
+                                                        >
+                                                            { "Delete" }
+                                                        </Button>
+                                                    </div>
+                                                }
+                                            </div>
+                                        }
+                                    }) }
+                                </div>
+                            }
+                        </div>
+                    </div>
+                </div>
+            }
+            if *crashes_open {
+                <div class="fixed inset-0 z-50 flex items-center justify-center">
+                    <div class="absolute inset-0 bg-ink" onclick={on_close_crashes.clone()} />
+                    <div class="relative w-[min(94vw,60rem)] max-h-[90vh] overflow-hidden rounded-2xl border border-ink/50 bg-inkLight shadow-2xl">
+                        <div class="flex items-center justify-between border-b border-ink/40 px-6 py-4">
+                            <div>
+                                <h2 class="text-lg font-semibold text-secondary">{ "Crash reports" }</h2>
+                                <p class="text-xs text-accent">{ crashes_app_id.as_str() }</p>
+                            </div>
+                            <div class="flex items-center gap-2">
+                                if !crashes.is_empty() {
+                                    <Button
+                                        class={Some("border border-rose-400/60 bg-rose-500/20 text-rose-100 hover:bg-rose-500/30".to_string())}
+                                        onclick={on_clear_crashes.clone()}
+                                    >
+                                        { "Clear all" }
+                                    </Button>
+                                }
+                                <Button
+                                    class={Some("border border-ink/50 bg-ink/40 text-secondary hover:bg-ink/50".to_string())}
+                                    onclick={on_close_crashes.clone()}
+                                >
+                                    { "Close" }
+                                </Button>
+                            </div>
+                        </div>
+                        <div class="max-h-[calc(90vh-5rem)] overflow-y-auto px-6 py-5 scrollbar-thin">
+                            if *crashes_loading {
+                                <p class="text-sm text-secondary/70">{ "Loading crash reports..." }</p>
+                            } else if crashes.is_empty() {
+                                <p class="text-sm text-secondary/70">{ "No crashes reported for this app." }</p>
+                            } else {
+                                <div class="mb-4 flex flex-wrap gap-2">
+                                    { for crash_counts_by_version(&crashes).into_iter().map(|(version, count)| html! {
+                                        <span class="rounded-full border border-ink/50 bg-ink/40 px-3 py-1 text-xs text-secondary/80">
+                                            { format!("{}: {} {}", version, count, if count == 1 { "crash" } else { "crashes" }) }
+                                        </span>
+                                    }) }
+                                </div>
+                                <div class="flex flex-col gap-3">
+                                    { for crashes.iter().map(|report| {
+                                        let on_toggle_crash = on_toggle_crash.clone();
+                                        let toggle_id = report.id.clone();
+                                        let expanded = crash_expanded.as_deref() == Some(report.id.as_str());
+                                        let user = if report.username.is_empty() { "unknown" } else { report.username.as_str() };
+                                        let mut details = vec![
+                                            format_timestamp(report.created_at),
+                                            format!("by {}", user),
+                                            format!("after {}s", report.duration_seconds),
+                                        ];
+                                        if !report.os.is_empty() {
+                                            details.push(report.os.clone());
+                                        }
+                                        if !report.client_version.is_empty() {
+                                            details.push(format!("client {}", report.client_version));
+                                        }
+                                        html! {
+                                            <div key={report.id.clone()} class="rounded-xl border border-ink/50 bg-ink/40 px-4 py-3">
+                                                <div class="flex flex-wrap items-center justify-between gap-3">
+                                                    <div>
+                                                        <p class="text-sm font-semibold">
+                                                            { format!("Version {} - {}", if report.app_version.is_empty() { "-" } else { &report.app_version }, describe_crash(report)) }
+                                                        </p>
+                                                        <p class="text-xs text-secondary/70">{ details.join(" - ") }</p>
+                                                    </div>
+                                                    <Button
+                                                        class={Some("border border-ink/50 bg-ink/40 text-secondary hover:bg-ink/50".to_string())}
+                                                        onclick={Callback::from(move |_| on_toggle_crash.emit(toggle_id.clone()))}
+                                                    >
+                                                        { if expanded { "Hide output" } else { "Show output" } }
+                                                    </Button>
+                                                </div>
+                                                if expanded {
+                                                    <pre class="mt-3 max-h-80 overflow-auto whitespace-pre-wrap rounded-lg border border-ink/50 bg-ink/60 p-3 text-xs text-secondary/80 scrollbar-thin">
+                                                        { if report.output.is_empty() { "No output was captured." } else { report.output.as_str() } }
+                                                    </pre>
+                                                }
+                                            </div>
+                                        }
+                                    }) }
+                                </div>
+                            }
+                        </div>
+                    </div>
+                </div>
+            }
         </div>
     }
 }
@@ -1209,6 +1726,61 @@ async fn clear_sessions(server_ip: &str, server_port: &str, token: &str) -> Resu
     Ok(())
 }
 
+async fn fetch_app_versions(
+    server_ip: &str,
+    server_port: &str,
+    token: &str,
+    app_id: &str,
+) -> Result<Vec<AppVersion>, String> {
+    let url = build_http_url(server_ip, server_port, &format!("apps/{}/versions", app_id));
+    get_json(&url, Some(token)).await
+}
+
+async fn fetch_crash_reports(
+    server_ip: &str,
+    server_port: &str,
+    token: &str,
+    app_id: &str,
+) -> Result<Vec<CrashReport>, String> {
+    let url = build_http_url(
+        server_ip,
+        server_port,
+        &format!("admin/apps/{}/crash-reports", app_id),
+    );
+    get_json(&url, Some(token)).await
+}
+
+fn describe_crash(report: &CrashReport) -> String {
+    let mut text = match (report.exit_code, report.signal) {
+        (_, Some(signal)) => format!("signal {}", signal),
+        (Some(code), None) => format!("exit code {}", code),
+        (None, None) => "unknown exit".to_string(),
+    };
+    if let Some(profile) = report.profile.as_deref().filter(|p| !p.is_empty()) {
+        text.push_str(&format!(" ({})", profile));
+    }
+    text
+}
+
+/// Crash counts per app version, most crashes first, so a broken build
+/// stands out.
+fn crash_counts_by_version(reports: &[CrashReport]) -> Vec<(String, usize)> {
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    for report in reports {
+        let version = if report.app_version.is_empty() {
+            "Unknown version".to_string()
+        } else {
+            format!("Version {}", report.app_version)
+        };
+        match counts.iter_mut().find(|(existing, _)| *existing == version) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((version, 1)),
+        }
+    }
+    counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    counts
+}
+
 #[derive(Clone, PartialEq, serde::Deserialize, serde::Serialize)]
 struct RawConfigPayload {
     content: String,
@@ -1227,3 +1799,35 @@ fn on_text_input(form: UseStateHandle<String>) -> Callback<InputEvent> {
         form.set(input.value());
     })
 }
+
+fn format_size(size: i64) -> String {
+    if size <= 0 {
+        return "-".to_string();
+    }
+    let units = ["B", "KB", "MB", "GB"];
+    let mut value = size as f64;
+    let mut unit = units[0];
+    for next in units.iter().skip(1) {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = next;
+    }
+    format!("{:.1} {}", value, unit)
+}
+
+fn format_timestamp(unix_seconds: i64) -> String {
+    if unix_seconds <= 0 {
+        return "-".to_string();
+    }
+    let date = js_sys::Date::new(&JsValue::from_f64(unix_seconds as f64 * 1000.0));
+    format!(
+        "{}-{:02}-{:02} {:02}:{:02}",
+        date.get_full_year(),
+        date.get_month() + 1,
+        date.get_date(),
+        date.get_hours(),
+        date.get_minutes()
+    )
+}
@@ -1,14 +1,15 @@
 use js_sys::{ArrayBuffer, Uint8Array};
 use wasm_bindgen::{closure::Closure, JsCast, JsValue};
 use wasm_bindgen_futures::spawn_local;
-use web_sys::{BinaryType, Blob, MessageEvent, WebSocket};
+use web_sys::{BinaryType, Blob, MessageEvent};
 use yew::prelude::*;
 
 use crate::api::{get_json, send_json};
-use crate::app::AppState;
+use crate::app::{recheck_session, AppState};
+use crate::auth::open_socket;
 use crate::components::Button;
 use crate::confirm::{use_confirm, ConfirmRequest};
-use crate::net::{build_http_url, build_ws_url};
+use crate::net::{build_http_url, DEFAULT_PORT};
 use crate::toast::{use_toast, ToastVariant};
 
 #[derive(Properties, PartialEq)]
@@ -25,7 +26,7 @@ pub fn chat_screen(props: &ChatScreenProps) -> Html {
     let server_port = app_state
         .server_port
         .clone()
-        .unwrap_or_else(|| "2121".to_string());
+        .unwrap_or_else(|| DEFAULT_PORT.to_string());
     let token = app_state.session_token.clone().unwrap_or_default();
     let is_admin = app_state.user.as_ref().map(|u| u.is_admin).unwrap_or(false);
 
@@ -109,6 +110,8 @@ pub fn chat_screen(props: &ChatScreenProps) -> Html {
         let token = token.clone();
         let active_ref = active_ref.clone();
         let on_unread = props.on_unread.clone();
+        let app_state = app_state.clone();
+        let toast = toast.clone();
 
         use_effect_with(
             (
@@ -128,15 +131,20 @@ pub fn chat_screen(props: &ChatScreenProps) -> Html {
                     return Box::new(|| ()) as Box<dyn FnOnce()>;
                 }
 
-                let ws_url =
-                    build_ws_url(&server_ip, &server_port, &format!("chat/ws?token={token}"));
-                let ws = match WebSocket::new(&ws_url) {
-                    Ok(ws) => ws,
-                    Err(_) => {
-                        error.set(Some("Failed to open chat socket.".to_string()));
-                        return Box::new(|| ()) as Box<dyn FnOnce()>;
-                    }
+                let on_unauthorized = {
+                    let app_state = app_state.clone();
+                    let toast = toast.clone();
+                    Callback::from(move |_| recheck_session(app_state.clone(), toast.clone()))
                 };
+                let ws =
+                    match open_socket(&server_ip, &server_port, "chat/ws", &token, on_unauthorized)
+                    {
+                        Ok(ws) => ws,
+                        Err(_) => {
+                            error.set(Some("Failed to open chat socket.".to_string()));
+                            return Box::new(|| ()) as Box<dyn FnOnce()>;
+                        }
+                    };
                 ws.set_binary_type(BinaryType::Arraybuffer);
 
                 let onmessage = {
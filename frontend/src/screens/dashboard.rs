@@ -1,14 +1,16 @@
 use yew::prelude::*;
 
 use crate::api::get_json;
-use crate::app::AppState;
-use crate::auth::handle_logout;
+use crate::app::{recheck_session, AppState};
+use crate::auth::{handle_logout, switch_server};
 use crate::components::Button;
-use crate::net::build_http_url;
+use crate::net::{build_http_url, DEFAULT_PORT};
+use crate::profiles::{load_profiles, rename_profile};
 use crate::screens::{
     admin::AdminScreen, chat::ChatScreen, downloads::DownloadsScreen, library::LibraryScreen,
     settings::SettingsScreen, social::SocialScreen,
 };
+use crate::toast::use_toast;
 use wasm_bindgen_futures::spawn_local;
 
 type AppStateHandle = UseStateHandle<AppState>;
@@ -40,32 +42,99 @@ pub fn dashboard() -> Html {
     let active_tab = use_state(|| Tab::Library);
     let unread_chat = use_state(|| 0usize);
     let chat_enabled = use_state(|| true);
+    let profiles = use_state(load_profiles);
+    let rename_draft = use_state(|| None::<String>);
+    let current_server = app_state.server_ip.clone().unwrap_or_default();
+    let toast = use_toast();
 
     let on_logout = {
         let app_state = app_state.clone();
         Callback::from(move |_| handle_logout(app_state.clone()))
     };
 
+    // An empty value is the "Add server" entry, which goes to the login
+    // screen without logging out of this server.
+    let on_switch_server = {
+        let app_state = app_state.clone();
+        Callback::from(move |event: Event| {
+            let select: web_sys::HtmlSelectElement = event.target_unchecked_into();
+            let value = select.value();
+            switch_server(
+                app_state.clone(),
+                Some(value.as_str()).filter(|v| !v.is_empty()),
+            );
+        })
+    };
+
+    let on_start_rename = {
+        let rename_draft = rename_draft.clone();
+        let profiles = profiles.clone();
+        let current_server = current_server.clone();
+        Callback::from(move |_| {
+            let name = profiles
+                .iter()
+                .find(|p| p.server_ip == current_server)
+                .map(|p| p.name.clone())
+                .unwrap_or_default();
+            rename_draft.set(Some(name));
+        })
+    };
+
+    let on_rename_input = {
+        let rename_draft = rename_draft.clone();
+        Callback::from(move |event: InputEvent| {
+            let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+            rename_draft.set(Some(input.value()));
+        })
+    };
+
+    let on_save_rename = {
+        let rename_draft = rename_draft.clone();
+        let profiles = profiles.clone();
+        let current_server = current_server.clone();
+        Callback::from(move |_| {
+            if let Some(name) = (*rename_draft).clone() {
+                rename_profile(&current_server, &name);
+                profiles.set(load_profiles());
+            }
+            rename_draft.set(None);
+        })
+    };
+
+    let on_rename_keydown = {
+        let on_save_rename = on_save_rename.clone();
+        let rename_draft = rename_draft.clone();
+        Callback::from(move |event: KeyboardEvent| match event.key().as_str() {
+            "Enter" => on_save_rename.emit(()),
+            "Escape" => rename_draft.set(None),
+            _ => {}
+        })
+    };
+
     {
         let server_ip = app_state.server_ip.clone().unwrap_or_default();
         let server_port = app_state
             .server_port
             .clone()
-            .unwrap_or_else(|| "2121".to_string());
+            .unwrap_or_else(|| DEFAULT_PORT.to_string());
         let chat_enabled = chat_enabled.clone();
-        use_effect_with((server_ip.clone(), server_port.clone()), move |_| {
-            if server_ip.is_empty() || server_port.is_empty() {
-                chat_enabled.set(true);
-                return ();
-            }
-            let chat_enabled = chat_enabled.clone();
-            spawn_local(async move {
-                if let Ok(features) = fetch_features(&server_ip, &server_port).await {
-                    chat_enabled.set(features.chat_enabled);
+        let offline = app_state.offline;
+        use_effect_with(
+            (server_ip.clone(), server_port.clone(), offline),
+            move |_| {
+                if server_ip.is_empty() || server_port.is_empty() {
+                    chat_enabled.set(true);
+                    return ();
                 }
-            });
-            ()
-        });
+                let chat_enabled = chat_enabled.clone();
+                spawn_local(async move {
+                    if let Ok(features) = fetch_features(&server_ip, &server_port).await {
+                        chat_enabled.set(features.chat_enabled);
+                    }
+                });
+                ()
+            },
+        );
     }
 
     let set_tab = {
@@ -116,6 +185,11 @@ pub fn dashboard() -> Html {
         <span>{ "Downloads" }</span>
     };
 
+    let on_retry_connection = {
+        let app_state = app_state.clone();
+        Callback::from(move |_| recheck_session(app_state.clone(), toast.clone()))
+    };
+
     let on_unread = {
         let unread_chat = unread_chat.clone();
         Callback::from(move |count: usize| {
@@ -129,6 +203,52 @@ pub fn dashboard() -> Html {
             <div class="flex h-full">
                 <aside class="w-64 border-r border-ink/40 bg-inkLight/90 p-6 shadow-2xl">
                     <h2 class="text-6xl font-semibold">{ "Gaggle" }</h2>
+                    <div class="mt-6">
+                        <div class="text-xs uppercase tracking-wide text-accent/80">{ "Server" }</div>
+                        if let Some(draft) = (*rename_draft).clone() {
+                            <div class="mt-2 flex items-center gap-2">
+                                <input
+                                    class="min-w-0 flex-1 rounded border border-ink/50 bg-ink/50 px-3 py-2 text-sm text-secondary outline outline-1 outline-accent/50 focus:outline-none focus:ring-2 focus:ring-primary/40"
+                                    type="text"
+                                    value={draft}
+                                    oninput={on_rename_input}
+                                    onkeydown={on_rename_keydown}
+                                />
+                                <Button
+                                    class={Some("border border-primary/60 bg-primary/30 px-3 text-sm text-secondary hover:bg-primary/40".to_string())}
+                                    onclick={{
+                                        let on_save_rename = on_save_rename.clone();
+                                        Callback::from(move |_| on_save_rename.emit(()))
+                                    }}
+                                >
+                                    { "Save" }
+                                </Button>
+                            </div>
+                        } else {
+                            <div class="mt-2 flex items-center gap-2">
+                                <select
+                                    class="min-w-0 flex-1 rounded border border-ink/50 bg-ink/50 px-3 py-2 text-sm text-secondary outline outline-1 outline-accent/50 focus:outline-none focus:ring-2 focus:ring-primary/40"
+                                    onchange={on_switch_server}
+                                >
+                                    { for profiles.iter().map(|profile| html! {
+                                        <option
+                                            value={profile.server_ip.clone()}
+                                            selected={profile.server_ip == current_server}
+                                        >
+                                            { profile.name.clone() }
+                                        </option>
+                                    }) }
+                                    <option value="">{ "Add server..." }</option>
+                                </select>
+                                <Button
+                                    class={Some("border border-ink/50 bg-ink/40 px-3 text-sm text-secondary hover:bg-ink/50".to_string())}
+                                    onclick={on_start_rename}
+                                >
+                                    { "Rename" }
+                                </Button>
+                            </div>
+                        }
+                    </div>
                     <nav class="mt-8 flex flex-col gap-2 text-sm">
                         { tab_button(html! { "Library" }, Tab::Library) }
                         { tab_button(downloads_label, Tab::Downloads) }
@@ -144,6 +264,19 @@ pub fn dashboard() -> Html {
                 </aside>
                 <section class="flex-1 bg-ink/70 p-10 min-h-screen overflow-y-auto">
                     <div class="h-full min-h-0 rounded-3xl border border-ink/40 bg-ink/40 p-8 shadow-2xl backdrop-blur flex flex-col overflow-y-auto scrollbar-thin">
+                        if app_state.offline {
+                            <div class="mb-6 flex items-center justify-between gap-4 rounded-2xl border border-primary/70 bg-inkLight px-5 py-3 text-sm text-secondary/90">
+                                <span>
+                                    { "Offline: the server can't be reached. Installed apps can still be launched, and playtime is uploaded once the server is back." }
+                                </span>
+                                <Button
+                                    class={Some("border border-ink/50 bg-ink/40 px-3 text-sm text-secondary hover:bg-ink/50".to_string())}
+                                    onclick={on_retry_connection}
+                                >
+                                    { "Retry" }
+                                </Button>
+                            </div>
+                        }
                         <div class={if *active_tab == Tab::Library { "" } else { "hidden" }}>
                             <LibraryScreen />
                         </div>
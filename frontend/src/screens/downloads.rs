@@ -9,9 +9,13 @@ use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::spawn_local;
 use yew::prelude::*;
 
-use crate::auth::{get_local_storage_item, INSTALL_DIR_KEY, SESSION_TOKEN_KEY};
+use crate::auth::{
+    get_local_storage_item, stored_server, INSTALL_DIR_KEY, SESSION_TOKEN_KEY,
+};
 use crate::components::DownloadSpeedGraph;
 use crate::components::{Button, IndeterminateBar};
+use crate::net::server_key;
+use crate::profiles::server_label;
 use crate::toast::{use_toast, ToastVariant};
 
 #[wasm_bindgen]
@@ -60,7 +64,11 @@ fn now_secs() -> f64 {
 pub fn downloads_screen() -> Html {
     let downloads = use_state(|| HashMap::<String, DownloadView>::new());
     let install_dir = use_state(|| get_local_storage_item(INSTALL_DIR_KEY).unwrap_or_default());
-    let session_token = use_state(|| get_local_storage_item(SESSION_TOKEN_KEY).unwrap_or_default());
+    let current_server = match stored_server() {
+        (Some(server_ip), server_port) => server_key(&server_ip, &server_port.unwrap_or_default()),
+        (None, _) => String::new(),
+    };
+    let sources = use_state(HashMap::<String, String>::new);
     let toast = use_toast();
 
     let on_pause = {
@@ -83,12 +91,13 @@ pub fn downloads_screen() -> Html {
     let on_resume = {
         let downloads = downloads.clone();
         let install_dir = install_dir.clone();
-        let session_token = session_token.clone();
         let toast = toast.clone();
         Callback::from(move |id: String| {
             let downloads = downloads.clone();
             let install_dir = (*install_dir).clone();
-            let token = (*session_token).clone();
+            // Read at click time: the session may have been renewed since
+            // the download was paused.
+            let token = get_local_storage_item(SESSION_TOKEN_KEY).unwrap_or_default();
             let toast = toast.clone();
             spawn_local(async move {
                 if token.trim().is_empty() {
@@ -131,10 +140,12 @@ pub fn downloads_screen() -> Html {
     {
         let downloads = downloads.clone();
         let install_dir = install_dir.clone();
+        let sources = sources.clone();
         let toast = toast.clone();
         use_effect_with((), move |_| {
             let downloads = downloads.clone();
             let install_dir = install_dir.clone();
+            let sources = sources.clone();
             let toast = toast.clone();
             spawn_local(async move {
                 let mut dir = (*install_dir).clone();
@@ -158,7 +169,11 @@ pub fn downloads_screen() -> Html {
                     "request": { "destDir": dir }
                 }))
                 .unwrap_or(JsValue::NULL);
-                let initial = invoke("list_downloads", payload).await;
+                let initial = invoke("list_downloads", payload.clone()).await;
+                let app_sources = invoke("list_app_sources", payload).await;
+                if let Ok(map) = serde_wasm_bindgen::from_value(app_sources) {
+                    sources.set(map);
+                }
                 if let Ok(list) = serde_wasm_bindgen::from_value::<Vec<DownloadSnapshot>>(initial) {
                     let mut next = (*downloads).clone();
                     for snapshot in list {
@@ -338,6 +353,9 @@ pub fn downloads_screen() -> Html {
                                         <div>
                                             <p class="text-xs uppercase tracking-wide text-accent/80">{ "Download" }</p>
                                             <p class="mt-2 text-xl font-semibold">{ view.name.clone() }</p>
+                                            if let Some(source) = sources.get(id).filter(|source| **source != current_server) {
+                                                <p class="mt-1 text-xs text-accent">{ format!("From {}", server_label(source)) }</p>
+                                            }
                                             <p class="mt-1 text-sm text-secondary/70">
                                                 { format!("{} - {}", format_size(view.downloaded as i64), format_size(total as i64)) }
                                             </p>
@@ -1,5 +1,7 @@
-﻿use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
+use futures_util::StreamExt;
+use gloo_timers::future::IntervalStream;
 use js_sys::{Function, Reflect};
 use serde::Deserialize;
 use serde_json;
@@ -9,14 +11,17 @@ use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::{spawn_local, JsFuture};
 use yew::prelude::*;
 
-use crate::api::{get_json, send_json};
+use crate::api::{get_json, is_unreachable, send_json, send_request};
 use crate::app::AppState;
-use crate::auth::{get_local_storage_item, set_local_storage_item, INSTALL_DIR_KEY};
-use crate::components::Button;
+use crate::auth::{
+    get_local_storage_item, set_local_storage_item, INSTALL_DIR_KEY, PINNED_VERSIONS_KEY,
+};
+use crate::components::{Button, DownloadSpeedGraph};
 use crate::confirm::use_confirm;
 use crate::confirm::ConfirmRequest;
-use crate::net::build_http_url;
-use crate::toast::{use_toast, ToastVariant};
+use crate::net::{build_http_url, server_key, DEFAULT_PORT};
+use crate::profiles::library_cache_key;
+use crate::toast::{use_toast, ToastAction, ToastVariant};
 
 #[wasm_bindgen]
 extern "C" {
@@ -27,7 +32,7 @@ extern "C" {
     async fn invoke_safe(cmd: &str, args: JsValue) -> Result<JsValue, JsValue>;
 }
 
-#[derive(Clone, PartialEq, Deserialize)]
+#[derive(Clone, PartialEq, Deserialize, serde::Serialize)]
 struct AppInfo {
     id: String,
     name: String,
@@ -37,15 +42,197 @@ struct AppInfo {
     has_archive: bool,
     #[serde(default)]
     executable: Option<String>,
+    #[serde(default)]
+    launch_profiles: Vec<LaunchProfile>,
+    /// Set on mod packages; they're managed from their base app's Mods panel.
+    #[serde(default)]
+    base_app: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Deserialize, serde::Serialize)]
+struct LaunchProfile {
+    name: String,
+}
+
+/// A `--run` request from the command line or a desktop shortcut.
+#[derive(Clone, PartialEq, Deserialize)]
+struct CliLaunch {
+    id: String,
+    profile: Option<String>,
+}
+
+/// A run the client is tracking, from `list_running_apps`.
+#[derive(Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RunningApp {
+    id: String,
+    elapsed_seconds: u64,
+    stopping: bool,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+struct RunningView {
+    /// Local start time in seconds, for the elapsed counter.
+    started_at: f64,
+    stopping: bool,
+}
+
+/// Usage sample of a running app, from the `app_run_stats` event.
+#[derive(Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RunStats {
+    id: String,
+    cpu_percent: f64,
+    memory_bytes: u64,
+    threads: u64,
+}
+
+#[derive(Clone, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LaunchSettings {
+    #[serde(default)]
+    default_wrapper: String,
+    #[serde(default)]
+    apps: HashMap<String, AppLaunchSettings>,
+    #[serde(default)]
+    stop_grace_seconds: Option<u64>,
+}
+
+#[derive(Clone, PartialEq, Default, Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AppLaunchSettings {
+    #[serde(default)]
+    extra_args: String,
+    #[serde(default)]
+    env: BTreeMap<String, String>,
+    #[serde(default)]
+    wrapper: Option<String>,
+    #[serde(default)]
+    save_paths: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+struct LogEntry {
+    name: String,
+    size: i64,
+    modified: i64,
+}
+
+/// Mods installed for a base app, in load order, from `list_app_mods`.
+#[derive(Clone, PartialEq, Default, Deserialize)]
+struct ModOverview {
+    mods: Vec<InstalledMod>,
+    conflicts: Vec<ModConflict>,
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+struct InstalledMod {
+    id: String,
+    name: String,
+    version: String,
+    enabled: bool,
+    files: usize,
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+struct ModConflict {
+    path: String,
+    /// In load order; the last one wins.
+    mods: Vec<String>,
+}
+
+/// The cloud save as `check_cloud_save` and `app_save_conflict` report it.
+#[derive(Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CloudSaveInfo {
+    size: i64,
+    uploaded_at: i64,
+    #[serde(default)]
+    device: String,
+}
+
+#[derive(Deserialize)]
+struct CloudSaveStatus {
+    state: String,
+    cloud: Option<CloudSaveInfo>,
+}
+
+#[derive(Deserialize)]
+struct SaveConflictEvent {
+    id: String,
+    cloud: CloudSaveInfo,
+}
+
+/// A cloud save waiting for the player to pick a side, optionally holding
+/// the launch it interrupted.
+#[derive(Clone, PartialEq)]
+struct SavePrompt {
+    id: String,
+    conflict: bool,
+    cloud: CloudSaveInfo,
+    launch: Option<(String, String, String, Option<String>)>,
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SaveSnapshot {
+    name: String,
+    created_at: i64,
+    reason: String,
+    size: i64,
+    files: usize,
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+struct AppVersion {
+    id: String,
+    #[serde(default)]
+    version: String,
+    uploaded_at: i64,
+    size: i64,
+    current: bool,
 }
 
+/// A lifecycle hook from an app config, as listed by `inspect_app_hooks`.
 #[derive(Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HookSummary {
+    stage: String,
+    command: String,
+    #[serde(default)]
+    args: Vec<String>,
+    timeout_seconds: u64,
+}
+
+#[derive(Deserialize)]
+struct HooksReview {
+    hooks: Vec<HookSummary>,
+    approved: bool,
+}
+
+/// An install waiting for the user to approve the app's hooks.
+#[derive(Clone, PartialEq)]
+struct PendingHooks {
+    app: AppInfo,
+    version: Option<AppVersion>,
+    config: String,
+    hooks: Vec<HookSummary>,
+}
+
+#[derive(Clone, PartialEq, Deserialize, serde::Serialize)]
 struct PlaytimeEntry {
     app_id: String,
     total_seconds: i64,
     last_played: i64,
 }
 
+/// What the Library last loaded from a server, shown while it's offline.
+#[derive(Default, Deserialize, serde::Serialize)]
+struct LibraryCache {
+    apps: Vec<AppInfo>,
+    playtime: Vec<PlaytimeEntry>,
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct RunStartResult {
@@ -78,8 +265,24 @@ struct RunEvent {
     status: String,
     duration_seconds: u64,
     #[serde(default)]
-    #[allow(dead_code)]
     exit_code: Option<i32>,
+    #[serde(default)]
+    profile: Option<String>,
+    #[serde(default)]
+    crash: Option<CrashInfo>,
+    #[serde(default)]
+    message: Option<String>,
+}
+
+/// Sent along with a `stopped` event when the run ended like a crash.
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CrashInfo {
+    app_name: String,
+    app_version: String,
+    signal: Option<i32>,
+    output: String,
+    os: String,
 }
 
 #[derive(serde::Serialize)]
@@ -90,6 +293,7 @@ struct StartDownloadArgs {
     config_url: String,
     dest_dir: String,
     token: String,
+    server: String,
 }
 
 #[function_component(LibraryScreen)]
@@ -100,8 +304,9 @@ pub fn library_screen() -> Html {
     let server_port = app_state
         .server_port
         .clone()
-        .unwrap_or_else(|| "2121".to_string());
+        .unwrap_or_else(|| DEFAULT_PORT.to_string());
     let token = app_state.session_token.clone().unwrap_or_default();
+    let offline = app_state.offline;
     let toast = use_toast();
 
     let apps = use_state(Vec::<AppInfo>::new);
@@ -112,7 +317,41 @@ pub fn library_screen() -> Html {
     let refresh_tick = use_state(|| 0u32);
     let installed = use_state(|| HashSet::<String>::new());
     let playtime = use_state(|| HashMap::<String, PlaytimeEntry>::new());
-    let running = use_state(|| HashSet::<String>::new());
+    // Run events arrive through listeners registered once, so running apps
+    // and their usage samples live outside state where the listeners always
+    // see the current values.
+    let running = use_mut_ref(HashMap::<String, RunningView>::new);
+    let run_stats = use_mut_ref(HashMap::<String, (f64, RunStats)>::new);
+    let rerender = use_force_update();
+    let pending_launch = use_state(|| None::<CliLaunch>);
+    let pinned = use_state(load_pinned_versions);
+    let launch_menu = use_state(|| None::<String>);
+    let settings_for = use_state(|| None::<AppInfo>);
+    let settings_args = use_state(String::new);
+    let settings_env = use_state(String::new);
+    let settings_wrapper_mode = use_state(|| "default".to_string());
+    let settings_wrapper = use_state(String::new);
+    let settings_default_wrapper = use_state(String::new);
+    let settings_stop_grace = use_state(String::new);
+    let settings_save_paths = use_state(String::new);
+    let settings_saving = use_state(|| false);
+    let logs_for = use_state(|| None::<AppInfo>);
+    let log_entries = use_state(Vec::<LogEntry>::new);
+    let log_selected = use_state(|| None::<String>);
+    let log_content = use_state(String::new);
+    let log_loading = use_state(|| false);
+    let versions_for = use_state(|| None::<AppInfo>);
+    let mods_for = use_state(|| None::<AppInfo>);
+    let mod_overview = use_state(ModOverview::default);
+    let mods_busy = use_state(|| false);
+    let save_prompt = use_state(|| None::<SavePrompt>);
+    let save_busy = use_state(|| false);
+    let snapshots_for = use_state(|| None::<AppInfo>);
+    let snapshots = use_state(Vec::<SaveSnapshot>::new);
+    let snapshots_loading = use_state(|| false);
+    let versions = use_state(Vec::<AppVersion>::new);
+    let versions_loading = use_state(|| false);
+    let pending_hooks = use_state(|| None::<PendingHooks>);
     let search = use_state(String::new);
     let sort = use_state(|| "downloaded".to_string());
     let carousel_ref = use_node_ref();
@@ -177,6 +416,7 @@ pub fn library_screen() -> Html {
                 token.clone(),
                 *refresh_tick,
                 (*install_dir).clone(),
+                offline,
             ),
             move |_| {
                 if server_ip.is_empty() || server_port.is_empty() || token.is_empty() {
@@ -196,25 +436,16 @@ pub fn library_screen() -> Html {
                 let token = token.clone();
                 spawn_local(async move {
                     let url = build_http_url(&server_ip, &server_port, "apps");
-                    match get_json::<Vec<AppInfo>>(&url, Some(&token)).await {
-                        Ok(list) => {
-                            apps.set(list);
-                            error.set(None);
-                        }
-                        Err(msg) => {
-                            error.set(Some(msg));
-                        }
-                    }
-                    let playtime_url = build_http_url(&server_ip, &server_port, "apps/playtime");
-                    if let Ok(list) =
-                        get_json::<Vec<PlaytimeEntry>>(&playtime_url, Some(&token)).await
-                    {
-                        let mut next = HashMap::new();
-                        for entry in list {
-                            next.insert(entry.app_id.clone(), entry);
-                        }
-                        playtime.set(next);
+                    let fetched = get_json::<Vec<AppInfo>>(&url, Some(&token)).await;
+                    let reachable = !matches!(&fetched, Err(msg) if is_unreachable(msg));
+                    let mut entries = None;
+                    if reachable {
+                        // Sessions journaled while the server was unreachable
+                        // go up first so the totals below include them.
+                        let _ = sync_playtime(&server_ip, &server_port, &token).await;
+                        entries = fetch_playtime(&server_ip, &server_port, &token).await;
                     }
+                    let mut local = HashSet::new();
                     if !install_dir.is_empty() {
                         let dest_dir: String = install_dir.as_str().to_string();
                         let payload = serde_wasm_bindgen::to_value(&serde_json::json!({
@@ -223,11 +454,50 @@ pub fn library_screen() -> Html {
                             }
                         }))
                         .unwrap_or(JsValue::NULL);
-                        let result = invoke("list_installed_apps", payload).await;
+                        let result = invoke("list_installed_apps", payload.clone()).await;
                         if let Ok(list) = serde_wasm_bindgen::from_value::<Vec<String>>(result) {
-                            installed.set(list.into_iter().collect());
+                            // Servers can share an install folder; an app with the
+                            // same id from another server isn't this one.
+                            let sources = invoke("list_app_sources", payload).await;
+                            let sources: HashMap<String, String> =
+                                serde_wasm_bindgen::from_value(sources).unwrap_or_default();
+                            let current = server_key(&server_ip, &server_port);
+                            local = list
+                                .into_iter()
+                                .filter(|id| {
+                                    sources.get(id).is_none_or(|source| *source == current)
+                                })
+                                .collect();
+                            installed.set(local.clone());
+                        }
+                    }
+                    match fetched {
+                        Ok(list) => {
+                            store_library_cache(&server_ip, &list, entries.as_ref());
+                            apps.set(list);
+                            error.set(None);
+                        }
+                        // Without the server, what's installed can still be
+                        // launched, described by the last list it sent.
+                        Err(_) if !reachable => {
+                            let cache = load_library_cache(&server_ip);
+                            apps.set(offline_apps(cache.apps, &local));
+                            entries = Some(
+                                cache
+                                    .playtime
+                                    .into_iter()
+                                    .map(|entry| (entry.app_id.clone(), entry))
+                                    .collect(),
+                            );
+                            error.set(None);
+                        }
+                        Err(msg) => {
+                            error.set(Some(msg));
                         }
                     }
+                    if let Some(entries) = entries {
+                        playtime.set(entries);
+                    }
                     loading.set(false);
                 });
                 ()
@@ -446,6 +716,8 @@ pub fn library_screen() -> Html {
 
     {
         let running = running.clone();
+        let run_stats = run_stats.clone();
+        let rerender = rerender.clone();
         let playtime = playtime.clone();
         let toast = toast.clone();
         let server_ip = server_ip.clone();
@@ -461,8 +733,8 @@ pub fn library_screen() -> Html {
 
                 let listen_fn: Function = listen.dyn_into().unwrap();
 
-                let callback =
-                    Closure::<dyn FnMut(JsValue)>::wrap(Box::new(move |value: JsValue| {
+                let callback = Closure::<dyn FnMut(JsValue)>::wrap(Box::new(
+                    move |value: JsValue| {
                         let payload = Reflect::get(&value, &JsValue::from_str("payload"))
                             .unwrap_or(JsValue::NULL);
 
@@ -472,9 +744,34 @@ pub fn library_screen() -> Html {
                         };
 
                         if event.status == "started" {
-                            let mut next = (*running).clone();
-                            next.insert(event.id.clone());
-                            running.set(next);
+                            running.borrow_mut().insert(
+                                event.id.clone(),
+                                RunningView {
+                                    started_at: js_sys::Date::now() / 1000.0,
+                                    stopping: false,
+                                },
+                            );
+                            rerender.force_update();
+                            return;
+                        }
+
+                        if event.status == "stopping" {
+                            if let Some(view) = running.borrow_mut().get_mut(&event.id) {
+                                view.stopping = true;
+                            }
+                            rerender.force_update();
+                            return;
+                        }
+
+                        if event.status == "hook_failed" {
+                            toast.toast(
+                                event
+                                    .message
+                                    .clone()
+                                    .unwrap_or_else(|| "App hook failed.".to_string()),
+                                ToastVariant::Error,
+                                Some(5000),
+                            );
                             return;
                         }
 
@@ -482,16 +779,74 @@ pub fn library_screen() -> Html {
                             return;
                         }
 
-                        let mut next = (*running).clone();
-                        next.remove(&event.id);
-                        running.set(next);
+                        running.borrow_mut().remove(&event.id);
+                        run_stats.borrow_mut().remove(&event.id);
+                        rerender.force_update();
+
+                        if let Some(crash) = event.crash.clone() {
+                            let server_ip = server_ip.clone();
+                            let server_port = server_port.clone();
+                            let token = token.clone();
+                            let report_toast = toast.clone();
+                            let id = event.id.clone();
+                            let exit_code = event.exit_code;
+                            let profile = event.profile.clone();
+                            let duration = event.duration_seconds;
+                            let message = format!(
+                                "{} closed unexpectedly ({}).",
+                                crash.app_name,
+                                describe_exit(exit_code, crash.signal)
+                            );
+                            let on_click = Callback::from(move |_| {
+                                let server_ip = server_ip.clone();
+                                let server_port = server_port.clone();
+                                let token = token.clone();
+                                let toast = report_toast.clone();
+                                let id = id.clone();
+                                let crash = crash.clone();
+                                let report = CrashReportRequest {
+                                    exit_code,
+                                    profile: profile.clone(),
+                                    duration_seconds: duration,
+                                };
+                                spawn_local(async move {
+                                    let result = send_crash_report(
+                                        &server_ip,
+                                        &server_port,
+                                        &token,
+                                        &id,
+                                        report,
+                                        &crash,
+                                    )
+                                    .await;
+                                    match result {
+                                        Ok(()) => toast.toast(
+                                            "Crash report sent. Thanks!",
+                                            ToastVariant::Success,
+                                            Some(2500),
+                                        ),
+                                        Err(err) => {
+                                            toast.toast(err, ToastVariant::Error, Some(3500))
+                                        }
+                                    }
+                                });
+                            });
+                            toast.toast_with_action(
+                                message,
+                                ToastVariant::Warning,
+                                Some(15000),
+                                ToastAction {
+                                    label: "Send crash report".to_string(),
+                                    on_click,
+                                },
+                            );
+                        }
 
                         let server_ip = server_ip.clone();
                         let server_port = server_port.clone();
                         let token = token.clone();
                         let playtime = playtime.clone();
                         let toast = toast.clone();
-                        let id = event.id.clone();
                         let duration = event.duration_seconds as i64;
 
                         spawn_local(async move {
@@ -521,40 +876,28 @@ pub fn library_screen() -> Html {
                                 return;
                             }
 
-                            let url = build_http_url(
-                                server_ip.trim(),
-                                server_port.trim(),
-                                &format!("apps/{}/playtime", id),
-                            );
-                            let body = serde_json::json!({ "seconds": duration });
-
-                            if let Ok(resp) =
-                                send_json("POST", &url, Some(token.trim()), Some(body)).await
+                            // The session is already in the local journal; this
+                            // only uploads it now instead of on the next retry.
+                            if sync_playtime(server_ip.trim(), server_port.trim(), token.trim())
+                                .await
+                                .is_err()
                             {
-                                if resp.ok() {
-                                    if let Ok(promise) = resp.json() {
-                                        if let Ok(json) = JsFuture::from(promise).await {
-                                            if let Ok(entry) =
-                                                serde_wasm_bindgen::from_value::<PlaytimeEntry>(
-                                                    json,
-                                                )
-                                            {
-                                                let mut next = (*playtime).clone();
-                                                next.insert(entry.app_id.clone(), entry);
-                                                playtime.set(next);
-                                            }
-                                        }
-                                    }
-                                } else {
-                                    toast.toast(
-                                        "Playtime upload failed.",
-                                        ToastVariant::Warning,
-                                        Some(2500),
-                                    );
-                                }
+                                toast.toast(
+                                    "Playtime saved locally and will be uploaded once the server is reachable.",
+                                    ToastVariant::Warning,
+                                    Some(3500),
+                                );
+                                return;
+                            }
+                            if let Some(entries) =
+                                fetch_playtime(server_ip.trim(), server_port.trim(), token.trim())
+                                    .await
+                            {
+                                playtime.set(entries);
                             }
                         });
-                    }));
+                    },
+                ));
 
                 let _ = listen_fn.call2(
                     &event,
@@ -576,14 +919,181 @@ pub fn library_screen() -> Html {
         });
     }
 
-    let on_download = {
+    // Launches from the command line wait in the client until the Library
+    // takes them: once on mount, then whenever another one comes in.
+    {
+        let pending_launch = pending_launch.clone();
+        use_effect_with((), move |_| {
+            let take_launch = {
+                let pending_launch = pending_launch.clone();
+                move || {
+                    let pending_launch = pending_launch.clone();
+                    spawn_local(async move {
+                        if let Ok(value) = invoke_safe("take_pending_launch", JsValue::NULL).await {
+                            if let Ok(Some(request)) =
+                                serde_wasm_bindgen::from_value::<Option<CliLaunch>>(value)
+                            {
+                                pending_launch.set(Some(request));
+                            }
+                        }
+                    });
+                }
+            };
+            take_launch();
+
+            let listen_launches = move || -> Result<(), JsValue> {
+                let window = web_sys::window().unwrap();
+                let tauri = Reflect::get(&window, &JsValue::from_str("__TAURI__"))?;
+                let event = Reflect::get(&tauri, &JsValue::from_str("event"))?;
+                let listen = Reflect::get(&event, &JsValue::from_str("listen"))?;
+                let listen_fn: Function = listen.dyn_into()?;
+
+                let callback = Closure::<dyn FnMut(JsValue)>::wrap(Box::new(move |_: JsValue| {
+                    take_launch();
+                }));
+                listen_fn.call2(
+                    &event,
+                    &JsValue::from_str("cli_launch"),
+                    callback.as_ref().unchecked_ref(),
+                )?;
+                callback.forget();
+                Ok(())
+            };
+            let _ = listen_launches();
+            || ()
+        });
+    }
+
+    // Runs outlive the webview, so pick up whatever is still running after a
+    // reload, then tick once a second for the elapsed counters.
+    {
+        let running = running.clone();
+        let rerender = rerender.clone();
+        use_effect_with((), move |_| {
+            let cancelled = std::rc::Rc::new(std::cell::RefCell::new(false));
+            let cancelled_task = cancelled.clone();
+            spawn_local(async move {
+                if let Ok(value) = invoke_safe("list_running_apps", JsValue::NULL).await {
+                    if let Ok(list) = serde_wasm_bindgen::from_value::<Vec<RunningApp>>(value) {
+                        let now = js_sys::Date::now() / 1000.0;
+                        let mut running = running.borrow_mut();
+                        for app in list {
+                            running.insert(
+                                app.id,
+                                RunningView {
+                                    started_at: now - app.elapsed_seconds as f64,
+                                    stopping: app.stopping,
+                                },
+                            );
+                        }
+                    }
+                    rerender.force_update();
+                }
+
+                let mut interval = IntervalStream::new(1_000);
+                while interval.next().await.is_some() {
+                    if *cancelled_task.borrow() {
+                        break;
+                    }
+                    if !running.borrow().is_empty() {
+                        rerender.force_update();
+                    }
+                }
+            });
+            move || {
+                *cancelled.borrow_mut() = true;
+            }
+        });
+    }
+
+    {
+        let run_stats = run_stats.clone();
+        let rerender = rerender.clone();
+        use_effect_with((), move |_| {
+            let listen_stats = move || -> Result<(), JsValue> {
+                let window = web_sys::window().unwrap();
+                let tauri = Reflect::get(&window, &JsValue::from_str("__TAURI__"))?;
+                let event = Reflect::get(&tauri, &JsValue::from_str("event"))?;
+                let listen = Reflect::get(&event, &JsValue::from_str("listen"))?;
+                let listen_fn: Function = listen.dyn_into()?;
+
+                let callback =
+                    Closure::<dyn FnMut(JsValue)>::wrap(Box::new(move |value: JsValue| {
+                        let payload = Reflect::get(&value, &JsValue::from_str("payload"))
+                            .unwrap_or(JsValue::NULL);
+                        let Ok(stats) = serde_wasm_bindgen::from_value::<RunStats>(payload) else {
+                            return;
+                        };
+                        run_stats
+                            .borrow_mut()
+                            .insert(stats.id.clone(), (js_sys::Date::now() / 1000.0, stats));
+                        rerender.force_update();
+                    }));
+
+                listen_fn.call2(
+                    &event,
+                    &JsValue::from_str("app_run_stats"),
+                    callback.as_ref().unchecked_ref(),
+                )?;
+                callback.forget();
+                Ok(())
+            };
+            let _ = listen_stats();
+            || ()
+        });
+    }
+
+    // A save that couldn't be uploaded after a session because the cloud one
+    // changed too.
+    {
+        let save_prompt = save_prompt.clone();
+        use_effect_with((), move |_| {
+            let listen_conflicts = move || -> Result<(), JsValue> {
+                let window = web_sys::window().unwrap();
+                let tauri = Reflect::get(&window, &JsValue::from_str("__TAURI__"))?;
+                let event = Reflect::get(&tauri, &JsValue::from_str("event"))?;
+                let listen = Reflect::get(&event, &JsValue::from_str("listen"))?;
+                let listen_fn: Function = listen.dyn_into()?;
+
+                let callback =
+                    Closure::<dyn FnMut(JsValue)>::wrap(Box::new(move |value: JsValue| {
+                        let payload = Reflect::get(&value, &JsValue::from_str("payload"))
+                            .unwrap_or(JsValue::NULL);
+                        let Ok(conflict) =
+                            serde_wasm_bindgen::from_value::<SaveConflictEvent>(payload)
+                        else {
+                            return;
+                        };
+                        save_prompt.set(Some(SavePrompt {
+                            id: conflict.id,
+                            conflict: true,
+                            cloud: conflict.cloud,
+                            launch: None,
+                        }));
+                    }));
+
+                listen_fn.call2(
+                    &event,
+                    &JsValue::from_str("app_save_conflict"),
+                    callback.as_ref().unchecked_ref(),
+                )?;
+                callback.forget();
+                Ok(())
+            };
+            let _ = listen_conflicts();
+            || ()
+        });
+    }
+
+    let start_download = {
         let server_ip = server_ip.clone();
         let server_port = server_port.clone();
         let install_dir = install_dir.clone();
         let downloads = downloads.clone();
+        let pinned = pinned.clone();
         let token = token.clone();
         let toast = toast.clone();
-        Callback::from(move |app: AppInfo| {
+        Callback::from(move |(app, version): (AppInfo, Option<AppVersion>)| {
             if server_ip.is_empty() || server_port.is_empty() || token.is_empty() {
                 toast.toast(
                     "Missing server address or token.",
@@ -613,13 +1123,26 @@ pub fn library_screen() -> Html {
                 return;
             }
 
-            let archive_url = build_http_url(
-                &server_ip,
-                &server_port,
-                &format!("apps/{}/archive", app.id),
-            );
-            let config_url =
-                build_http_url(&server_ip, &server_port, &format!("apps/{}/config", app.id));
+            // A picked version downloads from its own archive and stays pinned
+            // to that version label in the library.
+            let base = match &version {
+                Some(version) => format!("apps/{}/versions/{}", app.id, version.id),
+                None => format!("apps/{}", app.id),
+            };
+            let archive_url =
+                build_http_url(&server_ip, &server_port, &format!("{}/archive", base));
+            let config_url = build_http_url(&server_ip, &server_port, &format!("{}/config", base));
+            let mut pinned_next = (*pinned).clone();
+            match &version {
+                Some(version) => {
+                    pinned_next.insert(app.id.clone(), version_label(version));
+                }
+                None => {
+                    pinned_next.remove(&app.id);
+                }
+            }
+            save_pinned_versions(&pinned_next);
+            pinned.set(pinned_next);
             let args = StartDownloadArgs {
                 id: app.id.clone(),
                 name: app.name.clone(),
@@ -627,6 +1150,7 @@ pub fn library_screen() -> Html {
                 config_url,
                 dest_dir,
                 token: token.clone(),
+                server: server_key(&server_ip, &server_port),
             };
             let downloads = downloads.clone();
             let toast = toast.clone();
@@ -638,7 +1162,8 @@ pub fn library_screen() -> Html {
                         "archiveUrl": args.archive_url,
                         "configUrl": args.config_url,
                         "destDir": args.dest_dir,
-                        "token": args.token
+                        "token": args.token,
+                        "server": args.server
                     }
                 }))
                 .unwrap_or(JsValue::NULL);
@@ -663,20 +1188,104 @@ pub fn library_screen() -> Html {
         })
     };
 
-    let confirm = use_confirm();
-    let on_remove = {
-        let install_dir = install_dir.clone();
-        let installed = installed.clone();
-        let downloads = downloads.clone();
-        let toast = toast.clone();
-        let confirm = confirm.clone();
-
-        Callback::from(move |id: String| {
-            let install_dir = (*install_dir).clone();
-
-            if install_dir.trim().is_empty() {
-                toast.toast(
-                    "No install folder configured.",
+    // Apps with lifecycle hooks only install once the user has seen and
+    // approved them; the same hooks are checked again before they run.
+    let on_download = {
+        let server_ip = server_ip.clone();
+        let server_port = server_port.clone();
+        let token = token.clone();
+        let pending_hooks = pending_hooks.clone();
+        let start_download = start_download.clone();
+        Callback::from(move |(app, version): (AppInfo, Option<AppVersion>)| {
+            if server_ip.is_empty() || server_port.is_empty() || token.is_empty() {
+                start_download.emit((app, version));
+                return;
+            }
+            let base = match &version {
+                Some(version) => format!("apps/{}/versions/{}", app.id, version.id),
+                None => format!("apps/{}", app.id),
+            };
+            let config_url = build_http_url(&server_ip, &server_port, &format!("{}/config", base));
+            let token = token.clone();
+            let pending_hooks = pending_hooks.clone();
+            let start_download = start_download.clone();
+            spawn_local(async move {
+                // Without the config the install goes ahead; it fails later
+                // if the app turns out to have hooks nobody approved.
+                let Some(config) = fetch_text(&config_url, &token).await else {
+                    start_download.emit((app, version));
+                    return;
+                };
+                let payload = serde_wasm_bindgen::to_value(&serde_json::json!({
+                    "request": { "id": app.id.clone(), "config": config.clone() }
+                }))
+                .unwrap_or(JsValue::NULL);
+                let review = invoke_safe("inspect_app_hooks", payload)
+                    .await
+                    .ok()
+                    .and_then(|value| serde_wasm_bindgen::from_value::<HooksReview>(value).ok());
+                match review {
+                    Some(review) if !review.approved => pending_hooks.set(Some(PendingHooks {
+                        app,
+                        version,
+                        config,
+                        hooks: review.hooks,
+                    })),
+                    _ => start_download.emit((app, version)),
+                }
+            });
+        })
+    };
+
+    let on_approve_hooks = {
+        let pending_hooks = pending_hooks.clone();
+        let start_download = start_download.clone();
+        let toast = toast.clone();
+        Callback::from(move |_| {
+            let Some(pending) = (*pending_hooks).clone() else {
+                return;
+            };
+            pending_hooks.set(None);
+            let start_download = start_download.clone();
+            let toast = toast.clone();
+            spawn_local(async move {
+                let payload = serde_wasm_bindgen::to_value(&serde_json::json!({
+                    "request": { "id": pending.app.id.clone(), "config": pending.config }
+                }))
+                .unwrap_or(JsValue::NULL);
+                match invoke_safe("approve_app_hooks", payload).await {
+                    Ok(_) => start_download.emit((pending.app, pending.version)),
+                    Err(err) => toast.toast(
+                        err.as_string()
+                            .unwrap_or_else(|| "Failed to approve app hooks.".to_string()),
+                        ToastVariant::Error,
+                        Some(3000),
+                    ),
+                }
+            });
+        })
+    };
+
+    let on_cancel_hooks = {
+        let pending_hooks = pending_hooks.clone();
+        Callback::from(move |_| pending_hooks.set(None))
+    };
+
+    let confirm = use_confirm();
+    let on_remove = {
+        let install_dir = install_dir.clone();
+        let installed = installed.clone();
+        let downloads = downloads.clone();
+        let pinned = pinned.clone();
+        let toast = toast.clone();
+        let confirm = confirm.clone();
+
+        Callback::from(move |id: String| {
+            let install_dir = (*install_dir).clone();
+
+            if install_dir.trim().is_empty() {
+                toast.toast(
+                    "No install folder configured.",
                     ToastVariant::Warning,
                     Some(2500),
                 );
@@ -685,10 +1294,12 @@ pub fn library_screen() -> Html {
 
             let installed = installed.clone();
             let downloads = downloads.clone();
+            let pinned = pinned.clone();
+            let toast = toast.clone();
 
             confirm.confirm(ConfirmRequest {
             title: "Remove application".into(),
-            message: "This will remove the installed application from your system. This action cannot be undone."
+            message: "This will remove the installed application from your system. Saves are snapshotted first and can be restored after installing it again."
                 .into(),
             confirm_label: "Remove".into(),
             cancel_label: "Cancel".into(),
@@ -697,6 +1308,8 @@ pub fn library_screen() -> Html {
                 let install_dir = install_dir.clone();
                 let installed = installed.clone();
                 let downloads = downloads.clone();
+                let pinned = pinned.clone();
+                let toast = toast.clone();
 
                 spawn_local(async move {
                     let payload = serde_wasm_bindgen::to_value(&serde_json::json!({
@@ -707,7 +1320,13 @@ pub fn library_screen() -> Html {
                     }))
                     .unwrap_or(JsValue::NULL);
 
-                    let _ = invoke("remove_installed_app", payload).await;
+                    if let Err(err) = invoke_safe("remove_installed_app", payload).await {
+                        let message = err
+                            .as_string()
+                            .unwrap_or_else(|| "Failed to remove app.".to_string());
+                        toast.toast(message, ToastVariant::Error, Some(3000));
+                        return;
+                    }
 
                     let mut next = (*installed).clone();
                     next.remove(&id);
@@ -716,12 +1335,541 @@ pub fn library_screen() -> Html {
                     let mut downloads_next = (*downloads).clone();
                     downloads_next.remove(&id);
                     downloads.set(downloads_next);
+
+                    let mut pinned_next = (*pinned).clone();
+                    if pinned_next.remove(&id).is_some() {
+                        save_pinned_versions(&pinned_next);
+                        pinned.set(pinned_next);
+                    }
                 });
             }),
         });
         })
     };
 
+    let on_open_versions = {
+        let server_ip = server_ip.clone();
+        let server_port = server_port.clone();
+        let token = token.clone();
+        let versions_for = versions_for.clone();
+        let versions = versions.clone();
+        let versions_loading = versions_loading.clone();
+        let toast = toast.clone();
+        Callback::from(move |app: AppInfo| {
+            if server_ip.is_empty() || server_port.is_empty() || token.is_empty() {
+                toast.toast(
+                    "Missing server address or token.",
+                    ToastVariant::Warning,
+                    Some(2500),
+                );
+                return;
+            }
+            let url = build_http_url(
+                &server_ip,
+                &server_port,
+                &format!("apps/{}/versions", app.id),
+            );
+            versions_for.set(Some(app));
+            versions.set(Vec::new());
+            versions_loading.set(true);
+            let token = token.clone();
+            let versions = versions.clone();
+            let versions_loading = versions_loading.clone();
+            let toast = toast.clone();
+            spawn_local(async move {
+                match get_json::<Vec<AppVersion>>(&url, Some(&token)).await {
+                    Ok(list) => versions.set(list),
+                    Err(msg) => toast.toast(msg, ToastVariant::Error, Some(3000)),
+                }
+                versions_loading.set(false);
+            });
+        })
+    };
+
+    let on_close_versions = {
+        let versions_for = versions_for.clone();
+        Callback::from(move |_| versions_for.set(None))
+    };
+
+    let on_open_settings = {
+        let settings_for = settings_for.clone();
+        let settings_args = settings_args.clone();
+        let settings_env = settings_env.clone();
+        let settings_wrapper_mode = settings_wrapper_mode.clone();
+        let settings_wrapper = settings_wrapper.clone();
+        let settings_default_wrapper = settings_default_wrapper.clone();
+        let settings_stop_grace = settings_stop_grace.clone();
+        let settings_save_paths = settings_save_paths.clone();
+        let toast = toast.clone();
+        Callback::from(move |app: AppInfo| {
+            let settings_for = settings_for.clone();
+            let settings_args = settings_args.clone();
+            let settings_env = settings_env.clone();
+            let settings_wrapper_mode = settings_wrapper_mode.clone();
+            let settings_wrapper = settings_wrapper.clone();
+            let settings_default_wrapper = settings_default_wrapper.clone();
+            let settings_stop_grace = settings_stop_grace.clone();
+            let settings_save_paths = settings_save_paths.clone();
+            let toast = toast.clone();
+            spawn_local(async move {
+                let settings = match invoke_safe("get_launch_settings", JsValue::NULL).await {
+                    Ok(value) => {
+                        serde_wasm_bindgen::from_value::<LaunchSettings>(value).unwrap_or_default()
+                    }
+                    Err(_) => {
+                        toast.toast(
+                            "Failed to load launch settings.",
+                            ToastVariant::Error,
+                            Some(3000),
+                        );
+                        return;
+                    }
+                };
+                let app_settings = settings.apps.get(&app.id).cloned().unwrap_or_default();
+                settings_args.set(app_settings.extra_args.clone());
+                settings_env.set(
+                    app_settings
+                        .env
+                        .iter()
+                        .map(|(key, value)| format!("{}={}", key, value))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                );
+                settings_save_paths.set(app_settings.save_paths.join("\n"));
+                match app_settings.wrapper {
+                    None => {
+                        settings_wrapper_mode.set("default".to_string());
+                        settings_wrapper.set(String::new());
+                    }
+                    Some(wrapper) if wrapper.trim().is_empty() => {
+                        settings_wrapper_mode.set("none".to_string());
+                        settings_wrapper.set(String::new());
+                    }
+                    Some(wrapper) => {
+                        settings_wrapper_mode.set("custom".to_string());
+                        settings_wrapper.set(wrapper);
+                    }
+                }
+                settings_default_wrapper.set(settings.default_wrapper);
+                settings_stop_grace.set(
+                    settings
+                        .stop_grace_seconds
+                        .map(|seconds| seconds.to_string())
+                        .unwrap_or_default(),
+                );
+                settings_for.set(Some(app));
+            });
+        })
+    };
+
+    let on_close_settings = {
+        let settings_for = settings_for.clone();
+        Callback::from(move |_| settings_for.set(None))
+    };
+
+    let on_save_settings = {
+        let settings_for = settings_for.clone();
+        let settings_args = settings_args.clone();
+        let settings_env = settings_env.clone();
+        let settings_wrapper_mode = settings_wrapper_mode.clone();
+        let settings_wrapper = settings_wrapper.clone();
+        let settings_default_wrapper = settings_default_wrapper.clone();
+        let settings_stop_grace = settings_stop_grace.clone();
+        let settings_save_paths = settings_save_paths.clone();
+        let settings_saving = settings_saving.clone();
+        let toast = toast.clone();
+        Callback::from(move |_| {
+            let Some(app) = (*settings_for).clone() else {
+                return;
+            };
+            let env = match parse_env_lines(&settings_env) {
+                Ok(env) => env,
+                Err(msg) => {
+                    toast.toast(msg, ToastVariant::Warning, Some(3000));
+                    return;
+                }
+            };
+            let wrapper = match settings_wrapper_mode.as_str() {
+                "none" => Some(String::new()),
+                "custom" if !settings_wrapper.trim().is_empty() => {
+                    Some(settings_wrapper.trim().to_string())
+                }
+                _ => None,
+            };
+            let app_settings = AppLaunchSettings {
+                extra_args: settings_args.trim().to_string(),
+                env,
+                wrapper,
+                save_paths: settings_save_paths
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            };
+            let default_wrapper = settings_default_wrapper.trim().to_string();
+            let stop_grace = settings_stop_grace.trim();
+            let stop_grace_seconds = if stop_grace.is_empty() {
+                None
+            } else {
+                match stop_grace.parse::<u64>() {
+                    Ok(seconds) => Some(seconds),
+                    Err(_) => {
+                        toast.toast(
+                            "Stop grace period must be a whole number of seconds.",
+                            ToastVariant::Warning,
+                            Some(3000),
+                        );
+                        return;
+                    }
+                }
+            };
+            settings_saving.set(true);
+            let settings_for = settings_for.clone();
+            let settings_saving = settings_saving.clone();
+            let toast = toast.clone();
+            spawn_local(async move {
+                let payload = serde_wasm_bindgen::to_value(&serde_json::json!({
+                    "request": {
+                        "id": app.id,
+                        "settings": app_settings,
+                        "defaultWrapper": default_wrapper,
+                        "stopGraceSeconds": stop_grace_seconds
+                    }
+                }))
+                .unwrap_or(JsValue::NULL);
+                match invoke_safe("save_app_launch_settings", payload).await {
+                    Ok(_) => {
+                        toast.toast("Launch settings saved.", ToastVariant::Success, Some(2500));
+                        settings_for.set(None);
+                    }
+                    Err(err) => {
+                        let message = err
+                            .as_string()
+                            .unwrap_or_else(|| "Failed to save launch settings.".to_string());
+                        toast.toast(message, ToastVariant::Error, Some(3000));
+                    }
+                }
+                settings_saving.set(false);
+            });
+        })
+    };
+
+    let on_select_log = {
+        let install_dir = install_dir.clone();
+        let logs_for = logs_for.clone();
+        let log_selected = log_selected.clone();
+        let log_content = log_content.clone();
+        let log_loading = log_loading.clone();
+        let toast = toast.clone();
+        Callback::from(move |name: String| {
+            let Some(app) = (*logs_for).clone() else {
+                return;
+            };
+            let install_dir = (*install_dir).clone();
+            let log_selected = log_selected.clone();
+            let log_content = log_content.clone();
+            let log_loading = log_loading.clone();
+            let toast = toast.clone();
+            log_selected.set(Some(name.clone()));
+            log_loading.set(true);
+            spawn_local(async move {
+                match read_app_log(&app.id, &install_dir, &name).await {
+                    Ok(content) => log_content.set(content),
+                    Err(msg) => {
+                        log_content.set(String::new());
+                        toast.toast(msg, ToastVariant::Error, Some(3000));
+                    }
+                }
+                log_loading.set(false);
+            });
+        })
+    };
+
+    let on_open_logs = {
+        let install_dir = install_dir.clone();
+        let logs_for = logs_for.clone();
+        let log_entries = log_entries.clone();
+        let log_selected = log_selected.clone();
+        let log_content = log_content.clone();
+        let log_loading = log_loading.clone();
+        Callback::from(move |app: AppInfo| {
+            let install_dir = (*install_dir).clone();
+            let log_entries = log_entries.clone();
+            let log_selected = log_selected.clone();
+            let log_content = log_content.clone();
+            let log_loading = log_loading.clone();
+            logs_for.set(Some(app.clone()));
+            log_entries.set(Vec::new());
+            log_selected.set(None);
+            log_content.set(String::new());
+            log_loading.set(true);
+            spawn_local(async move {
+                let entries = list_app_logs(&app.id, &install_dir).await;
+                if let Some(newest) = entries.first() {
+                    log_selected.set(Some(newest.name.clone()));
+                    if let Ok(content) = read_app_log(&app.id, &install_dir, &newest.name).await {
+                        log_content.set(content);
+                    }
+                }
+                log_entries.set(entries);
+                log_loading.set(false);
+            });
+        })
+    };
+
+    let on_close_logs = {
+        let logs_for = logs_for.clone();
+        Callback::from(move |_| logs_for.set(None))
+    };
+
+    let on_copy_last_log = {
+        let install_dir = install_dir.clone();
+        let logs_for = logs_for.clone();
+        let toast = toast.clone();
+        Callback::from(move |_| {
+            let Some(app) = (*logs_for).clone() else {
+                return;
+            };
+            let install_dir = (*install_dir).clone();
+            let toast = toast.clone();
+            spawn_local(async move {
+                let entries = list_app_logs(&app.id, &install_dir).await;
+                let Some(newest) = entries.first() else {
+                    toast.toast("No logs recorded yet.", ToastVariant::Warning, Some(2500));
+                    return;
+                };
+                let content = match read_app_log(&app.id, &install_dir, &newest.name).await {
+                    Ok(content) => content,
+                    Err(msg) => {
+                        toast.toast(msg, ToastVariant::Error, Some(3000));
+                        return;
+                    }
+                };
+                match copy_to_clipboard(&content).await {
+                    Ok(()) => toast.toast(
+                        "Last log copied to the clipboard.",
+                        ToastVariant::Success,
+                        Some(2500),
+                    ),
+                    Err(()) => toast.toast(
+                        "Failed to copy log to the clipboard.",
+                        ToastVariant::Error,
+                        Some(3000),
+                    ),
+                }
+            });
+        })
+    };
+
+    // Installed mods of the open panel's app; reloads the panel whenever a
+    // mod download finishes.
+    let mods_installed = (*mods_for)
+        .as_ref()
+        .map(|base| {
+            apps.iter()
+                .filter(|app| app.base_app.as_deref() == Some(base.id.as_str()))
+                .filter(|app| {
+                    installed.contains(&app.id)
+                        || downloads
+                            .get(&app.id)
+                            .is_some_and(|d| d.status == "completed")
+                })
+                .count()
+        })
+        .unwrap_or(0);
+    {
+        let install_dir = install_dir.clone();
+        let mod_overview = mod_overview.clone();
+        let base_id = (*mods_for).as_ref().map(|app| app.id.clone());
+        use_effect_with((base_id, mods_installed), move |(base_id, _)| {
+            if let Some(base_id) = base_id.clone() {
+                let install_dir = (*install_dir).clone();
+                spawn_local(async move {
+                    mod_overview.set(list_app_mods(&base_id, &install_dir).await);
+                });
+            }
+            || ()
+        });
+    }
+
+    let on_open_mods = {
+        let mods_for = mods_for.clone();
+        let mod_overview = mod_overview.clone();
+        Callback::from(move |app: AppInfo| {
+            mod_overview.set(ModOverview::default());
+            mods_for.set(Some(app));
+        })
+    };
+
+    let on_close_mods = {
+        let mods_for = mods_for.clone();
+        Callback::from(move |_| mods_for.set(None))
+    };
+
+    let on_apply_mods = {
+        let install_dir = install_dir.clone();
+        let mods_for = mods_for.clone();
+        let mod_overview = mod_overview.clone();
+        let mods_busy = mods_busy.clone();
+        let toast = toast.clone();
+        Callback::from(move |order: Vec<(String, bool)>| {
+            let Some(app) = (*mods_for).clone() else {
+                return;
+            };
+            let install_dir = (*install_dir).clone();
+            let mod_overview = mod_overview.clone();
+            let mods_busy = mods_busy.clone();
+            let toast = toast.clone();
+            let mods: Vec<_> = order
+                .into_iter()
+                .map(|(id, enabled)| serde_json::json!({ "id": id, "enabled": enabled }))
+                .collect();
+            mods_busy.set(true);
+            spawn_local(async move {
+                let payload = serde_wasm_bindgen::to_value(&serde_json::json!({
+                    "request": { "id": app.id, "destDir": install_dir, "mods": mods }
+                }))
+                .unwrap_or(JsValue::NULL);
+                match invoke_safe("set_app_mods", payload).await {
+                    Ok(value) => {
+                        if let Ok(overview) = serde_wasm_bindgen::from_value(value) {
+                            mod_overview.set(overview);
+                        }
+                    }
+                    Err(err) => {
+                        let message = err
+                            .as_string()
+                            .unwrap_or_else(|| "Failed to apply mods.".to_string());
+                        toast.toast(message, ToastVariant::Error, Some(3000));
+                    }
+                }
+                mods_busy.set(false);
+            });
+        })
+    };
+
+    let on_remove_mod = {
+        let install_dir = install_dir.clone();
+        let mods_for = mods_for.clone();
+        let mod_overview = mod_overview.clone();
+        let mods_busy = mods_busy.clone();
+        let installed = installed.clone();
+        let downloads = downloads.clone();
+        let toast = toast.clone();
+        Callback::from(move |mod_id: String| {
+            let Some(app) = (*mods_for).clone() else {
+                return;
+            };
+            let install_dir = (*install_dir).clone();
+            let mod_overview = mod_overview.clone();
+            let mods_busy = mods_busy.clone();
+            let installed = installed.clone();
+            let downloads = downloads.clone();
+            let toast = toast.clone();
+            mods_busy.set(true);
+            spawn_local(async move {
+                let payload = serde_wasm_bindgen::to_value(&serde_json::json!({
+                    "request": { "id": app.id, "modId": mod_id.clone(), "destDir": install_dir }
+                }))
+                .unwrap_or(JsValue::NULL);
+                match invoke_safe("remove_app_mod", payload).await {
+                    Ok(value) => {
+                        let mut next = (*installed).clone();
+                        next.remove(&mod_id);
+                        installed.set(next);
+                        let mut downloads_next = (*downloads).clone();
+                        downloads_next.remove(&mod_id);
+                        downloads.set(downloads_next);
+                        if let Ok(overview) = serde_wasm_bindgen::from_value(value) {
+                            mod_overview.set(overview);
+                        }
+                    }
+                    Err(err) => {
+                        let message = err
+                            .as_string()
+                            .unwrap_or_else(|| "Failed to remove mod.".to_string());
+                        toast.toast(message, ToastVariant::Error, Some(3000));
+                    }
+                }
+                mods_busy.set(false);
+            });
+        })
+    };
+
+    let on_open_snapshots = {
+        let snapshots_for = snapshots_for.clone();
+        let snapshots = snapshots.clone();
+        let snapshots_loading = snapshots_loading.clone();
+        Callback::from(move |app: AppInfo| {
+            let snapshots = snapshots.clone();
+            let snapshots_loading = snapshots_loading.clone();
+            let id = app.id.clone();
+            snapshots_for.set(Some(app));
+            snapshots.set(Vec::new());
+            snapshots_loading.set(true);
+            spawn_local(async move {
+                snapshots.set(list_save_snapshots(&id).await);
+                snapshots_loading.set(false);
+            });
+        })
+    };
+
+    let on_close_snapshots = {
+        let snapshots_for = snapshots_for.clone();
+        Callback::from(move |_| snapshots_for.set(None))
+    };
+
+    let on_restore_snapshot = {
+        let install_dir = install_dir.clone();
+        let snapshots_for = snapshots_for.clone();
+        let snapshots = snapshots.clone();
+        let toast = toast.clone();
+        let confirm = confirm.clone();
+        Callback::from(move |name: String| {
+            let Some(app) = (*snapshots_for).clone() else {
+                return;
+            };
+            let install_dir = (*install_dir).clone();
+            let snapshots = snapshots.clone();
+            let toast = toast.clone();
+            confirm.confirm(ConfirmRequest {
+                title: "Restore save".into(),
+                message: format!(
+                    "Replace the current save of {} with this snapshot? The current save is snapshotted first.",
+                    app.name
+                ),
+                confirm_label: "Restore".into(),
+                cancel_label: "Cancel".into(),
+                on_confirm: Callback::from(move |_| {
+                    let id = app.id.clone();
+                    let name = name.clone();
+                    let install_dir = install_dir.clone();
+                    let snapshots = snapshots.clone();
+                    let toast = toast.clone();
+                    spawn_local(async move {
+                        let payload = serde_wasm_bindgen::to_value(&serde_json::json!({
+                            "request": { "id": id.clone(), "destDir": install_dir, "name": name }
+                        }))
+                        .unwrap_or(JsValue::NULL);
+                        match invoke_safe("restore_save_snapshot", payload).await {
+                            Ok(_) => {
+                                toast.toast("Save restored.", ToastVariant::Success, Some(2500))
+                            }
+                            Err(err) => {
+                                let message = err
+                                    .as_string()
+                                    .unwrap_or_else(|| "Failed to restore snapshot.".to_string());
+                                toast.toast(message, ToastVariant::Error, Some(3000));
+                            }
+                        }
+                        snapshots.set(list_save_snapshots(&id).await);
+                    });
+                }),
+            });
+        })
+    };
+
     let on_pause = {
         let downloads = downloads.clone();
         Callback::from(move |id: String| {
@@ -813,101 +1961,235 @@ pub fn library_screen() -> Html {
         })
     };
 
-    let on_run_app = {
+    let launch_app = {
         let install_dir = install_dir.clone();
         let toast = toast.clone();
         let server_ip = server_ip.clone();
         let server_port = server_port.clone();
         let token = token.clone();
-        Callback::from(move |(id, executable, name): (String, String, String)| {
+        Callback::from(
+            move |(id, executable, name, profile): (String, String, String, Option<String>)| {
+                let install_dir = (*install_dir).clone();
+                let toast = toast.clone();
+                let server_ip = server_ip.clone();
+                let server_port = server_port.clone();
+                let token = token.clone();
+                let app_name = name.clone();
+                if install_dir.trim().is_empty() {
+                    toast.toast(
+                        "No install folder configured.",
+                        ToastVariant::Warning,
+                        Some(2500),
+                    );
+                    return;
+                }
+                if executable.trim().is_empty() && profile.is_none() {
+                    toast.toast(
+                        "Executable not configured for this app.",
+                        ToastVariant::Warning,
+                        Some(2500),
+                    );
+                    return;
+                }
+                spawn_local(async move {
+                    let app_id = id.clone();
+                    if !server_ip.trim().is_empty()
+                        && !server_port.trim().is_empty()
+                        && !token.trim().is_empty()
+                    {
+                        let status_url =
+                            build_http_url(server_ip.trim(), server_port.trim(), "social/status");
+                        let body = serde_json::json!({
+                            "status": "playing",
+                            "app_id": app_id,
+                            "app_name": app_name
+                        });
+                        let _ =
+                            send_json("POST", &status_url, Some(token.trim()), Some(body)).await;
+                    }
+                    let payload = serde_wasm_bindgen::to_value(&serde_json::json!({
+                        "request": {
+                            "id": id,
+                            "destDir": install_dir,
+                            "executable": executable,
+                            "profile": profile
+                        }
+                    }))
+                    .unwrap_or(JsValue::NULL);
+                    let result = match invoke_safe("run_app_executable_tracked", payload).await {
+                        Ok(value) => value,
+                        Err(err) => {
+                            let message = err
+                                .as_string()
+                                .unwrap_or_else(|| "Failed to launch executable.".to_string());
+                            toast.toast(&message, ToastVariant::Error, Some(3000));
+                            if !server_ip.trim().is_empty()
+                                && !server_port.trim().is_empty()
+                                && !token.trim().is_empty()
+                            {
+                                let status_url = build_http_url(
+                                    server_ip.trim(),
+                                    server_port.trim(),
+                                    "social/status",
+                                );
+                                let body = serde_json::json!({ "status": "online" });
+                                let _ =
+                                    send_json("POST", &status_url, Some(token.trim()), Some(body))
+                                        .await;
+                            }
+                            return;
+                        }
+                    };
+                    if let Ok(result) = serde_wasm_bindgen::from_value::<RunStartResult>(result) {
+                        if !result.tracked {
+                            toast.toast(
+                                "Launched via default handler (not trackable).",
+                                ToastVariant::Warning,
+                                Some(2500),
+                            );
+                        }
+                    }
+                });
+            },
+        )
+    };
+
+    // Checks the cloud save first; a newer or conflicting one is put to the
+    // player before the app starts. Without a server the launch just goes on.
+    let on_run_app = {
+        let install_dir = install_dir.clone();
+        let launch_app = launch_app.clone();
+        let save_prompt = save_prompt.clone();
+        Callback::from(move |args: (String, String, String, Option<String>)| {
             let install_dir = (*install_dir).clone();
-            let toast = toast.clone();
-            let server_ip = server_ip.clone();
-            let server_port = server_port.clone();
-            let token = token.clone();
-            let app_name = name.clone();
+            let launch_app = launch_app.clone();
+            let save_prompt = save_prompt.clone();
             if install_dir.trim().is_empty() {
-                toast.toast(
-                    "No install folder configured.",
-                    ToastVariant::Warning,
-                    Some(2500),
-                );
-                return;
-            }
-            if executable.trim().is_empty() {
-                toast.toast(
-                    "Executable not configured for this app.",
-                    ToastVariant::Warning,
-                    Some(2500),
-                );
+                launch_app.emit(args);
                 return;
             }
             spawn_local(async move {
-                let app_id = id.clone();
-                if !server_ip.trim().is_empty()
-                    && !server_port.trim().is_empty()
-                    && !token.trim().is_empty()
-                {
-                    let status_url =
-                        build_http_url(server_ip.trim(), server_port.trim(), "social/status");
-                    let body = serde_json::json!({
-                        "status": "playing",
-                        "app_id": app_id,
-                        "app_name": app_name
-                    });
-                    let _ = send_json("POST", &status_url, Some(token.trim()), Some(body)).await;
-                }
                 let payload = serde_wasm_bindgen::to_value(&serde_json::json!({
-                    "request": {
-                        "id": id,
-                        "destDir": install_dir,
-                        "executable": executable
+                    "request": { "id": args.0.clone(), "destDir": install_dir }
+                }))
+                .unwrap_or(JsValue::NULL);
+                let status = invoke_safe("check_cloud_save", payload)
+                    .await
+                    .ok()
+                    .and_then(|value| {
+                        serde_wasm_bindgen::from_value::<CloudSaveStatus>(value).ok()
+                    });
+                match status {
+                    Some(CloudSaveStatus {
+                        state,
+                        cloud: Some(cloud),
+                    }) if state == "cloud_newer" || state == "conflict" => {
+                        save_prompt.set(Some(SavePrompt {
+                            id: args.0.clone(),
+                            conflict: state == "conflict",
+                            cloud,
+                            launch: Some(args),
+                        }));
                     }
+                    _ => launch_app.emit(args),
+                }
+            });
+        })
+    };
+
+    let on_use_cloud_save = {
+        let install_dir = install_dir.clone();
+        let save_prompt = save_prompt.clone();
+        let save_busy = save_busy.clone();
+        let launch_app = launch_app.clone();
+        let toast = toast.clone();
+        Callback::from(move |_| {
+            let Some(prompt) = (*save_prompt).clone() else {
+                return;
+            };
+            let install_dir = (*install_dir).clone();
+            let save_prompt = save_prompt.clone();
+            let save_busy = save_busy.clone();
+            let launch_app = launch_app.clone();
+            let toast = toast.clone();
+            save_busy.set(true);
+            spawn_local(async move {
+                let payload = serde_wasm_bindgen::to_value(&serde_json::json!({
+                    "request": { "id": prompt.id, "destDir": install_dir }
                 }))
                 .unwrap_or(JsValue::NULL);
-                let result = match invoke_safe("run_app_executable_tracked", payload).await {
-                    Ok(value) => value,
+                match invoke_safe("restore_cloud_save", payload).await {
+                    Ok(_) => {
+                        toast.toast("Cloud save restored.", ToastVariant::Success, Some(2500));
+                        if let Some(args) = prompt.launch {
+                            launch_app.emit(args);
+                        }
+                    }
                     Err(err) => {
                         let message = err
                             .as_string()
-                            .unwrap_or_else(|| "Failed to launch executable.".to_string());
-                        toast.toast(&message, ToastVariant::Error, Some(3000));
-                        if !server_ip.trim().is_empty()
-                            && !server_port.trim().is_empty()
-                            && !token.trim().is_empty()
-                        {
-                            let status_url = build_http_url(
-                                server_ip.trim(),
-                                server_port.trim(),
-                                "social/status",
-                            );
-                            let body = serde_json::json!({ "status": "online" });
-                            let _ = send_json("POST", &status_url, Some(token.trim()), Some(body))
-                                .await;
-                        }
-                        return;
+                            .unwrap_or_else(|| "Failed to restore the cloud save.".to_string());
+                        toast.toast(message, ToastVariant::Error, Some(3000));
                     }
-                };
-                if let Ok(result) = serde_wasm_bindgen::from_value::<RunStartResult>(result) {
-                    if !result.tracked {
-                        toast.toast(
-                            "Launched via default handler (not trackable).",
-                            ToastVariant::Warning,
-                            Some(2500),
-                        );
+                }
+                save_busy.set(false);
+                save_prompt.set(None);
+            });
+        })
+    };
+
+    let on_keep_local_save = {
+        let install_dir = install_dir.clone();
+        let save_prompt = save_prompt.clone();
+        let save_busy = save_busy.clone();
+        let launch_app = launch_app.clone();
+        let toast = toast.clone();
+        Callback::from(move |_| {
+            let Some(prompt) = (*save_prompt).clone() else {
+                return;
+            };
+            let install_dir = (*install_dir).clone();
+            let save_prompt = save_prompt.clone();
+            let save_busy = save_busy.clone();
+            let launch_app = launch_app.clone();
+            let toast = toast.clone();
+            save_busy.set(true);
+            spawn_local(async move {
+                // A conflict stays one until the cloud save is replaced; a
+                // merely older local save is uploaded after the next session.
+                if prompt.conflict {
+                    let payload = serde_wasm_bindgen::to_value(&serde_json::json!({
+                        "request": { "id": prompt.id, "destDir": install_dir }
+                    }))
+                    .unwrap_or(JsValue::NULL);
+                    if let Err(err) = invoke_safe("upload_cloud_save", payload).await {
+                        let message = err
+                            .as_string()
+                            .unwrap_or_else(|| "Failed to upload the save.".to_string());
+                        toast.toast(message, ToastVariant::Error, Some(3000));
                     }
                 }
+                if let Some(args) = prompt.launch {
+                    launch_app.emit(args);
+                }
+                save_busy.set(false);
+                save_prompt.set(None);
             });
         })
     };
 
+    let on_close_save_prompt = {
+        let save_prompt = save_prompt.clone();
+        Callback::from(move |_| save_prompt.set(None))
+    };
+
     let on_stop_app = {
         let toast = toast.clone();
-        Callback::from(move |id: String| {
+        Callback::from(move |(id, force): (String, bool)| {
             let toast = toast.clone();
             spawn_local(async move {
                 let payload = serde_wasm_bindgen::to_value(&serde_json::json!({
-                    "request": { "id": id }
+                    "request": { "id": id, "force": force }
                 }))
                 .unwrap_or(JsValue::NULL);
                 let result = invoke_safe("stop_app_executable_tracked", payload).await;
@@ -921,6 +2203,107 @@ pub fn library_screen() -> Html {
         })
     };
 
+    // Runs a command-line launch once the app list and install folder are
+    // known, through the same path as the Run button.
+    {
+        let pending_launch = pending_launch.clone();
+        let apps = apps.clone();
+        let installed = installed.clone();
+        let install_dir = install_dir.clone();
+        let running = running.clone();
+        let on_run_app = on_run_app.clone();
+        let toast = toast.clone();
+        use_effect_with(
+            (
+                (*pending_launch).clone(),
+                (*apps).clone(),
+                (*installed).clone(),
+                (*install_dir).clone(),
+            ),
+            move |(request, list, installed_ids, dest_dir)| {
+                if let Some(request) = request.clone() {
+                    if !list.is_empty() && !dest_dir.trim().is_empty() {
+                        pending_launch.set(None);
+                        match list.iter().find(|app| app.id == request.id) {
+                            None => toast.toast(
+                                format!("No app with id \"{}\" to run.", request.id),
+                                ToastVariant::Error,
+                                Some(3000),
+                            ),
+                            Some(app) if !installed_ids.contains(&app.id) => toast.toast(
+                                format!("{} is not installed.", app.name),
+                                ToastVariant::Warning,
+                                Some(3000),
+                            ),
+                            Some(app) if running.borrow().contains_key(&app.id) => toast.toast(
+                                format!("{} is already running.", app.name),
+                                ToastVariant::Info,
+                                Some(2500),
+                            ),
+                            Some(app) => {
+                                let executable = app.executable.clone().unwrap_or_default();
+                                // Same default as the Run button.
+                                let profile = request.profile.or_else(|| {
+                                    if executable.trim().is_empty() {
+                                        app.launch_profiles
+                                            .first()
+                                            .map(|profile| profile.name.clone())
+                                    } else {
+                                        None
+                                    }
+                                });
+                                on_run_app.emit((
+                                    app.id.clone(),
+                                    executable,
+                                    app.name.clone(),
+                                    profile,
+                                ));
+                            }
+                        }
+                    }
+                }
+                || ()
+            },
+        );
+    }
+
+    let on_create_shortcut = {
+        let toast = toast.clone();
+        Callback::from(move |app: AppInfo| {
+            let toast = toast.clone();
+            let executable = app.executable.clone().unwrap_or_default();
+            let profile = if executable.trim().is_empty() {
+                app.launch_profiles
+                    .first()
+                    .map(|profile| profile.name.clone())
+            } else {
+                None
+            };
+            spawn_local(async move {
+                let payload = serde_wasm_bindgen::to_value(&serde_json::json!({
+                    "request": { "id": app.id, "name": app.name, "profile": profile }
+                }))
+                .unwrap_or(JsValue::NULL);
+                match invoke_safe("create_app_shortcut", payload).await {
+                    Ok(value) => {
+                        let path = value.as_string().unwrap_or_default();
+                        toast.toast(
+                            format!("Shortcut created: {}", path),
+                            ToastVariant::Success,
+                            Some(4000),
+                        );
+                    }
+                    Err(err) => {
+                        let message = err
+                            .as_string()
+                            .unwrap_or_else(|| "Failed to create shortcut.".to_string());
+                        toast.toast(message, ToastVariant::Error, Some(3000));
+                    }
+                }
+            });
+        })
+    };
+
     html! {
         <div class="h-full">
             <div class="flex items-center justify-between gap-4">
@@ -991,6 +2374,7 @@ pub fn library_screen() -> Html {
                         let mut items: Vec<_> = apps
                             .iter()
                             .cloned()
+                            .filter(|app| app.base_app.is_none())
                             .filter(|app| {
                                 let query = (*search).trim().to_lowercase();
                                 if query.is_empty() {
@@ -1017,6 +2401,7 @@ pub fn library_screen() -> Html {
                         let is_installed = (*installed).contains(&app.id)
                             || status.status == "completed";
                         let on_download = on_download.clone();
+                        let on_open_versions = on_open_versions.clone();
                         let on_pause = on_pause.clone();
                         let on_resume = on_resume.clone();
                         let on_cancel = on_cancel.clone();
@@ -1028,10 +2413,22 @@ pub fn library_screen() -> Html {
                         let app_id_resume = app.id.clone();
                         let app_id_cancel = app.id.clone();
                         let app_for_download = app.clone();
+                        let app_for_versions = app.clone();
                         let app_for_remove = app.clone();
+                        let app_for_settings = app.clone();
+                        let app_for_logs = app.clone();
+                        let on_open_logs = on_open_logs.clone();
+                        let on_open_mods = on_open_mods.clone();
+                        let app_for_mods = app.clone();
+                        let has_mods = apps
+                            .iter()
+                            .any(|other| other.base_app.as_deref() == Some(app.id.as_str()));
+                        let on_open_settings = on_open_settings.clone();
                         let app_for_open = app.clone();
                         let app_for_run = app.clone();
                         let app_for_stop = app.clone();
+                        let app_for_menu = app.clone();
+                        let launch_menu = launch_menu.clone();
                         let playtime_label = (*playtime)
                             .get(&app.id)
                             .map(|entry| format_playtime(entry.total_seconds))
@@ -1039,12 +2436,17 @@ pub fn library_screen() -> Html {
                         let is_busy = (*downloads)
                             .values()
                             .any(|d| d.status == "downloading" || d.status == "paused");
-                        let has_exec = app
-                            .executable
-                            .as_ref()
-                            .map(|value| !value.trim().is_empty())
-                            .unwrap_or(false);
-                        let is_running = (*running).contains(&app.id);
+                        let default_exec = app.executable.clone().unwrap_or_default();
+                        let has_default_exec = !default_exec.trim().is_empty();
+                        let has_exec = has_default_exec || !app.launch_profiles.is_empty();
+                        let run_view = running.borrow().get(&app.id).copied();
+                        let is_running = run_view.is_some();
+                        let is_stopping = run_view.is_some_and(|view| view.stopping);
+                        let live_stats = if is_running {
+                            run_stats.borrow().get(&app.id).cloned()
+                        } else {
+                            None
+                        };
                         let action = if status.status == "installing" {
                             html! {
                                 <div class="flex items-center gap-2">
@@ -1094,22 +2496,96 @@ pub fn library_screen() -> Html {
                         } else if is_installed {
                             let primary = if has_exec {
                                 if is_running {
+                                    let stop_id = app_for_stop.id.clone();
+                                    let on_force_stop = on_stop_app.clone();
                                     html! {
-                                        <Button
-                                            class={Some("border border-rose-400/60 bg-rose-500/20 text-rose-100 hover:bg-rose-500/30".to_string())}
-                                            onclick={Callback::from(move |_| on_stop_app.emit(app_for_stop.id.clone()))}
-                                        >
-                                            { "Stop" }
-                                        </Button>
+                                        <div class="flex items-center gap-2">
+                                            <Button
+                                                class={Some("border border-rose-400/60 bg-rose-500/20 text-rose-100 hover:bg-rose-500/30".to_string())}
+                                                onclick={Callback::from(move |_| on_stop_app.emit((app_for_stop.id.clone(), false)))}
+                                                disabled={is_stopping}
+                                            >
+                                                { if is_stopping { "Stopping..." } else { "Stop" } }
+                                            </Button>
+                                            <Button
+                                                class={Some("border border-rose-400/60 bg-rose-500/10 text-rose-100 hover:bg-rose-500/30".to_string())}
+                                                onclick={Callback::from(move |_| on_force_stop.emit((stop_id.clone(), true)))}
+                                            >
+                                                { "Force stop" }
+                                            </Button>
+                                        </div>
                                     }
                                 } else {
+                                    // Run starts the plain executable, or the first
+                                    // profile when the app only defines profiles.
+                                    let default_profile = if has_default_exec {
+                                        None
+                                    } else {
+                                        app.launch_profiles.first().map(|profile| profile.name.clone())
+                                    };
+                                    let menu_open = (*launch_menu).as_deref() == Some(app.id.as_str());
+                                    let mut entries: Vec<(String, Option<String>)> = Vec::new();
+                                    if has_default_exec {
+                                        entries.push(("Default".to_string(), None));
+                                    }
+                                    for profile in &app.launch_profiles {
+                                        entries.push((profile.name.clone(), Some(profile.name.clone())));
+                                    }
+                                    let on_run_default = {
+                                        let on_run_app = on_run_app.clone();
+                                        let app = app_for_run.clone();
+                                        let executable = default_exec.clone();
+                                        Callback::from(move |_| on_run_app.emit((app.id.clone(), executable.clone(), app.name.clone(), default_profile.clone())))
+                                    };
+                                    let on_toggle_menu = {
+                                        let launch_menu = launch_menu.clone();
+                                        let id = app.id.clone();
+                                        Callback::from(move |_| {
+                                            if (*launch_menu).as_deref() == Some(id.as_str()) {
+                                                launch_menu.set(None);
+                                            } else {
+                                                launch_menu.set(Some(id.clone()));
+                                            }
+                                        })
+                                    };
                                     html! {
-                                        <Button
-                                            class={Some("border border-accent/50 bg-accent/20 text-secondary hover:bg-accent/30".to_string())}
-                                            onclick={Callback::from(move |_| on_run_app.emit((app_for_run.id.clone(), app_for_run.executable.clone().unwrap_or_default(), app_for_run.name.clone())))}
-                                        >
-                                            { "Run" }
-                                        </Button>
+                                        <div class="relative flex items-center gap-1">
+                                            <Button
+                                                class={Some("border border-accent/50 bg-accent/20 text-secondary hover:bg-accent/30".to_string())}
+                                                onclick={on_run_default}
+                                            >
+                                                { "Run" }
+                                            </Button>
+                                            if entries.len() > 1 {
+                                                <Button
+                                                    class={Some("border border-accent/50 bg-accent/20 px-2 text-secondary hover:bg-accent/30".to_string())}
+                                                    onclick={on_toggle_menu}
+                                                >
+                                                    { "\u{25BE}" }
+                                                </Button>
+                                            }
+                                            if menu_open {
+                                                <div class="absolute bottom-full left-0 z-20 mb-2 min-w-[12rem] rounded-xl border border-ink/50 bg-inkLight p-1 shadow-xl">
+                                                    { for entries.into_iter().map(|(label, profile)| {
+                                                        let on_run_app = on_run_app.clone();
+                                                        let launch_menu = launch_menu.clone();
+                                                        let app = app_for_menu.clone();
+                                                        let executable = default_exec.clone();
+                                                        html! {
+                                                            <button
+                                                                class="block w-full rounded-lg px-3 py-2 text-left text-sm text-secondary hover:bg-ink/40"
+                                                                onclick={Callback::from(move |_| {
+                                                                    launch_menu.set(None);
+                                                                    on_run_app.emit((app.id.clone(), executable.clone(), app.name.clone(), profile.clone()));
+                                                                })}
+                                                            >
+                                                                { label }
+                                                            </button>
+                                                        }
+                                                    }) }
+                                                </div>
+                                            }
+                                        </div>
                                     }
                                 }
                             } else {
@@ -1125,6 +2601,28 @@ pub fn library_screen() -> Html {
                             html! {
                                 <div class="flex items-center gap-2">
                                     { primary }
+                                    if has_exec {
+                                        <Button
+                                            class={Some("border border-ink/50 bg-ink/40 text-secondary hover:bg-ink/50".to_string())}
+                                            onclick={Callback::from(move |_| on_open_settings.emit(app_for_settings.clone()))}
+                                        >
+                                            { "Settings" }
+                                        </Button>
+                                        <Button
+                                            class={Some("border border-ink/50 bg-ink/40 text-secondary hover:bg-ink/50".to_string())}
+                                            onclick={Callback::from(move |_| on_open_logs.emit(app_for_logs.clone()))}
+                                        >
+                                            { "Logs" }
+                                        </Button>
+                                    }
+                                    if has_mods {
+                                        <Button
+                                            class={Some("border border-ink/50 bg-ink/40 text-secondary hover:bg-ink/50".to_string())}
+                                            onclick={Callback::from(move |_| on_open_mods.emit(app_for_mods.clone()))}
+                                        >
+                                            { "Mods" }
+                                        </Button>
+                                    }
                                     <Button
                                         class={Some("border border-rose-400/60 bg-rose-500/20 text-rose-100 hover:bg-rose-500/30".to_string())}
                                         onclick={Callback::from(move |_| on_remove.emit(app_for_remove.id.clone()))}
@@ -1135,13 +2633,24 @@ pub fn library_screen() -> Html {
                             }
                         } else {
                             html! {
-                                <Button
-                                    class={Some("border border-primary/60 bg-primary/30 text-secondary hover:bg-primary/40".to_string())}
-                                    onclick={Callback::from(move |_| on_download.emit(app_for_download.clone()))}
-                                    disabled={!app.has_archive || is_busy}
-                                >
-                                    { if !app.has_archive { "Unavailable" } else if is_busy { "Busy" } else { "Download" } }
-                                </Button>
+                                <div class="flex items-center gap-2">
+                                    <Button
+                                        class={Some("border border-primary/60 bg-primary/30 text-secondary hover:bg-primary/40".to_string())}
+                                        onclick={Callback::from(move |_| on_download.emit((app_for_download.clone(), None)))}
+                                        disabled={!app.has_archive || is_busy}
+                                    >
+                                        { if !app.has_archive { "Unavailable" } else if is_busy { "Busy" } else { "Download" } }
+                                    </Button>
+                                    if app.has_archive {
+                                        <Button
+                                            class={Some("border border-ink/50 bg-ink/40 text-secondary hover:bg-ink/50".to_string())}
+                                            onclick={Callback::from(move |_| on_open_versions.emit(app_for_versions.clone()))}
+                                            disabled={is_busy}
+                                        >
+                                            { "Versions" }
+                                        </Button>
+                                    }
+                                </div>
                             }
                         };
 
@@ -1176,12 +2685,34 @@ pub fn library_screen() -> Html {
                                 <p class="mt-4 text-lg font-semibold">{ app.name.clone() }</p>
                                 <p class="mt-2 text-sm text-secondary/70">{ app.description.clone() }</p>
                                 <div class="mt-4 flex items-center justify-between text-xs text-secondary/60">
-                                    <span>{ format!("Version {}", if app.version.is_empty() { "-" } else { &app.version }) }</span>
+                                    <span>{ match (*pinned).get(&app.id).filter(|_| is_installed) {
+                                        Some(label) => format!("Version {} (pinned)", label),
+                                        None => format!("Version {}", if app.version.is_empty() { "-" } else { &app.version }),
+                                    } }</span>
                                     <span>{ format_size(app.archive_size) }</span>
                                 </div>
                                 <div class="mt-2 text-xs text-secondary/60">
                                     { format!("Playtime: {}", playtime_label) }
+                                    if let Some(view) = run_view {
+                                        <span class="ml-2 text-primary">
+                                            { format!("Running for {}", format_elapsed(js_sys::Date::now() / 1000.0 - view.started_at)) }
+                                        </span>
+                                    }
                                 </div>
+                                if let Some((sampled_at, stats)) = live_stats {
+                                    <div class="mt-3">
+                                        <DownloadSpeedGraph
+                                            points={vec![(sampled_at, stats.cpu_percent)]}
+                                            paused={false}
+                                            window_seconds={60.0}
+                                        />
+                                        <div class="mt-2 flex items-center justify-between text-xs text-secondary/60">
+                                            <span>{ format!("CPU {:.0}%", stats.cpu_percent) }</span>
+                                            <span>{ format!("Memory {}", format_size(stats.memory_bytes as i64)) }</span>
+                                            <span>{ format!("{} threads", stats.threads) }</span>
+                                        </div>
+                                    </div>
+                                }
                                 <div class="mt-auto flex items-center justify-between">
                                     { action }
                                     if !progress.is_empty() && !status_label.is_empty() {
@@ -1204,10 +2735,615 @@ pub fn library_screen() -> Html {
                     }) }
                 </div>
             }
+            if let Some(app) = (*settings_for).clone() {
+                <div class="fixed inset-0 z-50 flex items-center justify-center">
+                    <div class="absolute inset-0 bg-ink" onclick={on_close_settings.clone()} />
+                    <div class="relative w-[min(94vw,40rem)] max-h-[90vh] overflow-hidden rounded-2xl border border-ink/50 bg-inkLight shadow-2xl">
+                        <div class="flex items-center justify-between border-b border-ink/40 px-6 py-4">
+                            <div>
+                                <h2 class="text-lg font-semibold text-secondary">{ format!("{} launch settings", app.name) }</h2>
+                                <p class="text-xs text-accent">{ "Stored on this computer only." }</p>
+                            </div>
+                            <Button
+                                class={Some("border border-ink/50 bg-ink/40 text-secondary hover:bg-ink/50".to_string())}
+                                onclick={on_close_settings.clone()}
+                            >
+                                { "Close" }
+                            </Button>
+                        </div>
+                        <div class="max-h-[calc(90vh-9rem)] overflow-y-auto px-6 py-5 scrollbar-thin flex flex-col gap-4">
+                            <div>
+                                <label class="text-xs uppercase tracking-wide text-accent/80">{ "Extra arguments" }</label>
+                                <input
+                                    class="mt-2 w-full rounded border border-ink/50 bg-ink/50 px-4 py-2 text-secondary placeholder:text-secondary/60 outline outline-1 outline-accent/50 focus:outline-none focus:ring-2 focus:ring-primary/40"
+                                    type="text"
+                                    placeholder="-windowed -novid"
+                                    value={(*settings_args).clone()}
+                                    oninput={on_input_value(settings_args.clone())}
+                                />
+                            </div>
+                            <div>
+                                <label class="text-xs uppercase tracking-wide text-accent/80">{ "Environment variables" }</label>
+                                <textarea
+                                    class="mt-2 h-28 resize-none font-mono text-sm w-full rounded border border-ink/50 bg-ink/50 px-4 py-2 text-secondary placeholder:text-secondary/60 outline outline-1 outline-accent/50 focus:outline-none focus:ring-2 focus:ring-primary/40"
+                                    placeholder="NAME=value, one per line"
+                                    value={(*settings_env).clone()}
+                                    oninput={on_textarea_value(settings_env.clone())}
+                                />
+                            </div>
+                            <div>
+                                <label class="text-xs uppercase tracking-wide text-accent/80">{ "Save snapshots" }</label>
+                                <textarea
+                                    class="mt-2 h-20 resize-none font-mono text-sm w-full rounded border border-ink/50 bg-ink/50 px-4 py-2 text-secondary placeholder:text-secondary/60 outline outline-1 outline-accent/50 focus:outline-none focus:ring-2 focus:ring-primary/40"
+                                    placeholder="Extra save files or folders, relative to the app folder, one per line"
+                                    value={(*settings_save_paths).clone()}
+                                    oninput={on_textarea_value(settings_save_paths.clone())}
+                                />
+                                <div class="mt-2 flex items-center gap-3">
+                                    <Button
+                                        class={Some("border border-accent/50 bg-accent/20 text-secondary hover:bg-accent/30".to_string())}
+                                        onclick={{
+                                            let on_open_snapshots = on_open_snapshots.clone();
+                                            let app = app.clone();
+                                            Callback::from(move |_| on_open_snapshots.emit(app.clone()))
+                                        }}
+                                    >
+                                        { "Restore save" }
+                                    </Button>
+                                </div>
+                                <p class="mt-2 text-xs text-secondary/60">
+                                    { "Saves are snapshotted before every update and uninstall, along with the app's own save locations." }
+                                </p>
+                            </div>
+                            <div>
+                                <label class="text-xs uppercase tracking-wide text-accent/80">{ "Wrapper command" }</label>
+                                <select
+                                    class="mt-2 w-full rounded border border-ink/50 bg-ink/50 px-4 py-2 text-secondary placeholder:text-secondary/60 outline outline-1 outline-accent/50 focus:outline-none focus:ring-2 focus:ring-primary/40"
+                                    onchange={on_select_value(settings_wrapper_mode.clone())}
+                                >
+                                    <option value="default" selected={settings_wrapper_mode.as_str() == "default"}>{ "Use default wrapper" }</option>
+                                    <option value="none" selected={settings_wrapper_mode.as_str() == "none"}>{ "No wrapper" }</option>
+                                    <option value="custom" selected={settings_wrapper_mode.as_str() == "custom"}>{ "Custom wrapper" }</option>
+                                </select>
+                                if settings_wrapper_mode.as_str() == "custom" {
+                                    <input
+                                        class="mt-2 w-full rounded border border-ink/50 bg-ink/50 px-4 py-2 text-secondary placeholder:text-secondary/60 outline outline-1 outline-accent/50 focus:outline-none focus:ring-2 focus:ring-primary/40"
+                                        type="text"
+                                        placeholder="gamemoderun"
+                                        value={(*settings_wrapper).clone()}
+                                        oninput={on_input_value(settings_wrapper.clone())}
+                                    />
+                                }
+                            </div>
+                            <div>
+                                <label class="text-xs uppercase tracking-wide text-accent/80">{ "Default wrapper (all apps)" }</label>
+                                <input
+                                    class="mt-2 w-full rounded border border-ink/50 bg-ink/50 px-4 py-2 text-secondary placeholder:text-secondary/60 outline outline-1 outline-accent/50 focus:outline-none focus:ring-2 focus:ring-primary/40"
+                                    type="text"
+                                    placeholder="e.g. gamemoderun or env VAR=1 %command%"
+                                    value={(*settings_default_wrapper).clone()}
+                                    oninput={on_input_value(settings_default_wrapper.clone())}
+                                />
+                                <p class="mt-2 text-xs text-secondary/60">
+                                    { "The app command is appended to the wrapper, or placed where %command% appears." }
+                                </p>
+                            </div>
+                            <div>
+                                <label class="text-xs uppercase tracking-wide text-accent/80">{ "Stop grace period (seconds, all apps)" }</label>
+                                <input
+                                    class="mt-2 w-full rounded border border-ink/50 bg-ink/50 px-4 py-2 text-secondary placeholder:text-secondary/60 outline outline-1 outline-accent/50 focus:outline-none focus:ring-2 focus:ring-primary/40"
+                                    type="number"
+                                    min="0"
+                                    placeholder="10"
+                                    value={(*settings_stop_grace).clone()}
+                                    oninput={on_input_value(settings_stop_grace.clone())}
+                                />
+                                <p class="mt-2 text-xs text-secondary/60">
+                                    { "How long Stop waits for the app to close before killing it." }
+                                </p>
+                            </div>
+                            <div>
+                                <label class="text-xs uppercase tracking-wide text-accent/80">{ "Shortcut" }</label>
+                                <div class="mt-2 flex items-center gap-3">
+                                    <Button
+                                        class={Some("border border-accent/50 bg-accent/20 text-secondary hover:bg-accent/30".to_string())}
+                                        onclick={{
+                                            let on_create_shortcut = on_create_shortcut.clone();
+                                            let app = app.clone();
+                                            Callback::from(move |_| on_create_shortcut.emit(app.clone()))
+                                        }}
+                                    >
+                                        { "Create desktop shortcut" }
+                                    </Button>
+                                </div>
+                                <p class="mt-2 text-xs text-secondary/60">
+                                    { format!("Scripts can start it by running Gaggle with --run {} [--profile <name>].", app.id) }
+                                </p>
+                            </div>
+                        </div>
+                        <div class="flex items-center justify-end gap-3 border-t border-ink/40 px-6 py-4">
+                            <Button
+                                class={Some("border border-ink/50 bg-ink/40 text-secondary hover:bg-ink/50".to_string())}
+                                onclick={on_close_settings.clone()}
+                            >
+                                { "Cancel" }
+                            </Button>
+                            <Button
+                                class={Some("border border-primary/60 bg-primary/30 text-secondary hover:bg-primary/40".to_string())}
+                                onclick={on_save_settings.clone()}
+                                disabled={*settings_saving}
+                            >
+                                { if *settings_saving { "Saving..." } else { "Save" } }
+                            </Button>
+                        </div>
+                    </div>
+                </div>
+            }
+            if let Some(app) = (*logs_for).clone() {
+                <div class="fixed inset-0 z-50 flex items-center justify-center">
+                    <div class="absolute inset-0 bg-ink" onclick={on_close_logs.clone()} />
+                    <div class="relative flex w-[min(96vw,64rem)] h-[85vh] flex-col overflow-hidden rounded-2xl border border-ink/50 bg-inkLight shadow-2xl">
+                        <div class="flex items-center justify-between border-b border-ink/40 px-6 py-4">
+                            <div>
+                                <h2 class="text-lg font-semibold text-secondary">{ format!("{} logs", app.name) }</h2>
+                                <p class="text-xs text-accent">{ "Output of the last runs, newest first." }</p>
+                            </div>
+                            <div class="flex items-center gap-2">
+                                <Button
+                                    class={Some("border border-accent/50 bg-accent/20 text-secondary hover:bg-accent/30".to_string())}
+                                    onclick={on_copy_last_log.clone()}
+                                    disabled={log_entries.is_empty()}
+                                >
+                                    { "Copy last log" }
+                                </Button>
+                                <Button
+                                    class={Some("border border-ink/50 bg-ink/40 text-secondary hover:bg-ink/50".to_string())}
+                                    onclick={on_close_logs.clone()}
+                                >
+                                    { "Close" }
+                                </Button>
+                            </div>
+                        </div>
+                        if log_entries.is_empty() && !*log_loading {
+                            <p class="px-6 py-5 text-sm text-secondary/70">{ "No logs recorded yet. Run the app to create one." }</p>
+                        } else {
+                            <div class="flex min-h-0 flex-1">
+                                <div class="w-56 shrink-0 overflow-y-auto border-r border-ink/40 p-2 scrollbar-thin">
+                                    { for log_entries.iter().map(|entry| {
+                                        let on_select_log = on_select_log.clone();
+                                        let name = entry.name.clone();
+                                        let selected = (*log_selected).as_deref() == Some(entry.name.as_str());
+                                        let modified = js_sys::Date::new(&JsValue::from_f64(entry.modified as f64 * 1000.0));
+                                        html! {
+                                            <button
+                                                class={if selected { "block w-full rounded-lg bg-ink/50 px-3 py-2 text-left" } else { "block w-full rounded-lg px-3 py-2 text-left hover:bg-ink/30" }}
+                                                onclick={Callback::from(move |_| on_select_log.emit(name.clone()))}
+                                            >
+                                                <p class="text-sm text-secondary">{ String::from(modified.to_locale_string("default", &JsValue::UNDEFINED)) }</p>
+                                                <p class="text-xs text-secondary/60">{ format_size(entry.size) }</p>
+                                            </button>
+                                        }
+                                    }) }
+                                </div>
+                                <pre class="min-w-0 flex-1 overflow-auto whitespace-pre-wrap break-all px-4 py-3 font-mono text-xs text-secondary/90 scrollbar-thin">
+                                    { if *log_loading { "Loading log...".to_string() } else { (*log_content).clone() } }
+                                </pre>
+                            </div>
+                        }
+                    </div>
+                </div>
+            }
+            if let Some(app) = (*mods_for).clone() {
+                <div class="fixed inset-0 z-50 flex items-center justify-center">
+                    <div class="absolute inset-0 bg-ink" onclick={on_close_mods.clone()} />
+                    <div class="relative w-[min(94vw,44rem)] max-h-[90vh] overflow-hidden rounded-2xl border border-ink/50 bg-inkLight shadow-2xl">
+                        <div class="flex items-center justify-between border-b border-ink/40 px-6 py-4">
+                            <div>
+                                <h2 class="text-lg font-semibold text-secondary">{ format!("{} mods", app.name) }</h2>
+                                <p class="text-xs text-accent">{ "Mods further down the load order win when two ship the same file." }</p>
+                            </div>
+                            <Button
+                                class={Some("border border-ink/50 bg-ink/40 text-secondary hover:bg-ink/50".to_string())}
+                                onclick={on_close_mods.clone()}
+                            >
+                                { "Close" }
+                            </Button>
+                        </div>
+                        <div class="max-h-[calc(90vh-5rem)] overflow-y-auto px-6 py-5 scrollbar-thin">
+                            <p class="text-xs uppercase tracking-wide text-accent/80">{ "Load order" }</p>
+                            if mod_overview.mods.is_empty() {
+                                <p class="mt-2 text-sm text-secondary/70">{ "No mods installed yet." }</p>
+                            } else {
+                                <div class="mt-2 flex flex-col gap-2">
+                                    { for mod_overview.mods.iter().enumerate().map(|(index, entry)| {
+                                        let order: Vec<(String, bool)> = mod_overview
+                                            .mods
+                                            .iter()
+                                            .map(|entry| (entry.id.clone(), entry.enabled))
+                                            .collect();
+                                        let last = order.len() - 1;
+                                        let apply = |order: Vec<(String, bool)>| {
+                                            let on_apply_mods = on_apply_mods.clone();
+                                            Callback::from(move |_| on_apply_mods.emit(order.clone()))
+                                        };
+                                        let mut toggled = order.clone();
+                                        toggled[index].1 = !toggled[index].1;
+                                        let on_toggle = apply(toggled);
+                                        let mut raised = order.clone();
+                                        raised.swap(index, index.saturating_sub(1));
+                                        let on_up = apply(raised);
+                                        let mut lowered = order;
+                                        lowered.swap(index, (index + 1).min(last));
+                                        let on_down = apply(lowered);
+                                        let on_remove_mod = on_remove_mod.clone();
+                                        let mod_id = entry.id.clone();
+                                        html! {
+                                            <div key={entry.id.clone()} class="flex items-center justify-between gap-3 rounded-xl border border-ink/50 bg-ink/40 px-4 py-3">
+                                                <div class="min-w-0">
+                                                    <p class="text-sm font-semibold">
+                                                        { format!("{}. {}", index + 1, entry.name) }
+                                                        if !entry.version.is_empty() {
+                                                            <span class="ml-2 text-xs text-secondary/60">{ format!("v{}", entry.version) }</span>
+                                                        }
+                                                    </p>
+                                                    <p class="text-xs text-secondary/70">
+                                                        { format!("{} files - {}", entry.files, if entry.enabled { "enabled" } else { "disabled" }) }
+                                                    </p>
+                                                </div>
+                                                <div class="flex items-center gap-2">
+                                                    <Button
+                                                        class={Some("border border-ink/50 bg-ink/40 text-secondary hover:bg-ink/50".to_string())}
+                                                        onclick={on_up}
+                                                        disabled={*mods_busy || index == 0}
+                                                    >
+                                                        { "Up" }
+                                                    </Button>
+                                                    <Button
+                                                        class={Some("border border-ink/50 bg-ink/40 text-secondary hover:bg-ink/50".to_string())}
+                                                        onclick={on_down}
+                                                        disabled={*mods_busy || index == last}
+                                                    >
+                                                        { "Down" }
+                                                    </Button>
+                                                    <Button
+                                                        class={Some(if entry.enabled {
+                                                            "border border-ink/50 bg-ink/40 text-secondary hover:bg-ink/50".to_string()
+                                                        } else {
+                                                            "border border-primary/60 bg-primary/30 text-secondary hover:bg-primary/40".to_string()
+                                                        })}
+                                                        onclick={on_toggle}
+                                                        disabled={*mods_busy}
+                                                    >
+                                                        { if entry.enabled { "Disable" } else { "Enable" } }
+                                                    </Button>
+                                                    <Button
+                                                        class={Some("border border-rose-400/60 bg-rose-500/20 text-rose-100 hover:bg-rose-500/30".to_string())}
+                                                        onclick={Callback::from(move |_| on_remove_mod.emit(mod_id.clone()))}
+                                                        disabled={*mods_busy}
+                                                    >
+                                                        { "Remove" }
+                                                    </Button>
+                                                </div>
+                                            </div>
+                                        }
+                                    }) }
+                                </div>
+                            }
+                            if !mod_overview.conflicts.is_empty() {
+                                <p class="mt-5 text-xs uppercase tracking-wide text-accent/80">{ "Conflicts" }</p>
+                                <div class="mt-2 flex flex-col gap-1">
+                                    { for mod_overview.conflicts.iter().map(|conflict| {
+                                        let name = |id: &String| {
+                                            mod_overview
+                                                .mods
+                                                .iter()
+                                                .find(|entry| &entry.id == id)
+                                                .map(|entry| entry.name.clone())
+                                                .unwrap_or_else(|| id.clone())
+                                        };
+                                        let winner = conflict.mods.last().map(name).unwrap_or_default();
+                                        let losers: Vec<String> = conflict.mods[..conflict.mods.len() - 1].iter().map(name).collect();
+                                        html! {
+                                            <p class="text-xs text-secondary/80">
+                                                <span class="font-mono">{ conflict.path.clone() }</span>
+                                                { format!(": {} overrides {}", winner, losers.join(", ")) }
+                                            </p>
+                                        }
+                                    }) }
+                                </div>
+                            }
+                            {{
+                                let available: Vec<AppInfo> = apps
+                                    .iter()
+                                    .filter(|other| other.base_app.as_deref() == Some(app.id.as_str()))
+                                    .filter(|other| !mod_overview.mods.iter().any(|entry| entry.id == other.id))
+                                    .cloned()
+                                    .collect();
+                                let is_busy = (*downloads)
+                                    .values()
+                                    .any(|d| d.status == "downloading" || d.status == "paused");
+                                html! {
+                                    if !available.is_empty() {
+                                        <p class="mt-5 text-xs uppercase tracking-wide text-accent/80">{ "Available" }</p>
+                                        <div class="mt-2 flex flex-col gap-2">
+                                            { for available.into_iter().map(|other| {
+                                                let on_download = on_download.clone();
+                                                let status = (*downloads).get(&other.id).map(|d| d.status.clone()).unwrap_or_default();
+                                                let label = match status.as_str() {
+                                                    "downloading" => "Downloading...",
+                                                    "installing" => "Installing...",
+                                                    "paused" => "Paused",
+                                                    _ if is_busy => "Busy",
+                                                    _ => "Download",
+                                                };
+                                                html! {
+                                                    <div key={other.id.clone()} class="flex items-center justify-between gap-3 rounded-xl border border-ink/50 bg-ink/40 px-4 py-3">
+                                                        <div class="min-w-0">
+                                                            <p class="text-sm font-semibold">{ other.name.clone() }</p>
+                                                            <p class="text-xs text-secondary/70">
+                                                                { format!("{} - {}", other.description, format_size(other.archive_size)) }
+                                                            </p>
+                                                        </div>
+                                                        <Button
+                                                            class={Some("border border-primary/60 bg-primary/30 text-secondary hover:bg-primary/40".to_string())}
+                                                            onclick={{
+                                                                let other = other.clone();
+                                                                Callback::from(move |_| on_download.emit((other.clone(), None)))
+                                                            }}
+                                                            disabled={!other.has_archive || is_busy}
+                                                        >
+                                                            { label }
+                                                        </Button>
+                                                    </div>
+                                                }
+                                            }) }
+                                        </div>
+                                    }
+                                }
+                            }}
+                        </div>
+                    </div>
+                </div>
+            }
+            if let Some(prompt) = (*save_prompt).clone() {
+                <div class="fixed inset-0 z-50 flex items-center justify-center">
+                    <div class="absolute inset-0 bg-ink" onclick={on_close_save_prompt.clone()} />
+                    <div class="relative w-[min(94vw,32rem)] overflow-hidden rounded-2xl border border-ink/50 bg-inkLight shadow-2xl">
+                        <div class="border-b border-ink/40 px-6 py-4">
+                            <h2 class="text-lg font-semibold text-secondary">
+                                {{
+                                    let name = apps
+                                        .iter()
+                                        .find(|app| app.id == prompt.id)
+                                        .map(|app| app.name.clone())
+                                        .unwrap_or_else(|| prompt.id.clone());
+                                    if prompt.conflict {
+                                        format!("{}: save conflict", name)
+                                    } else {
+                                        format!("{}: newer cloud save", name)
+                                    }
+                                }}
+                            </h2>
+                            <p class="text-xs text-accent">
+                                { if prompt.conflict {
+                                    "The save on this PC and the cloud save both changed since they were last synced. Pick the one to keep; the other is replaced."
+                                } else {
+                                    "The cloud save is newer than the one on this PC."
+                                } }
+                            </p>
+                        </div>
+                        <div class="px-6 py-5 text-sm text-secondary/80">
+                            {{
+                                let uploaded = js_sys::Date::new(&JsValue::from_f64(prompt.cloud.uploaded_at as f64 * 1000.0));
+                                let from = if prompt.cloud.device.is_empty() {
+                                    String::new()
+                                } else {
+                                    format!(" from {}", prompt.cloud.device)
+                                };
+                                format!(
+                                    "Cloud save{}, {} ({})",
+                                    from,
+                                    String::from(uploaded.to_locale_string("default", &JsValue::UNDEFINED)),
+                                    format_size(prompt.cloud.size)
+                                )
+                            }}
+                        </div>
+                        <div class="flex items-center justify-end gap-2 border-t border-ink/40 px-6 py-4">
+                            <Button
+                                class={Some("border border-ink/50 bg-ink/40 text-secondary hover:bg-ink/50".to_string())}
+                                onclick={on_close_save_prompt.clone()}
+                                disabled={*save_busy}
+                            >
+                                { "Cancel" }
+                            </Button>
+                            <Button
+                                class={Some("border border-ink/50 bg-ink/40 text-secondary hover:bg-ink/50".to_string())}
+                                onclick={on_keep_local_save.clone()}
+                                disabled={*save_busy}
+                            >
+                                { if prompt.conflict { "Keep this PC's save" } else { "Keep local save" } }
+                            </Button>
+                            <Button
+                                class={Some("border border-primary/60 bg-primary/30 text-secondary hover:bg-primary/40".to_string())}
+                                onclick={on_use_cloud_save.clone()}
+                                disabled={*save_busy}
+                            >
+                                { if *save_busy { "Working..." } else { "Use cloud save" } }
+                            </Button>
+                        </div>
+                    </div>
+                </div>
+            }
+            if let Some(app) = (*snapshots_for).clone() {
+                <div class="fixed inset-0 z-[60] flex items-center justify-center">
+                    <div class="absolute inset-0 bg-ink" onclick={on_close_snapshots.clone()} />
+                    <div class="relative w-[min(94vw,40rem)] max-h-[90vh] overflow-hidden rounded-2xl border border-ink/50 bg-inkLight shadow-2xl">
+                        <div class="flex items-center justify-between border-b border-ink/40 px-6 py-4">
+                            <div>
+                                <h2 class="text-lg font-semibold text-secondary">{ format!("Restore {} save", app.name) }</h2>
+                                <p class="text-xs text-accent">{ "Snapshots taken on this PC, newest first." }</p>
+                            </div>
+                            <Button
+                                class={Some("border border-ink/50 bg-ink/40 text-secondary hover:bg-ink/50".to_string())}
+                                onclick={on_close_snapshots.clone()}
+                            >
+                                { "Close" }
+                            </Button>
+                        </div>
+                        <div class="max-h-[calc(90vh-5rem)] overflow-y-auto px-6 py-5 scrollbar-thin">
+                            if *snapshots_loading {
+                                <p class="text-sm text-secondary/70">{ "Loading snapshots..." }</p>
+                            } else if snapshots.is_empty() {
+                                <p class="text-sm text-secondary/70">{ "No snapshots yet. One is taken before the app is updated or removed." }</p>
+                            } else {
+                                <div class="flex flex-col gap-3">
+                                    { for snapshots.iter().map(|snapshot| {
+                                        let on_restore_snapshot = on_restore_snapshot.clone();
+                                        let name = snapshot.name.clone();
+                                        let created = js_sys::Date::new(&JsValue::from_f64(snapshot.created_at as f64 * 1000.0));
+                                        let reason = match snapshot.reason.as_str() {
+                                            "update" => "Before install",
+                                            "uninstall" => "Before removal",
+                                            "restore" => "Before restore",
+                                            _ => "Snapshot",
+                                        };
+                                        html! {
+                                            <div key={snapshot.name.clone()} class="flex items-center justify-between gap-3 rounded-xl border border-ink/50 bg-ink/40 px-4 py-3">
+                                                <div>
+                                                    <p class="text-sm font-semibold">{ String::from(created.to_locale_string("default", &JsValue::UNDEFINED)) }</p>
+                                                    <p class="text-xs text-secondary/70">
+                                                        { format!("{} - {} files, {}", reason, snapshot.files, format_size(snapshot.size)) }
+                                                    </p>
+                                                </div>
+                                                <Button
+                                                    class={Some("border border-primary/60 bg-primary/30 text-secondary hover:bg-primary/40".to_string())}
+                                                    onclick={Callback::from(move |_| on_restore_snapshot.emit(name.clone()))}
+                                                >
+                                                    { "Restore" }
+                                                </Button>
+                                            </div>
+                                        }
+                                    }) }
+                                </div>
+                            }
+                        </div>
+                    </div>
+                </div>
+            }
+            if let Some(app) = (*versions_for).clone() {
+                <div class="fixed inset-0 z-50 flex items-center justify-center">
+                    <div class="absolute inset-0 bg-ink" onclick={on_close_versions.clone()} />
+                    <div class="relative w-[min(94vw,40rem)] max-h-[90vh] overflow-hidden rounded-2xl border border-ink/50 bg-inkLight shadow-2xl">
+                        <div class="flex items-center justify-between border-b border-ink/40 px-6 py-4">
+                            <div>
+                                <h2 class="text-lg font-semibold text-secondary">{ format!("Install {}", app.name) }</h2>
+                                <p class="text-xs text-accent">{ "Pick a version to install and keep." }</p>
+                            </div>
+                            <Button
+                                class={Some("border border-ink/50 bg-ink/40 text-secondary hover:bg-ink/50".to_string())}
+                                onclick={on_close_versions.clone()}
+                            >
+                                { "Close" }
+                            </Button>
+                        </div>
+                        <div class="max-h-[calc(90vh-5rem)] overflow-y-auto px-6 py-5 scrollbar-thin">
+                            if *versions_loading {
+                                <p class="text-sm text-secondary/70">{ "Loading versions..." }</p>
+                            } else if versions.is_empty() {
+                                <p class="text-sm text-secondary/70">{ "No versions available." }</p>
+                            } else {
+                                <div class="flex flex-col gap-3">
+                                    { for versions.iter().cloned().map(|version| {
+                                        let on_download = on_download.clone();
+                                        let versions_for = versions_for.clone();
+                                        let app = app.clone();
+                                        let uploaded = js_sys::Date::new(&JsValue::from_f64(version.uploaded_at as f64 * 1000.0));
+                                        html! {
+                                            <div key={version.id.clone()} class="flex items-center justify-between gap-3 rounded-xl border border-ink/50 bg-ink/40 px-4 py-3">
+                                                <div>
+                                                    <p class="text-sm font-semibold">
+                                                        { format!("Version {}", version_label(&version)) }
+                                                        if version.current {
+                                                            <span class="ml-2 text-xs text-accent">{ "latest" }</span>
+                                                        }
+                                                    </p>
+                                                    <p class="text-xs text-secondary/70">
+                                                        { format!("{} - {}", String::from(uploaded.to_locale_date_string("default", &JsValue::UNDEFINED)), format_size(version.size)) }
+                                                    </p>
+                                                </div>
+                                                <Button
+                                                    class={Some("border border-primary/60 bg-primary/30 text-secondary hover:bg-primary/40".to_string())}
+                                                    onclick={Callback::from(move |_| {
+                                                        on_download.emit((app.clone(), Some(version.clone())));
+                                                        versions_for.set(None);
+                                                    })}
+                                                >
+                                                    { "Install" }
+                                                </Button>
+                                            </div>
+                                        }
+                                    }) }
+                                </div>
+                            }
+                        </div>
+                    </div>
+                </div>
+            }
+            if let Some(pending) = (*pending_hooks).clone() {
+                <div class="fixed inset-0 z-50 flex items-center justify-center">
+                    <div class="absolute inset-0 bg-ink" onclick={on_cancel_hooks.clone()} />
+                    <div class="relative w-[min(94vw,40rem)] max-h-[90vh] overflow-hidden rounded-2xl border border-ink/50 bg-inkLight shadow-2xl">
+                        <div class="border-b border-ink/40 px-6 py-4">
+                            <h2 class="text-lg font-semibold text-secondary">{ format!("Install {}", pending.app.name) }</h2>
+                            <p class="text-xs text-accent">
+                                { "This app runs the commands below on your computer. Only continue if you trust its publisher." }
+                            </p>
+                        </div>
+                        <div class="max-h-[calc(90vh-10rem)] overflow-y-auto px-6 py-5 scrollbar-thin">
+                            <div class="flex flex-col gap-3">
+                                { for pending.hooks.iter().map(|hook| {
+                                    let mut command = vec![hook.command.clone()];
+                                    command.extend(hook.args.iter().cloned());
+                                    html! {
+                                        <div class="rounded-xl border border-ink/50 bg-ink/40 px-4 py-3">
+                                            <p class="text-sm font-semibold">{ hook.stage.clone() }</p>
+                                            <p class="mt-1 break-all font-mono text-xs text-secondary/80">{ command.join(" ") }</p>
+                                            <p class="mt-1 text-xs text-secondary/60">{ format!("Stopped after {}s", hook.timeout_seconds) }</p>
+                                        </div>
+                                    }
+                                }) }
+                            </div>
+                        </div>
+                        <div class="flex items-center justify-end gap-2 border-t border-ink/40 px-6 py-4">
+                            <Button
+                                class={Some("border border-ink/50 bg-ink/40 text-secondary hover:bg-ink/50".to_string())}
+                                onclick={on_cancel_hooks.clone()}
+                            >
+                                { "Cancel" }
+                            </Button>
+                            <Button
+                                class={Some("border border-primary/60 bg-primary/30 text-secondary hover:bg-primary/40".to_string())}
+                                onclick={on_approve_hooks.clone()}
+                            >
+                                { "Approve and install" }
+                            </Button>
+                        </div>
+                    </div>
+                </div>
+            }
         </div>
     }
 }
 
+/// GETs a plain-text resource, `None` on any failure.
+async fn fetch_text(url: &str, token: &str) -> Option<String> {
+    let resp = send_request("GET", url, Some(token), None).await.ok()?;
+    if !resp.ok() {
+        return None;
+    }
+    JsFuture::from(resp.text().ok()?).await.ok()?.as_string()
+}
+
 fn format_size(size: i64) -> String {
     if size <= 0 {
         return "-".to_string();
@@ -1226,6 +3362,149 @@ fn format_size(size: i64) -> String {
     format!("{:.1} {}", value, unit)
 }
 
+fn describe_exit(exit_code: Option<i32>, signal: Option<i32>) -> String {
+    match (exit_code, signal) {
+        (_, Some(signal)) => format!("signal {}", signal),
+        (Some(code), None) => format!("exit code {}", code),
+        (None, None) => "unknown exit status".to_string(),
+    }
+}
+
+/// What the run event knows about a crash besides `CrashInfo`.
+struct CrashReportRequest {
+    exit_code: Option<i32>,
+    profile: Option<String>,
+    duration_seconds: u64,
+}
+
+async fn send_crash_report(
+    server_ip: &str,
+    server_port: &str,
+    token: &str,
+    id: &str,
+    report: CrashReportRequest,
+    crash: &CrashInfo,
+) -> Result<(), String> {
+    if server_ip.trim().is_empty() || server_port.trim().is_empty() || token.trim().is_empty() {
+        return Err("Not connected to a server.".to_string());
+    }
+    let client_version = invoke("get_app_version", JsValue::NULL)
+        .await
+        .as_string()
+        .unwrap_or_default();
+    let url = build_http_url(
+        server_ip.trim(),
+        server_port.trim(),
+        &format!("apps/{}/crash-reports", id),
+    );
+    let body = serde_json::json!({
+        "app_version": crash.app_version,
+        "profile": report.profile.unwrap_or_default(),
+        "exit_code": report.exit_code,
+        "signal": crash.signal,
+        "duration_seconds": report.duration_seconds,
+        "os": crash.os,
+        "client_version": client_version,
+        "output": crash.output,
+    });
+    match send_json("POST", &url, Some(token.trim()), Some(body)).await {
+        Ok(resp) if resp.ok() => Ok(()),
+        _ => Err("Failed to send crash report.".to_string()),
+    }
+}
+
+fn load_library_cache(server_ip: &str) -> LibraryCache {
+    get_local_storage_item(&library_cache_key(server_ip))
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+/// Keeps the cached playtime when it couldn't be fetched this time.
+fn store_library_cache(
+    server_ip: &str,
+    apps: &[AppInfo],
+    playtime: Option<&HashMap<String, PlaytimeEntry>>,
+) {
+    let mut cache = load_library_cache(server_ip);
+    cache.apps = apps.to_vec();
+    if let Some(playtime) = playtime {
+        cache.playtime = playtime.values().cloned().collect();
+    }
+    if let Ok(raw) = serde_json::to_string(&cache) {
+        set_local_storage_item(&library_cache_key(server_ip), &raw);
+    }
+}
+
+/// The installed apps, with their cached details. One installed before the
+/// cache existed only has its id to go by.
+fn offline_apps(cached: Vec<AppInfo>, installed: &HashSet<String>) -> Vec<AppInfo> {
+    let mut apps: Vec<AppInfo> = cached
+        .into_iter()
+        .filter(|app| installed.contains(&app.id))
+        .collect();
+    for id in installed {
+        if !apps.iter().any(|app| &app.id == id) {
+            apps.push(AppInfo {
+                id: id.clone(),
+                name: id.clone(),
+                description: String::new(),
+                version: String::new(),
+                archive_size: 0,
+                has_archive: false,
+                executable: None,
+                launch_profiles: Vec::new(),
+                base_app: None,
+            });
+        }
+    }
+    apps
+}
+
+/// Uploads the playtime sessions waiting in the client's local journal.
+async fn sync_playtime(server_ip: &str, server_port: &str, token: &str) -> Result<(), String> {
+    let payload = serde_wasm_bindgen::to_value(&serde_json::json!({
+        "request": {
+            "serverIp": server_ip,
+            "serverPort": server_port,
+            "token": token
+        }
+    }))
+    .unwrap_or(JsValue::NULL);
+    invoke_safe("sync_playtime", payload)
+        .await
+        .map(|_| ())
+        .map_err(|err| {
+            err.as_string()
+                .unwrap_or_else(|| "Playtime upload failed.".to_string())
+        })
+}
+
+async fn fetch_playtime(
+    server_ip: &str,
+    server_port: &str,
+    token: &str,
+) -> Option<HashMap<String, PlaytimeEntry>> {
+    let url = build_http_url(server_ip, server_port, "apps/playtime");
+    let list = get_json::<Vec<PlaytimeEntry>>(&url, Some(token))
+        .await
+        .ok()?;
+    Some(
+        list.into_iter()
+            .map(|entry| (entry.app_id.clone(), entry))
+            .collect(),
+    )
+}
+
+fn format_elapsed(seconds: f64) -> String {
+    let total = seconds.max(0.0) as u64;
+    let (hours, minutes, secs) = (total / 3600, total / 60 % 60, total % 60);
+    if hours > 0 {
+        format!("{}h {:02}m", hours, minutes)
+    } else {
+        format!("{}m {:02}s", minutes, secs)
+    }
+}
+
 fn format_playtime(total_seconds: i64) -> String {
     if total_seconds <= 0 {
         return "Not played yet".to_string();
@@ -1241,3 +3520,113 @@ fn format_playtime(total_seconds: i64) -> String {
     }
     format!("{}h", hours)
 }
+
+fn version_label(version: &AppVersion) -> String {
+    if version.version.trim().is_empty() {
+        version.id.clone()
+    } else {
+        version.version.clone()
+    }
+}
+
+fn load_pinned_versions() -> HashMap<String, String> {
+    get_local_storage_item(PINNED_VERSIONS_KEY)
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn save_pinned_versions(pinned: &HashMap<String, String>) {
+    if let Ok(raw) = serde_json::to_string(pinned) {
+        set_local_storage_item(PINNED_VERSIONS_KEY, &raw);
+    }
+}
+
+async fn list_app_logs(id: &str, install_dir: &str) -> Vec<LogEntry> {
+    let payload = serde_wasm_bindgen::to_value(&serde_json::json!({
+        "request": { "id": id, "destDir": install_dir }
+    }))
+    .unwrap_or(JsValue::NULL);
+    match invoke_safe("list_app_logs", payload).await {
+        Ok(value) => serde_wasm_bindgen::from_value(value).unwrap_or_default(),
+        Err(_) => Vec::new(),
+    }
+}
+
+async fn list_app_mods(id: &str, install_dir: &str) -> ModOverview {
+    let payload = serde_wasm_bindgen::to_value(&serde_json::json!({
+        "request": { "id": id, "destDir": install_dir }
+    }))
+    .unwrap_or(JsValue::NULL);
+    match invoke_safe("list_app_mods", payload).await {
+        Ok(value) => serde_wasm_bindgen::from_value(value).unwrap_or_default(),
+        Err(_) => ModOverview::default(),
+    }
+}
+
+async fn list_save_snapshots(id: &str) -> Vec<SaveSnapshot> {
+    let payload = serde_wasm_bindgen::to_value(&serde_json::json!({
+        "request": { "id": id }
+    }))
+    .unwrap_or(JsValue::NULL);
+    match invoke_safe("list_save_snapshots", payload).await {
+        Ok(value) => serde_wasm_bindgen::from_value(value).unwrap_or_default(),
+        Err(_) => Vec::new(),
+    }
+}
+
+async fn read_app_log(id: &str, install_dir: &str, name: &str) -> Result<String, String> {
+    let payload = serde_wasm_bindgen::to_value(&serde_json::json!({
+        "request": { "id": id, "destDir": install_dir, "name": name }
+    }))
+    .unwrap_or(JsValue::NULL);
+    match invoke_safe("read_app_log", payload).await {
+        Ok(value) => Ok(value.as_string().unwrap_or_default()),
+        Err(err) => Err(err
+            .as_string()
+            .unwrap_or_else(|| "Failed to read log.".to_string())),
+    }
+}
+
+async fn copy_to_clipboard(text: &str) -> Result<(), ()> {
+    let window = web_sys::window().ok_or(())?;
+    let promise = window.navigator().clipboard().write_text(text);
+    JsFuture::from(promise).await.map(|_| ()).map_err(|_| ())
+}
+
+fn parse_env_lines(raw: &str) -> Result<BTreeMap<String, String>, String> {
+    let mut env = BTreeMap::new();
+    for line in raw.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match line.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => {
+                env.insert(key.trim().to_string(), value.to_string());
+            }
+            _ => return Err(format!("\"{}\" is not in NAME=value form.", line)),
+        }
+    }
+    Ok(env)
+}
+
+fn on_input_value(state: UseStateHandle<String>) -> Callback<InputEvent> {
+    Callback::from(move |event: InputEvent| {
+        let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+        state.set(input.value());
+    })
+}
+
+fn on_textarea_value(state: UseStateHandle<String>) -> Callback<InputEvent> {
+    Callback::from(move |event: InputEvent| {
+        let input: web_sys::HtmlTextAreaElement = event.target_unchecked_into();
+        state.set(input.value());
+    })
+}
+
+fn on_select_value(state: UseStateHandle<String>) -> Callback<Event> {
+    Callback::from(move |event: Event| {
+        let input: web_sys::HtmlSelectElement = event.target_unchecked_into();
+        state.set(input.value());
+    })
+}
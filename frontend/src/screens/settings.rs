@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use yew::prelude::*;
 
 use crate::app::{apply_theme, fetch_theme};
@@ -17,6 +18,19 @@ pub struct SettingsScreenProps {
 extern "C" {
     #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"])]
     async fn invoke(cmd: &str, args: JsValue) -> JsValue;
+
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"], js_name = invoke, catch)]
+    async fn invoke_safe(cmd: &str, args: JsValue) -> Result<JsValue, JsValue>;
+}
+
+#[derive(Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NetworkSettings {
+    proxy: String,
+    connect_timeout_seconds: Option<u64>,
+    read_timeout_seconds: Option<u64>,
+    user_agent: String,
+    ca_certificate: String,
 }
 
 #[function_component(SettingsScreen)]
@@ -29,6 +43,12 @@ pub fn settings_screen(props: &SettingsScreenProps) -> Html {
     let updating = use_state(|| false);
     let app_version = use_state(|| None::<String>);
     let install_dir = use_state(|| get_local_storage_item(INSTALL_DIR_KEY).unwrap_or_default());
+    let net_proxy = use_state(String::new);
+    let net_connect_timeout = use_state(String::new);
+    let net_read_timeout = use_state(String::new);
+    let net_user_agent = use_state(String::new);
+    let net_ca = use_state(String::new);
+    let net_saving = use_state(|| false);
 
     {
         let install_dir = install_dir.clone();
@@ -69,6 +89,101 @@ pub fn settings_screen(props: &SettingsScreenProps) -> Html {
         });
     }
 
+    {
+        let server_ip = server_ip.clone().unwrap_or_default();
+        let server_port = server_port.clone().unwrap_or_default();
+        let net_proxy = net_proxy.clone();
+        let net_connect_timeout = net_connect_timeout.clone();
+        let net_read_timeout = net_read_timeout.clone();
+        let net_user_agent = net_user_agent.clone();
+        let net_ca = net_ca.clone();
+        use_effect_with((), move |_| {
+            spawn_local(async move {
+                let payload = serde_wasm_bindgen::to_value(&serde_json::json!({
+                    "request": { "serverIp": server_ip, "serverPort": server_port }
+                }))
+                .unwrap_or(JsValue::NULL);
+                let result = invoke("get_network_settings", payload).await;
+                if let Ok(settings) = serde_wasm_bindgen::from_value::<NetworkSettings>(result) {
+                    let seconds =
+                        |value: Option<u64>| value.map(|v| v.to_string()).unwrap_or_default();
+                    net_proxy.set(settings.proxy);
+                    net_connect_timeout.set(seconds(settings.connect_timeout_seconds));
+                    net_read_timeout.set(seconds(settings.read_timeout_seconds));
+                    net_user_agent.set(settings.user_agent);
+                    net_ca.set(settings.ca_certificate);
+                }
+            });
+            || ()
+        });
+    }
+
+    let on_save_network = {
+        let server_ip = server_ip.clone().unwrap_or_default();
+        let server_port = server_port.clone().unwrap_or_default();
+        let net_proxy = net_proxy.clone();
+        let net_connect_timeout = net_connect_timeout.clone();
+        let net_read_timeout = net_read_timeout.clone();
+        let net_user_agent = net_user_agent.clone();
+        let net_ca = net_ca.clone();
+        let net_saving = net_saving.clone();
+        let toast = toast.clone();
+        Callback::from(move |_| {
+            let seconds = |value: &str, label: &str| -> Result<Option<u64>, String> {
+                let value = value.trim();
+                if value.is_empty() {
+                    return Ok(None);
+                }
+                value
+                    .parse::<u64>()
+                    .map(Some)
+                    .map_err(|_| format!("{} must be a whole number of seconds.", label))
+            };
+            let timeouts = seconds(&net_connect_timeout, "Connect timeout").and_then(|connect| {
+                seconds(&net_read_timeout, "Read timeout").map(|read| (connect, read))
+            });
+            let (connect_timeout_seconds, read_timeout_seconds) = match timeouts {
+                Ok(timeouts) => timeouts,
+                Err(message) => {
+                    toast.toast(message, ToastVariant::Warning, Some(3000));
+                    return;
+                }
+            };
+            let settings = NetworkSettings {
+                proxy: (*net_proxy).clone(),
+                connect_timeout_seconds,
+                read_timeout_seconds,
+                user_agent: (*net_user_agent).clone(),
+                ca_certificate: (*net_ca).clone(),
+            };
+            let payload = serde_wasm_bindgen::to_value(&serde_json::json!({
+                "request": {
+                    "serverIp": server_ip.clone(),
+                    "serverPort": server_port.clone(),
+                    "settings": settings
+                }
+            }))
+            .unwrap_or(JsValue::NULL);
+            net_saving.set(true);
+            let net_saving = net_saving.clone();
+            let toast = toast.clone();
+            spawn_local(async move {
+                match invoke_safe("save_network_settings", payload).await {
+                    Ok(_) => {
+                        toast.toast("Network settings saved.", ToastVariant::Success, Some(2000))
+                    }
+                    Err(err) => {
+                        let message = err
+                            .as_string()
+                            .unwrap_or_else(|| "Failed to save network settings.".to_string());
+                        toast.toast(message, ToastVariant::Error, Some(3000));
+                    }
+                }
+                net_saving.set(false);
+            });
+        })
+    };
+
     let on_reload_theme = {
         let server_ip = server_ip.clone();
         let server_port = server_port.clone();
@@ -201,6 +316,79 @@ pub fn settings_screen(props: &SettingsScreenProps) -> Html {
                     oninput={on_install_dir_input}
                 />
             </div>
+            <div class="mt-6 rounded-2xl border border-ink/50 bg-inkLight p-6">
+                <div class="flex items-center justify-between gap-4">
+                    <div>
+                        <h2 class="text-sm font-semibold">{ "Network" }</h2>
+                        <p class="mt-2 text-sm text-secondary/70">
+                            { "Proxy, timeouts and certificates for downloads, uploads and cloud saves." }
+                        </p>
+                    </div>
+                    <Button
+                        class={Some("border border-accent/50 bg-accent/20 text-secondary hover:bg-accent/30".to_string())}
+                        onclick={on_save_network}
+                        disabled={*net_saving}
+                    >
+                        { if *net_saving { "Saving..." } else { "Save" } }
+                    </Button>
+                </div>
+                <div class="mt-4 grid gap-4 md:grid-cols-2">
+                    <div class="md:col-span-2">
+                        <label class="text-xs uppercase tracking-wide text-accent/80">{ "Proxy" }</label>
+                        <input
+                            class="mt-2 w-full rounded border border-ink/50 bg-ink/40 px-3 py-2 text-secondary placeholder:text-secondary/60 outline outline-1 outline-accent/50 focus:outline-none focus:ring-2 focus:ring-primary/40"
+                            type="text"
+                            placeholder="http://proxy:3128 or socks5://proxy:1080, empty uses the system setting"
+                            value={(*net_proxy).clone()}
+                            oninput={on_input_value(net_proxy.clone())}
+                        />
+                    </div>
+                    <div>
+                        <label class="text-xs uppercase tracking-wide text-accent/80">{ "Connect timeout (seconds)" }</label>
+                        <input
+                            class="mt-2 w-full rounded border border-ink/50 bg-ink/40 px-3 py-2 text-secondary placeholder:text-secondary/60 outline outline-1 outline-accent/50 focus:outline-none focus:ring-2 focus:ring-primary/40"
+                            type="number"
+                            min="1"
+                            placeholder="15"
+                            value={(*net_connect_timeout).clone()}
+                            oninput={on_input_value(net_connect_timeout.clone())}
+                        />
+                    </div>
+                    <div>
+                        <label class="text-xs uppercase tracking-wide text-accent/80">{ "Read timeout (seconds)" }</label>
+                        <input
+                            class="mt-2 w-full rounded border border-ink/50 bg-ink/40 px-3 py-2 text-secondary placeholder:text-secondary/60 outline outline-1 outline-accent/50 focus:outline-none focus:ring-2 focus:ring-primary/40"
+                            type="number"
+                            min="1"
+                            placeholder="60"
+                            value={(*net_read_timeout).clone()}
+                            oninput={on_input_value(net_read_timeout.clone())}
+                        />
+                    </div>
+                    <div class="md:col-span-2">
+                        <label class="text-xs uppercase tracking-wide text-accent/80">{ "User agent" }</label>
+                        <input
+                            class="mt-2 w-full rounded border border-ink/50 bg-ink/40 px-3 py-2 text-secondary placeholder:text-secondary/60 outline outline-1 outline-accent/50 focus:outline-none focus:ring-2 focus:ring-primary/40"
+                            type="text"
+                            placeholder="Gaggle/<version>"
+                            value={(*net_user_agent).clone()}
+                            oninput={on_input_value(net_user_agent.clone())}
+                        />
+                    </div>
+                    <div class="md:col-span-2">
+                        <label class="text-xs uppercase tracking-wide text-accent/80">{ "Trusted CA for this server" }</label>
+                        <textarea
+                            class="mt-2 w-full rounded border border-ink/50 bg-ink/40 px-3 py-2 text-secondary placeholder:text-secondary/60 outline outline-1 outline-accent/50 focus:outline-none focus:ring-2 focus:ring-primary/40 h-28 resize-none font-mono text-xs"
+                            placeholder="-----BEGIN CERTIFICATE-----"
+                            value={(*net_ca).clone()}
+                            oninput={on_textarea_value(net_ca.clone())}
+                        />
+                        <p class="mt-2 text-xs text-secondary/60">
+                            { "PEM certificate of a private CA or a self-signed server, trusted on top of the system ones." }
+                        </p>
+                    </div>
+                </div>
+            </div>
             <div class="mt-8 rounded-2xl border border-ink/50 bg-inkLight p-6">
                 <div class="flex items-center justify-between">
                     <div>
@@ -278,3 +466,17 @@ pub fn settings_screen(props: &SettingsScreenProps) -> Html {
         </div>
     }
 }
+
+fn on_input_value(state: UseStateHandle<String>) -> Callback<InputEvent> {
+    Callback::from(move |event: InputEvent| {
+        let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+        state.set(input.value());
+    })
+}
+
+fn on_textarea_value(state: UseStateHandle<String>) -> Callback<InputEvent> {
+    Callback::from(move |event: InputEvent| {
+        let input: web_sys::HtmlTextAreaElement = event.target_unchecked_into();
+        state.set(input.value());
+    })
+}
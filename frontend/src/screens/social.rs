@@ -3,11 +3,13 @@ use serde_json;
 use serde_wasm_bindgen;
 use wasm_bindgen::{closure::Closure, JsCast, JsValue};
 use wasm_bindgen_futures::spawn_local;
-use web_sys::{BinaryType, Blob, MessageEvent, WebSocket};
+use web_sys::{BinaryType, Blob, MessageEvent};
 use yew::prelude::*;
 
-use crate::app::AppState;
-use crate::net::build_ws_url;
+use crate::app::{recheck_session, AppState};
+use crate::auth::open_socket;
+use crate::net::DEFAULT_PORT;
+use crate::toast::use_toast;
 
 #[derive(Clone, PartialEq, serde::Deserialize)]
 struct SocialUser {
@@ -36,8 +38,9 @@ pub fn social_screen() -> Html {
     let server_port = app_state
         .server_port
         .clone()
-        .unwrap_or_else(|| "2121".to_string());
+        .unwrap_or_else(|| DEFAULT_PORT.to_string());
     let token = app_state.session_token.clone().unwrap_or_default();
+    let toast = use_toast();
 
     let users = use_state(Vec::<SocialUser>::new);
     let error = use_state(|| None::<String>);
@@ -50,6 +53,8 @@ pub fn social_screen() -> Html {
         let server_ip = server_ip.clone();
         let server_port = server_port.clone();
         let token = token.clone();
+        let app_state = app_state.clone();
+        let toast = toast.clone();
 
         use_effect_with(
             (server_ip.clone(), server_port.clone(), token.clone()),
@@ -59,12 +64,18 @@ pub fn social_screen() -> Html {
                     return Box::new(|| ()) as Box<dyn FnOnce()>;
                 }
 
-                let ws_url = build_ws_url(
+                let on_unauthorized = {
+                    let app_state = app_state.clone();
+                    let toast = toast.clone();
+                    Callback::from(move |_| recheck_session(app_state.clone(), toast.clone()))
+                };
+                let ws = match open_socket(
                     &server_ip,
                     &server_port,
-                    &format!("social/ws?token={token}"),
-                );
-                let ws = match WebSocket::new(&ws_url) {
+                    "social/ws",
+                    &token,
+                    on_unauthorized,
+                ) {
                     Ok(ws) => ws,
                     Err(_) => {
                         error.set(Some("Failed to open social socket.".to_string()));
@@ -17,6 +17,14 @@ pub struct ToastRequest {
     pub message: String,
     pub variant: ToastVariant,
     pub timeout_ms: Option<u32>,
+    pub action: Option<ToastAction>,
+}
+
+/// A button shown under the toast message; clicking it also dismisses the toast.
+#[derive(Clone, PartialEq)]
+pub struct ToastAction {
+    pub label: String,
+    pub on_click: Callback<()>,
 }
 
 #[derive(Clone, PartialEq)]
@@ -35,6 +43,22 @@ impl ToastContext {
             message: message.into(),
             variant,
             timeout_ms,
+            action: None,
+        });
+    }
+
+    pub fn toast_with_action(
+        &self,
+        message: impl Into<String>,
+        variant: ToastVariant,
+        timeout_ms: Option<u32>,
+        action: ToastAction,
+    ) {
+        self.push.emit(ToastRequest {
+            message: message.into(),
+            variant,
+            timeout_ms,
+            action: Some(action),
         });
     }
 }
@@ -45,6 +69,7 @@ struct Toast {
     message: String,
     variant: ToastVariant,
     timeout_ms: Option<u32>,
+    action: Option<ToastAction>,
 }
 
 #[derive(Properties, PartialEq)]
@@ -72,6 +97,7 @@ pub fn toast_provider(props: &ToastProviderProps) -> Html {
                 message: req.message,
                 variant: req.variant,
                 timeout_ms: req.timeout_ms,
+                action: req.action,
             };
             let mut next = (*toasts).clone();
             next.push(toast.clone());
@@ -116,6 +142,24 @@ fn toast_viewport(props: &ToastViewportProps) -> Html {
                     let id = toast.id;
                     Callback::from(move |_| on_dismiss.emit(id))
                 };
+                let action = toast.action.clone().map(|action| {
+                    let on_dismiss = props.on_dismiss.clone();
+                    let id = toast.id;
+                    let on_click = action.on_click.clone();
+                    let onclick = Callback::from(move |_| {
+                        on_click.emit(());
+                        on_dismiss.emit(id);
+                    });
+                    html! {
+                        <button
+                            class="mt-2 text-sm font-semibold text-primary transition hover:text-primary/80"
+                            type="button"
+                            onclick={onclick}
+                        >
+                            { action.label }
+                        </button>
+                    }
+                });
                 html! {
                     <div class={toast_classes(&toast.variant)}>
                         <div class="flex items-start justify-between gap-4">
@@ -128,6 +172,7 @@ fn toast_viewport(props: &ToastViewportProps) -> Html {
                                     <p class="mt-1 text-sm text-secondary/80">
                                         { toast.message.clone() }
                                     </p>
+                                    { for action }
                                 </div>
                             </div>
                             <button